@@ -11,10 +11,19 @@ use revm_primitives::Bytes;
 ///
 /// * `result` - The result of the interpreter's execution, including output data and gas usage.
 /// * `memory_offset` - The range in memory where the output data is located.
+/// * `gas_forwarded` - Gas given to the sub-calls and creates of the frame.
+/// * `gas_returned` - Gas returned to the frame by its sub-calls and creates.
+///
+/// Gas spent by the frame itself, excluding the gas spent by its sub-calls, is
+/// `result.gas.spend() + gas_returned - gas_forwarded`. Call stipend of value transfers is
+/// not paid by the frame and is not included in the forwarded gas, so gas spent by all frames
+/// adds up to the gas spent by the transaction frame plus the stipends.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CallOutcome {
     pub result: InterpreterResult,
     pub memory_offset: Range<usize>,
+    pub gas_forwarded: u64,
+    pub gas_returned: u64,
 }
 
 impl CallOutcome {
@@ -30,6 +39,8 @@ impl CallOutcome {
         Self {
             result,
             memory_offset,
+            gas_forwarded: 0,
+            gas_returned: 0,
         }
     }
 
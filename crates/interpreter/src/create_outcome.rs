@@ -11,6 +11,8 @@ pub struct CreateOutcome {
     pub result: InterpreterResult,
     // An optional address associated with the create operation.
     pub address: Option<Address>,
+    // Runtime code deployed to the created address, set only if the create succeeded.
+    pub deployed_code: Option<Bytes>,
 }
 
 impl CreateOutcome {
@@ -25,7 +27,16 @@ impl CreateOutcome {
     ///
     /// A new `CreateOutcome` instance.
     pub fn new(result: InterpreterResult, address: Option<Address>) -> Self {
-        Self { result, address }
+        Self {
+            result,
+            address,
+            deployed_code: None,
+        }
+    }
+
+    /// Returns the runtime code deployed by the successful create.
+    pub fn deployed_code(&self) -> Option<&Bytes> {
+        self.deployed_code.as_ref()
     }
 
     /// Retrieves a reference to the `InstructionResult` from the `InterpreterResult`.
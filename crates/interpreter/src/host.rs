@@ -19,6 +19,16 @@ pub trait Host {
     /// Returns (is_cold, is_new_account)
     fn load_account(&mut self, address: Address) -> Option<(bool, bool)>;
 
+    /// Returns custom account access gas cost of a call to `address`.
+    ///
+    /// If `None` is returned, the mainnet cost is used. Default implementation returns `None`.
+    #[inline]
+    fn call_access_gas(&mut self, address: Address, is_cold: bool) -> Option<u64> {
+        let _ = address;
+        let _ = is_cold;
+        None
+    }
+
     /// Get the block hash of the given block `number`.
     fn block_hash(&mut self, number: U256) -> Option<B256>;
 
@@ -53,6 +63,25 @@ pub trait Host {
     /// Emit a log owned by `address` with given `LogData`.
     fn log(&mut self, log: Log);
 
+    /// Returns number of logs emitted in the current transaction, including reverted ones.
+    ///
+    /// Default implementation returns zero, logs are not limited by `max_logs`.
+    #[inline]
+    fn log_count(&self) -> usize {
+        0
+    }
+
+    /// Records `len` bytes returned by RETURN or REVERT.
+    ///
+    /// Returns `false` if data returned in the current transaction exceeds the limit.
+    ///
+    /// Default implementation does not limit the returned data.
+    #[inline]
+    fn record_return_data(&mut self, len: usize) -> bool {
+        let _ = len;
+        true
+    }
+
     /// Mark `address` to be deleted, with funds transferred to `target`.
     fn selfdestruct(&mut self, address: Address, target: Address) -> Option<SelfDestructResult>;
 }
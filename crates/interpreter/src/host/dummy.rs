@@ -48,6 +48,11 @@ impl Host for DummyHost {
         Some((true, true))
     }
 
+    #[inline]
+    fn call_access_gas(&mut self, _address: Address, _is_cold: bool) -> Option<u64> {
+        None
+    }
+
     #[inline]
     fn block_hash(&mut self, _number: U256) -> Option<B256> {
         Some(B256::ZERO)
@@ -115,6 +120,16 @@ impl Host for DummyHost {
         self.log.push(log)
     }
 
+    #[inline]
+    fn log_count(&self) -> usize {
+        self.log.len()
+    }
+
+    #[inline]
+    fn record_return_data(&mut self, _len: usize) -> bool {
+        true
+    }
+
     #[inline]
     fn selfdestruct(&mut self, _address: Address, _target: Address) -> Option<SelfDestructResult> {
         panic!("Selfdestruct is not supported for this host")
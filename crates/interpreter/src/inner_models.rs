@@ -14,6 +14,11 @@ pub struct CallInputs {
     /// The call data of the call.
     pub input: Bytes,
     /// The gas limit of the call.
+    ///
+    /// For calls made by CALL-like opcodes this is the gas forwarded to the callee: gas
+    /// requested on the stack capped to all but one 64th of the gas left after the call cost
+    /// is charged (EIP-150), plus the call stipend if value is transferred.
+    /// For the transaction call it is the transaction gas limit minus the initial gas.
     pub gas_limit: u64,
     /// The context of the call.
     pub context: CallContext,
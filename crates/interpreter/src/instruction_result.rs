@@ -15,6 +15,8 @@ pub enum InstructionResult {
     Revert = 0x10, // revert opcode
     CallTooDeep,
     OutOfFunds,
+    /// Create nesting depth exceeded the configured limit.
+    CreateDepthLimit,
 
     // Actions
     CallOrCreate = 0x20,
@@ -44,6 +46,18 @@ pub enum InstructionResult {
     CreateContractStartingWithEF,
     /// EIP-3860: Limit and meter initcode. Initcode size limit exceeded.
     CreateInitCodeSizeLimit,
+    /// Number of emitted logs exceeded the configured limit.
+    LogLimitReached,
+    /// Contract creation is disabled.
+    CreateNotAllowed,
+    /// Execution was interrupted, for example by an inspector.
+    Interrupted,
+    /// Journal checkpoint depth exceeded the configured limit.
+    JournalDepthLimit,
+    /// Data returned by RETURN and REVERT in the transaction exceeded the configured limit.
+    ReturnDataLimit,
+    /// Single opcode charged more gas than the inspector allows, see `Inspector::gas_bomb`.
+    GasBomb,
 
     /// Fatal external error. Returned by database.
     FatalExternalError,
@@ -82,6 +96,13 @@ impl From<HaltReason> for InstructionResult {
             HaltReason::CreateContractSizeLimit => Self::CreateContractSizeLimit,
             HaltReason::CreateContractStartingWithEF => Self::CreateContractStartingWithEF,
             HaltReason::CreateInitCodeSizeLimit => Self::CreateInitCodeSizeLimit,
+            HaltReason::LogLimitReached => Self::LogLimitReached,
+            HaltReason::CreateNotAllowed => Self::CreateNotAllowed,
+            HaltReason::Interrupted => Self::Interrupted,
+            HaltReason::CreateDepthLimit => Self::CreateDepthLimit,
+            HaltReason::JournalDepthLimit => Self::JournalDepthLimit,
+            HaltReason::ReturnDataLimit => Self::ReturnDataLimit,
+            HaltReason::GasBomb => Self::GasBomb,
             HaltReason::OverflowPayment => Self::OverflowPayment,
             HaltReason::StateChangeDuringStaticCall => Self::StateChangeDuringStaticCall,
             HaltReason::CallNotAllowedInsideStatic => Self::CallNotAllowedInsideStatic,
@@ -106,7 +127,10 @@ macro_rules! return_ok {
 #[macro_export]
 macro_rules! return_revert {
     () => {
-        InstructionResult::Revert | InstructionResult::CallTooDeep | InstructionResult::OutOfFunds
+        InstructionResult::Revert
+            | InstructionResult::CallTooDeep
+            | InstructionResult::OutOfFunds
+            | InstructionResult::CreateDepthLimit
     };
 }
 
@@ -134,6 +158,12 @@ macro_rules! return_error {
             | InstructionResult::CreateContractSizeLimit
             | InstructionResult::CreateContractStartingWithEF
             | InstructionResult::CreateInitCodeSizeLimit
+            | InstructionResult::LogLimitReached
+            | InstructionResult::CreateNotAllowed
+            | InstructionResult::Interrupted
+            | InstructionResult::JournalDepthLimit
+            | InstructionResult::ReturnDataLimit
+            | InstructionResult::GasBomb
             | InstructionResult::FatalExternalError
     };
 }
@@ -247,13 +277,22 @@ impl From<InstructionResult> for SuccessOrHalt {
             InstructionResult::OverflowPayment => Self::Halt(HaltReason::OverflowPayment), // Check for first call is done separately.
             InstructionResult::PrecompileError => Self::Halt(HaltReason::PrecompileError),
             InstructionResult::NonceOverflow => Self::Halt(HaltReason::NonceOverflow),
-            InstructionResult::CreateContractSizeLimit
-            | InstructionResult::CreateContractStartingWithEF => {
+            InstructionResult::CreateContractSizeLimit => {
                 Self::Halt(HaltReason::CreateContractSizeLimit)
             }
+            InstructionResult::CreateContractStartingWithEF => {
+                Self::Halt(HaltReason::CreateContractStartingWithEF)
+            }
             InstructionResult::CreateInitCodeSizeLimit => {
                 Self::Halt(HaltReason::CreateInitCodeSizeLimit)
             }
+            InstructionResult::LogLimitReached => Self::Halt(HaltReason::LogLimitReached),
+            InstructionResult::CreateNotAllowed => Self::Halt(HaltReason::CreateNotAllowed),
+            InstructionResult::Interrupted => Self::Halt(HaltReason::Interrupted),
+            InstructionResult::CreateDepthLimit => Self::Halt(HaltReason::CreateDepthLimit),
+            InstructionResult::JournalDepthLimit => Self::Halt(HaltReason::JournalDepthLimit),
+            InstructionResult::ReturnDataLimit => Self::Halt(HaltReason::ReturnDataLimit),
+            InstructionResult::GasBomb => Self::Halt(HaltReason::GasBomb),
             InstructionResult::FatalExternalError => Self::FatalExternalError,
         }
     }
@@ -261,7 +300,10 @@ impl From<InstructionResult> for SuccessOrHalt {
 
 #[cfg(test)]
 mod tests {
-    use crate::InstructionResult;
+    use crate::{
+        primitives::{HaltReason, OutOfGasError},
+        InstructionResult, SuccessOrHalt,
+    };
 
     #[test]
     fn all_results_are_covered() {
@@ -292,6 +334,7 @@ mod tests {
             InstructionResult::Revert,
             InstructionResult::CallTooDeep,
             InstructionResult::OutOfFunds,
+            InstructionResult::CreateDepthLimit,
         ];
 
         for result in revert_results {
@@ -322,6 +365,12 @@ mod tests {
             InstructionResult::CreateContractSizeLimit,
             InstructionResult::CreateContractStartingWithEF,
             InstructionResult::CreateInitCodeSizeLimit,
+            InstructionResult::LogLimitReached,
+            InstructionResult::CreateNotAllowed,
+            InstructionResult::Interrupted,
+            InstructionResult::JournalDepthLimit,
+            InstructionResult::ReturnDataLimit,
+            InstructionResult::GasBomb,
             InstructionResult::FatalExternalError,
         ];
 
@@ -331,4 +380,45 @@ mod tests {
             assert!(result.is_error());
         }
     }
+
+    #[test]
+    fn test_halt_reason_roundtrip() {
+        let halt_reasons = vec![
+            HaltReason::OutOfGas(OutOfGasError::Basic),
+            HaltReason::OutOfGas(OutOfGasError::MemoryLimit),
+            HaltReason::OutOfGas(OutOfGasError::Memory),
+            HaltReason::OutOfGas(OutOfGasError::Precompile),
+            HaltReason::OutOfGas(OutOfGasError::InvalidOperand),
+            HaltReason::OpcodeNotFound,
+            HaltReason::InvalidFEOpcode,
+            HaltReason::InvalidJump,
+            HaltReason::NotActivated,
+            HaltReason::StackUnderflow,
+            HaltReason::StackOverflow,
+            HaltReason::OutOfOffset,
+            HaltReason::CreateCollision,
+            HaltReason::PrecompileError,
+            HaltReason::NonceOverflow,
+            HaltReason::CreateContractSizeLimit,
+            HaltReason::CreateContractStartingWithEF,
+            HaltReason::CreateInitCodeSizeLimit,
+            HaltReason::LogLimitReached,
+            HaltReason::CreateNotAllowed,
+            HaltReason::Interrupted,
+            HaltReason::CreateDepthLimit,
+            HaltReason::JournalDepthLimit,
+            HaltReason::ReturnDataLimit,
+            HaltReason::GasBomb,
+            HaltReason::OverflowPayment,
+            HaltReason::StateChangeDuringStaticCall,
+            HaltReason::CallNotAllowedInsideStatic,
+            HaltReason::OutOfFunds,
+            HaltReason::CallTooDeep,
+        ];
+
+        for reason in halt_reasons {
+            let result = InstructionResult::from(reason);
+            assert_eq!(SuccessOrHalt::from(result), SuccessOrHalt::Halt(reason));
+        }
+    }
 }
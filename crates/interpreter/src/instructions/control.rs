@@ -47,7 +47,11 @@ pub fn pc<H: Host>(interpreter: &mut Interpreter, _host: &mut H) {
 }
 
 #[inline(always)]
-fn return_inner(interpreter: &mut Interpreter, instruction_result: InstructionResult) {
+fn return_inner<H: Host>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+    instruction_result: InstructionResult,
+) {
     // zero gas cost
     // gas!(interpreter, gas::ZERO);
     pop!(interpreter, offset, len);
@@ -60,6 +64,10 @@ fn return_inner(interpreter: &mut Interpreter, instruction_result: InstructionRe
 
         output = interpreter.shared_memory.slice(offset, len).to_vec().into()
     }
+    if !host.record_return_data(len) {
+        interpreter.instruction_result = InstructionResult::ReturnDataLimit;
+        return;
+    }
     interpreter.instruction_result = instruction_result;
     interpreter.next_action = crate::InterpreterAction::Return {
         result: InterpreterResult {
@@ -70,14 +78,14 @@ fn return_inner(interpreter: &mut Interpreter, instruction_result: InstructionRe
     };
 }
 
-pub fn ret<H: Host>(interpreter: &mut Interpreter, _host: &mut H) {
-    return_inner(interpreter, InstructionResult::Return);
+pub fn ret<H: Host>(interpreter: &mut Interpreter, host: &mut H) {
+    return_inner(interpreter, host, InstructionResult::Return);
 }
 
 /// EIP-140: REVERT instruction
-pub fn revert<H: Host, SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut H) {
+pub fn revert<H: Host, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
     check!(interpreter, BYZANTIUM);
-    return_inner(interpreter, InstructionResult::Revert);
+    return_inner(interpreter, host, InstructionResult::Revert);
 }
 
 /// Stop opcode. This opcode halts the execution.
@@ -86,11 +94,19 @@ pub fn stop<H: Host>(interpreter: &mut Interpreter, _host: &mut H) {
 }
 
 /// Invalid opcode. This opcode halts the execution.
-pub fn invalid<H: Host>(interpreter: &mut Interpreter, _host: &mut H) {
+pub fn invalid<H: Host>(interpreter: &mut Interpreter, host: &mut H) {
+    let cfg = &host.env().cfg;
+    if !cfg.invalid_opcode_consumes_all {
+        gas!(interpreter, cfg.invalid_opcode_cost);
+    }
     interpreter.instruction_result = InstructionResult::InvalidFEOpcode;
 }
 
 /// Unknown opcode. This opcode halts the execution.
-pub fn unknown<H: Host>(interpreter: &mut Interpreter, _host: &mut H) {
+pub fn unknown<H: Host>(interpreter: &mut Interpreter, host: &mut H) {
+    let cfg = &host.env().cfg;
+    if !cfg.invalid_opcode_consumes_all {
+        gas!(interpreter, cfg.invalid_opcode_cost);
+    }
     interpreter.instruction_result = InstructionResult::OpcodeNotFound;
 }
@@ -172,7 +172,13 @@ pub fn sstore<H: Host, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H)
 pub fn tstore<H: Host, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
     check!(interpreter, CANCUN);
     check_staticcall!(interpreter);
-    gas!(interpreter, gas::WARM_STORAGE_READ_COST);
+    gas!(
+        interpreter,
+        host.env()
+            .cfg
+            .transient_store_cost
+            .unwrap_or(gas::WARM_STORAGE_READ_COST)
+    );
 
     pop!(interpreter, index, value);
 
@@ -183,7 +189,13 @@ pub fn tstore<H: Host, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H)
 /// Load value from transient storage
 pub fn tload<H: Host, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
     check!(interpreter, CANCUN);
-    gas!(interpreter, gas::WARM_STORAGE_READ_COST);
+    gas!(
+        interpreter,
+        host.env()
+            .cfg
+            .transient_load_cost
+            .unwrap_or(gas::WARM_STORAGE_READ_COST)
+    );
 
     pop_top!(interpreter, index);
 
@@ -192,6 +204,12 @@ pub fn tload<H: Host, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
 
 pub fn log<const N: usize, H: Host>(interpreter: &mut Interpreter, host: &mut H) {
     check_staticcall!(interpreter);
+    if let Some(max_logs) = host.env().cfg.max_logs {
+        if host.log_count() >= max_logs {
+            interpreter.instruction_result = InstructionResult::LogLimitReached;
+            return;
+        }
+    }
 
     pop!(interpreter, offset, len);
     let len = as_usize_or_fail!(interpreter, len);
@@ -342,7 +360,8 @@ pub fn call<H: Host, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
 
     // add call stipend if there is value to be transferred.
     if value != U256::ZERO {
-        gas_limit = gas_limit.saturating_add(gas::CALL_STIPEND);
+        gas_limit =
+            gas_limit.saturating_add(host.env().cfg.call_stipend.unwrap_or(gas::CALL_STIPEND));
     }
 
     // Call host to interact with target contract
@@ -397,7 +416,8 @@ pub fn call_code<H: Host, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut
 
     // add call stipend if there is value to be transferred.
     if value != U256::ZERO {
-        gas_limit = gas_limit.saturating_add(gas::CALL_STIPEND);
+        gas_limit =
+            gas_limit.saturating_add(host.env().cfg.call_stipend.unwrap_or(gas::CALL_STIPEND));
     }
 
     // Call host to interact with target contract
@@ -49,13 +49,17 @@ pub fn calc_call_gas<H: Host, SPEC: Spec>(
     };
     let is_new = !exist;
 
-    let call_cost = gas::call_cost::<SPEC>(
+    let mut call_cost = gas::call_cost::<SPEC>(
         has_transfer,
         is_new,
         is_cold,
         is_call_or_callcode,
         is_call_or_staticcall,
     );
+    // replace account access cost with the custom one.
+    if let Some(access_cost) = host.call_access_gas(to, is_cold) {
+        call_cost = (call_cost - gas::call_gas::<SPEC>(is_cold)).saturating_add(access_cost);
+    }
 
     gas!(interpreter, call_cost, None);
 
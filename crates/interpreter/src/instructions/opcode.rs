@@ -903,3 +903,44 @@ pub const fn spec_opcode_gas(spec_id: SpecId) -> &'static [OpInfo; 256] {
         LATEST,
     )
 }
+
+/// Returns the static gas cost of every opcode for the given [`SpecId`].
+///
+/// Opcodes that are undefined in the spec or whose cost depends on runtime
+/// values (memory expansion, account access, etc.) are `None`.
+pub fn gas_table(spec: SpecId) -> [Option<u64>; 256] {
+    let infos = spec_opcode_gas(spec);
+    let mut table = [None; 256];
+    for (opcode, info) in infos.iter().enumerate() {
+        table[opcode] = match opcode as u8 {
+            STOP => Some(0),
+            // JUMPDEST gas is not stored in its info.
+            JUMPDEST => Some(gas::JUMPDEST),
+            _ => match info.get_gas() {
+                0 => None,
+                gas => Some(gas as u64),
+            },
+        };
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_table() {
+        let table = gas_table(SpecId::CANCUN);
+        assert_eq!(table[STOP as usize], Some(0));
+        assert_eq!(table[ADD as usize], Some(3));
+        assert_eq!(table[MUL as usize], Some(5));
+        assert_eq!(table[JUMPDEST as usize], Some(1));
+        assert_eq!(table[PUSH0 as usize], Some(2));
+        assert_eq!(table[SLOAD as usize], None);
+        assert_eq!(table[0x0C], None);
+
+        // PUSH0 is not defined before Shanghai.
+        assert_eq!(gas_table(SpecId::MERGE)[PUSH0 as usize], None);
+    }
+}
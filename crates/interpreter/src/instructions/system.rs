@@ -111,24 +111,35 @@ pub fn returndatasize<H: Host, SPEC: Spec>(interpreter: &mut Interpreter, _host:
 }
 
 /// EIP-211: New opcodes: RETURNDATASIZE and RETURNDATACOPY
-pub fn returndatacopy<H: Host, SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut H) {
+pub fn returndatacopy<H: Host, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
     check!(interpreter, BYZANTIUM);
     pop!(interpreter, memory_offset, offset, len);
     let len = as_usize_or_fail!(interpreter, len);
     gas_or_fail!(interpreter, gas::verylowcopy_cost(len as u64));
     let data_offset = as_usize_saturated!(offset);
+    let lenient = host.env().cfg.lenient_returndatacopy;
     let (data_end, overflow) = data_offset.overflowing_add(len);
-    if overflow || data_end > interpreter.return_data_buffer.len() {
+    if !lenient && (overflow || data_end > interpreter.return_data_buffer.len()) {
         interpreter.instruction_result = InstructionResult::OutOfOffset;
         return;
     }
     if len != 0 {
         let memory_offset = as_usize_or_fail!(interpreter, memory_offset);
         shared_memory_resize!(interpreter, memory_offset, len);
-        interpreter.shared_memory.set(
-            memory_offset,
-            &interpreter.return_data_buffer[data_offset..data_end],
-        );
+        if lenient {
+            // bytes past the end of the buffer are zero-filled.
+            interpreter.shared_memory.set_data(
+                memory_offset,
+                data_offset,
+                len,
+                &interpreter.return_data_buffer,
+            );
+        } else {
+            interpreter.shared_memory.set(
+                memory_offset,
+                &interpreter.return_data_buffer[data_offset..data_end],
+            );
+        }
     }
 }
 
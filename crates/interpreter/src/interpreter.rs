@@ -10,7 +10,7 @@ pub use stack::{Stack, STACK_LIMIT};
 
 use crate::alloc::borrow::ToOwned;
 use crate::{
-    primitives::Bytes, push, push_b256, return_ok, return_revert, CallInputs, CallOutcome,
+    opcode, primitives::Bytes, push, push_b256, return_ok, return_revert, CallInputs, CallOutcome,
     CreateInputs, CreateOutcome, Gas, Host, InstructionResult,
 };
 use alloc::boxed::Box;
@@ -244,11 +244,31 @@ impl Interpreter {
     }
 
     /// Returns the opcode at the current instruction pointer.
+    ///
+    /// In inspector `step` the instruction pointer points to the opcode that is about
+    /// to be executed.
     #[inline]
     pub fn current_opcode(&self) -> u8 {
         unsafe { *self.instruction_pointer }
     }
 
+    /// Returns the immediate bytes of the opcode at the current instruction pointer.
+    ///
+    /// Only PUSH1-PUSH32 have immediate bytes, empty slice is returned for other opcodes.
+    /// Immediate that runs past the end of the code is truncated, missing bytes are read
+    /// as zeros by the PUSH.
+    #[inline]
+    pub fn current_immediate(&self) -> &[u8] {
+        let opcode = self.current_opcode();
+        if !(opcode::PUSH1..=opcode::PUSH32).contains(&opcode) {
+            return &[];
+        }
+        let code = self.contract.bytecode.original_bytecode_slice();
+        let start = min(self.program_counter() + 1, code.len());
+        let end = min(start + (opcode - opcode::PUSH0) as usize, code.len());
+        &code[start..end]
+    }
+
     /// Returns a reference to the contract.
     #[inline]
     pub fn contract(&self) -> &Contract {
@@ -3,7 +3,7 @@ use revm_primitives::{B256, U256};
 use crate::alloc::vec::Vec;
 use core::{
     cmp::min,
-    fmt,
+    fmt, mem,
     ops::{BitAnd, Not},
 };
 
@@ -21,6 +21,17 @@ pub struct SharedMemory {
     checkpoints: Vec<usize>,
     /// Invariant: equals `self.checkpoints.last()`
     last_checkpoint: usize,
+    /// Number of times the buffer grew its capacity.
+    reallocations: usize,
+    /// Free-list of buffers indexed by context depth, `None` if the memory is not pooled.
+    ///
+    /// In pooled mode every context has its own buffer and `buffer` is the buffer of the
+    /// current context.
+    pool: Option<Vec<Vec<u8>>>,
+    /// Buffers of the parent contexts in pooled mode.
+    parents: Vec<Vec<u8>>,
+    /// Summed length of the `parents` buffers.
+    parents_len: usize,
     /// Memory limit. See [`CfgEnv`](revm_primitives::CfgEnv).
     #[cfg(feature = "memory_limit")]
     memory_limit: u64,
@@ -33,6 +44,10 @@ pub const EMPTY_SHARED_MEMORY: SharedMemory = SharedMemory {
     buffer: Vec::new(),
     checkpoints: Vec::new(),
     last_checkpoint: 0,
+    reallocations: 0,
+    pool: None,
+    parents: Vec::new(),
+    parents_len: 0,
     #[cfg(feature = "memory_limit")]
     memory_limit: u64::MAX,
 };
@@ -72,6 +87,10 @@ impl SharedMemory {
             buffer: Vec::with_capacity(capacity),
             checkpoints: Vec::with_capacity(32),
             last_checkpoint: 0,
+            reallocations: 0,
+            pool: None,
+            parents: Vec::new(),
+            parents_len: 0,
             #[cfg(feature = "memory_limit")]
             memory_limit: u64::MAX,
         }
@@ -90,17 +109,90 @@ impl SharedMemory {
         }
     }
 
+    /// Creates a new memory instance that can be shared between calls with the given `capacity`,
+    /// with `memory_limit` as upper bound for allocation size.
+    #[cfg(feature = "memory_limit")]
+    #[inline]
+    pub fn with_capacity_and_memory_limit(capacity: usize, memory_limit: u64) -> Self {
+        Self {
+            memory_limit,
+            ..Self::with_capacity(capacity)
+        }
+    }
+
+    /// Returns the number of times the underlying buffer grew its capacity.
+    #[inline]
+    pub fn reallocations(&self) -> usize {
+        self.reallocations
+    }
+
+    /// Switches the memory to pooled mode, using `pool` as the free-list of buffers.
+    ///
+    /// In pooled mode every context gets its own buffer, taken from the free-list at the
+    /// context depth. Returning from a context puts its buffer back to the free-list, so the
+    /// next context at the same depth reuses the allocation. Memory contents and sizes are
+    /// the same as in the default mode.
+    ///
+    /// Must be called before the first context is created.
+    pub fn set_pool(&mut self, mut pool: Vec<Vec<u8>>) {
+        debug_assert!(self.checkpoints.is_empty(), "memory context is active");
+        if let Some(root) = pool.first_mut() {
+            if root.capacity() > self.buffer.capacity() {
+                mem::swap(root, &mut self.buffer);
+            }
+        }
+        self.parents.reserve(pool.len());
+        self.pool = Some(pool);
+    }
+
+    /// Takes the free-list of buffers and switches the memory back to the default mode.
+    ///
+    /// The buffer of the first context is returned at index 0. Returns an empty list if
+    /// the memory is not pooled.
+    pub fn take_pool(&mut self) -> Vec<Vec<u8>> {
+        debug_assert!(self.checkpoints.is_empty(), "memory context is active");
+        let Some(mut pool) = self.pool.take() else {
+            return Vec::new();
+        };
+        let mut root = mem::take(&mut self.buffer);
+        root.clear();
+        match pool.first_mut() {
+            Some(first) => *first = root,
+            None => pool.push(root),
+        }
+        pool
+    }
+
+    /// Returns `true` if the memory is in pooled mode, see [`SharedMemory::set_pool`].
+    #[inline]
+    pub fn is_pooled(&self) -> bool {
+        self.pool.is_some()
+    }
+
     /// Returns `true` if the `new_size` for the current context memory will
     /// make the shared buffer length exceed the `memory_limit`.
     #[cfg(feature = "memory_limit")]
     #[inline]
     pub fn limit_reached(&self, new_size: usize) -> bool {
-        (self.last_checkpoint + new_size) as u64 > self.memory_limit
+        (self.parents_len + self.last_checkpoint + new_size) as u64 > self.memory_limit
     }
 
     /// Prepares the shared memory for a new context.
     #[inline]
     pub fn new_context(&mut self) {
+        if let Some(pool) = &mut self.pool {
+            let depth = self.checkpoints.len();
+            // first context uses the root buffer.
+            if depth > 0 {
+                let buffer = pool.get_mut(depth).map(mem::take).unwrap_or_default();
+                let parent = mem::replace(&mut self.buffer, buffer);
+                self.parents_len += parent.len();
+                self.parents.push(parent);
+            }
+            self.checkpoints.push(0);
+            return;
+        }
+
         let new_checkpoint = self.buffer.len();
         self.checkpoints.push(new_checkpoint);
         self.last_checkpoint = new_checkpoint;
@@ -109,6 +201,26 @@ impl SharedMemory {
     /// Prepares the shared memory for returning to the previous context.
     #[inline]
     pub fn free_context(&mut self) {
+        if let Some(pool) = &mut self.pool {
+            if self.checkpoints.pop().is_none() {
+                return;
+            }
+            let Some(parent) = self.parents.pop() else {
+                self.buffer.clear();
+                return;
+            };
+            self.parents_len -= parent.len();
+            let mut buffer = mem::replace(&mut self.buffer, parent);
+            buffer.clear();
+            // return the buffer to the free-list for the next context at this depth.
+            let depth = self.checkpoints.len();
+            if pool.len() <= depth {
+                pool.resize_with(depth + 1, Vec::new);
+            }
+            pool[depth] = buffer;
+            return;
+        }
+
         if let Some(old_checkpoint) = self.checkpoints.pop() {
             self.last_checkpoint = self.checkpoints.last().cloned().unwrap_or_default();
             // SAFETY: buffer length is less than or equal `old_checkpoint`
@@ -131,7 +243,11 @@ impl SharedMemory {
     /// Resizes the memory in-place so that `len` is equal to `new_len`.
     #[inline]
     pub fn resize(&mut self, new_size: usize) {
+        let capacity = self.buffer.capacity();
         self.buffer.resize(self.last_checkpoint + new_size, 0);
+        if self.buffer.capacity() > capacity {
+            self.reallocations += 1;
+        }
     }
 
     /// Returns a byte slice of the memory region at the given offset.
@@ -404,4 +520,48 @@ mod tests {
         assert_eq!(shared_memory.len(), 64);
         assert_eq!(shared_memory.buffer.get(0..64), Some(&[0_u8; 64] as &[u8]));
     }
+
+    #[test]
+    fn pooled_new_free_context() {
+        let mut shared_memory = SharedMemory::new();
+        shared_memory.set_pool(Vec::new());
+        assert!(shared_memory.is_pooled());
+        shared_memory.new_context();
+
+        shared_memory.resize(32);
+        shared_memory.set_byte(0, 1);
+        shared_memory.new_context();
+        assert_eq!(shared_memory.len(), 0);
+        assert_eq!(shared_memory.parents_len, 32);
+
+        shared_memory.resize(96);
+        assert_eq!(shared_memory.len(), 96);
+        assert_eq!(shared_memory.context_memory(), &[0_u8; 96]);
+        shared_memory.set_byte(0, 2);
+        let ptr = shared_memory.buffer.as_ptr();
+
+        // returned buffer is kept in the free-list at depth 1.
+        shared_memory.free_context();
+        assert_eq!(shared_memory.len(), 32);
+        assert_eq!(shared_memory.get_byte(0), 1);
+        assert_eq!(shared_memory.parents_len, 0);
+
+        // next context at the same depth reuses the allocation, zeroed.
+        shared_memory.new_context();
+        assert_eq!(shared_memory.buffer.as_ptr(), ptr);
+        let reallocations = shared_memory.reallocations();
+        shared_memory.resize(64);
+        assert_eq!(shared_memory.reallocations(), reallocations);
+        assert_eq!(shared_memory.context_memory(), &[0_u8; 64]);
+        shared_memory.free_context();
+
+        shared_memory.free_context();
+        assert_eq!(shared_memory.len(), 0);
+
+        let pool = shared_memory.take_pool();
+        assert!(!shared_memory.is_pooled());
+        assert_eq!(pool.len(), 2);
+        assert!(pool.iter().all(|buffer| buffer.is_empty()));
+        assert!(pool[1].capacity() >= 96);
+    }
 }
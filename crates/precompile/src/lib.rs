@@ -20,6 +20,8 @@ mod modexp;
 mod secp256k1;
 pub mod utilities;
 
+pub use secp256k1::recover_address;
+
 use alloc::{boxed::Box, vec::Vec};
 use core::{fmt, hash::Hash};
 use once_cell::race::OnceBox;
@@ -1,5 +1,5 @@
 use crate::{utilities::right_pad, Error, Precompile, PrecompileResult, PrecompileWithAddress};
-use revm_primitives::{alloy_primitives::B512, Bytes, B256};
+use revm_primitives::{alloy_primitives::B512, Address, Bytes, B256};
 
 pub const ECRECOVER: PrecompileWithAddress = PrecompileWithAddress(
     crate::u64_to_address(1),
@@ -64,6 +64,21 @@ mod secp256k1 {
     }
 }
 
+/// Recovers the address that signed `msg` with signature `sig` and recovery id `recid` (0 or 1).
+///
+/// Uses the same backend as the ECRECOVER precompile. Transaction sender can be recovered
+/// by passing the transaction signing hash, `r ++ s` and the y parity of the signature.
+///
+/// Returns `None` if the signature is invalid.
+pub fn recover_address(sig: &B512, recid: u8, msg: &B256) -> Option<Address> {
+    if recid > 1 {
+        return None;
+    }
+    secp256k1::ecrecover(sig, recid, msg)
+        .ok()
+        .map(|hash| Address::from_slice(&hash[12..]))
+}
+
 fn ec_recover_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
     const ECRECOVER_BASE: u64 = 3_000;
 
@@ -87,3 +102,29 @@ fn ec_recover_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
         .unwrap_or_default();
     Ok((ECRECOVER_BASE, out))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm_primitives::{address, b256, hex};
+
+    #[test]
+    fn test_recover_address() {
+        let msg = b256!("38d18acb67d25c8bb9942764b62f18e17054f66a817bd4295423adf9ed98873e");
+        let sig = B512::from(hex!(
+            "38d18acb67d25c8bb9942764b62f18e17054f66a817bd4295423adf9ed98873e"
+            "789d1dd423d25f0772d2748d60f7e4b81bb14d086eba8e8e8efb6dcff8a4ae02"
+        ));
+        let expected = address!("ceaccac640adf55b2028469bd36ba501f28b699d");
+        assert_eq!(recover_address(&sig, 0, &msg), Some(expected));
+
+        // consistent with ECRECOVER precompile.
+        let mut input = msg.to_vec();
+        input.extend_from_slice(B256::with_last_byte(27).as_slice());
+        input.extend_from_slice(sig.as_slice());
+        let (_, out) = ec_recover_run(&input.into(), 3_000).unwrap();
+        assert_eq!(&out[12..], expected.as_slice());
+
+        assert_eq!(recover_address(&sig, 2, &msg), None);
+    }
+}
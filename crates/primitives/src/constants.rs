@@ -1,4 +1,4 @@
-use crate::Address;
+use crate::{address, Address};
 
 /// EIP-170: Contract code size limit
 /// By default limit is 0x6000 (~25kb)
@@ -15,6 +15,17 @@ pub const MAX_INITCODE_SIZE: usize = 2 * MAX_CODE_SIZE;
 /// Precompile 3 is special in few places
 pub const PRECOMPILE3: Address =
     Address::new([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3]);
+
+/// Caller of system calls, for example EIP-4788 beacon root update.
+pub const SYSTEM_ADDRESS: Address = address!("fffffffffffffffffffffffffffffffffffffffe");
+/// Gas limit of system calls.
+pub const SYSTEM_CALL_GAS_LIMIT: u64 = 30_000_000;
+
+// EIP-4788 constants
+/// Address of the beacon roots contract.
+pub const BEACON_ROOTS_ADDRESS: Address = address!("000F3df6D732807Ef1319fB7B8bB8522d0Beac02");
+/// Length of the ring buffer of the beacon roots contract.
+pub const BEACON_ROOTS_HISTORY_BUFFER_LENGTH: u64 = 8191;
 // EIP-4844 constants
 /// Gas consumption of a single data blob (== blob byte size).
 pub const GAS_PER_BLOB: u64 = 1 << 17;
@@ -3,12 +3,17 @@ pub mod handler_cfg;
 pub use handler_cfg::{CfgEnvWithHandlerCfg, EnvWithHandlerCfg, HandlerCfg};
 
 use crate::{
-    alloc::vec::Vec, calc_blob_gasprice, Account, Address, Bytes, InvalidHeader,
-    InvalidTransaction, Spec, SpecId, B256, GAS_PER_BLOB, KECCAK_EMPTY, MAX_BLOB_NUMBER_PER_BLOCK,
-    MAX_INITCODE_SIZE, U256, VERSIONED_HASH_VERSION_KZG,
+    alloc::vec::Vec, calc_blob_gasprice, Account, AccountInfo, Address, Bytecode, Bytes,
+    InvalidHeader, InvalidTransaction, Spec, SpecId, B256, GAS_PER_BLOB, KECCAK_EMPTY,
+    MAX_BLOB_NUMBER_PER_BLOCK, MAX_INITCODE_SIZE, U256, VERSIONED_HASH_VERSION_KZG,
+};
+use alloc::{boxed::Box, collections::BTreeMap, sync::Arc};
+use core::{
+    any::Any,
+    cmp::{min, Ordering},
+    fmt,
+    hash::{Hash, Hasher},
 };
-use alloc::boxed::Box;
-use core::cmp::{min, Ordering};
 
 /// EVM environment configuration.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
@@ -70,6 +75,23 @@ impl Env {
         })
     }
 
+    /// Calculates the balance the caller needs to pay for the transaction:
+    /// `gas_limit * gas_price + value`, plus the max data fee after Cancun.
+    ///
+    /// Returns `None` on overflow.
+    pub fn calc_max_tx_cost(&self, spec_id: SpecId) -> Option<U256> {
+        let mut cost = U256::from(self.tx.gas_limit)
+            .checked_mul(self.tx.gas_price)?
+            .checked_add(self.tx.value)?;
+
+        if SpecId::enabled(spec_id, SpecId::CANCUN) {
+            // if the tx is not a blob tx, this will be None, so we add zero
+            let data_fee = self.calc_max_data_fee().unwrap_or_default();
+            cost = cost.checked_add(data_fee)?;
+        }
+        Some(cost)
+    }
+
     /// Validate the block environment.
     #[inline]
     pub fn validate_block_env<SPEC: Spec>(&self) -> Result<(), InvalidHeader> {
@@ -89,6 +111,16 @@ impl Env {
     /// Return initial spend gas (Gas needed to execute transaction).
     #[inline]
     pub fn validate_tx<SPEC: Spec>(&self) -> Result<(), InvalidTransaction> {
+        self.validate_tx_with_blob_hash_check::<SPEC>(Self::is_valid_blob_versioned_hash)
+    }
+
+    /// Validate transaction data like [`Env::validate_tx`], with the blob versioned hashes
+    /// checked by `is_valid_blob_hash` instead of [`Env::is_valid_blob_versioned_hash`].
+    #[inline]
+    pub fn validate_tx_with_blob_hash_check<SPEC: Spec>(
+        &self,
+        mut is_valid_blob_hash: impl FnMut(&B256) -> bool,
+    ) -> Result<(), InvalidTransaction> {
         // BASEFEE tx check
         if SPEC::enabled(SpecId::LONDON) {
             if let Some(priority_fee) = self.tx.gas_priority_fee {
@@ -113,6 +145,11 @@ impl Env {
             return Err(InvalidTransaction::CallerGasLimitMoreThanBlock);
         }
 
+        // Check if contract creation is allowed
+        if self.cfg.disable_create && self.tx.transact_to.is_create() {
+            return Err(InvalidTransaction::CreateNotAllowed);
+        }
+
         // EIP-3860: Limit and meter initcode
         if SPEC::enabled(SpecId::SHANGHAI) && self.tx.transact_to.is_create() {
             let max_initcode_size = self
@@ -161,17 +198,25 @@ impl Env {
                     return Err(InvalidTransaction::BlobCreateTransaction);
                 }
 
-                // all versioned blob hashes must start with VERSIONED_HASH_VERSION_KZG
+                // all versioned blob hashes must be valid, by default they must start
+                // with VERSIONED_HASH_VERSION_KZG
                 for blob in self.tx.blob_hashes.iter() {
-                    if blob[0] != VERSIONED_HASH_VERSION_KZG {
+                    if !is_valid_blob_hash(blob) {
                         return Err(InvalidTransaction::BlobVersionNotSupported);
                     }
                 }
 
                 // ensure the total blob gas spent is at most equal to the limit
                 // assert blob_gas_used <= MAX_BLOB_GAS_PER_BLOCK
-                if self.tx.blob_hashes.len() > MAX_BLOB_NUMBER_PER_BLOCK as usize {
-                    return Err(InvalidTransaction::TooManyBlobs);
+                let max = self
+                    .cfg
+                    .max_blobs_per_tx
+                    .unwrap_or(MAX_BLOB_NUMBER_PER_BLOCK as usize);
+                if self.tx.blob_hashes.len() > max {
+                    return Err(InvalidTransaction::TooManyBlobs {
+                        have: self.tx.blob_hashes.len(),
+                        max,
+                    });
                 }
             }
         } else {
@@ -186,22 +231,41 @@ impl Env {
         Ok(())
     }
 
+    /// Returns true if [EIP-4844] blob versioned hash starts with [`VERSIONED_HASH_VERSION_KZG`].
+    ///
+    /// [EIP-4844]: https://eips.ethereum.org/EIPS/eip-4844
+    #[inline]
+    pub fn is_valid_blob_versioned_hash(hash: &B256) -> bool {
+        hash[0] == VERSIONED_HASH_VERSION_KZG
+    }
+
     /// Validate transaction against state.
     #[inline]
     pub fn validate_tx_against_state<SPEC: Spec>(
         &self,
         account: &mut Account,
     ) -> Result<(), InvalidTransaction> {
+        self.validate_caller_nonce(&account.info)?;
+
+        let balance_check = self
+            .calc_max_tx_cost(SPEC::SPEC_ID)
+            .ok_or(InvalidTransaction::OverflowPaymentInTransaction)?;
+        self.validate_caller_balance(account, balance_check)
+    }
+
+    /// Validate that the caller has no code and its nonce matches the transaction nonce.
+    #[inline]
+    pub fn validate_caller_nonce(&self, info: &AccountInfo) -> Result<(), InvalidTransaction> {
         // EIP-3607: Reject transactions from senders with deployed code
         // This EIP is introduced after london but there was no collision in past
         // so we can leave it enabled always
-        if !self.cfg.is_eip3607_disabled() && account.info.code_hash != KECCAK_EMPTY {
+        if !self.cfg.is_eip3607_disabled() && info.code_hash != KECCAK_EMPTY {
             return Err(InvalidTransaction::RejectCallerWithCode);
         }
 
         // Check that the transaction's nonce is correct
         if let Some(tx) = self.tx.nonce {
-            let state = account.info.nonce;
+            let state = info.nonce;
             match tx.cmp(&state) {
                 Ordering::Greater => {
                     return Err(InvalidTransaction::NonceTooHigh { tx, state });
@@ -212,34 +276,31 @@ impl Env {
                 _ => {}
             }
         }
+        Ok(())
+    }
 
-        let mut balance_check = U256::from(self.tx.gas_limit)
-            .checked_mul(self.tx.gas_price)
-            .and_then(|gas_cost| gas_cost.checked_add(self.tx.value))
-            .ok_or(InvalidTransaction::OverflowPaymentInTransaction)?;
-
-        if SPEC::enabled(SpecId::CANCUN) {
-            // if the tx is not a blob tx, this will be None, so we add zero
-            let data_fee = self.calc_max_data_fee().unwrap_or_default();
-            balance_check = balance_check
-                .checked_add(U256::from(data_fee))
-                .ok_or(InvalidTransaction::OverflowPaymentInTransaction)?;
-        }
-
+    /// Validate that the caller can pay the `tx_cost` of the transaction.
+    ///
+    /// If the balance check is disabled, the missing balance is added to the caller.
+    #[inline]
+    pub fn validate_caller_balance(
+        &self,
+        account: &mut Account,
+        tx_cost: U256,
+    ) -> Result<(), InvalidTransaction> {
         // Check if account has enough balance for gas_limit*gas_price and value transfer.
         // Transfer will be done inside `*_inner` functions.
-        if balance_check > account.info.balance {
+        if tx_cost > account.info.balance {
             if self.cfg.is_balance_check_disabled() {
                 // Add transaction cost to balance to ensure execution doesn't fail.
-                account.info.balance = balance_check;
+                account.info.balance = tx_cost;
             } else {
                 return Err(InvalidTransaction::LackOfFundForMaxFee {
-                    fee: Box::new(balance_check),
+                    fee: Box::new(tx_cost),
                     balance: Box::new(account.info.balance),
                 });
             }
         }
-
         Ok(())
     }
 }
@@ -264,6 +325,135 @@ pub struct CfgEnv {
     /// If some it will effects EIP-170: Contract code size limit. Useful to increase this because of tests.
     /// By default it is 0x6000 (~25kb).
     pub limit_contract_code_size: Option<usize>,
+    /// If some it will limit the number of logs that can be emitted in a single transaction.
+    /// Logs that are reverted still count towards the limit, as they were allocated.
+    ///
+    /// When exceeded, execution halts with [crate::result::HaltReason::LogLimitReached].
+    /// By default it is `None` and there is no limit.
+    pub max_logs: Option<usize>,
+    /// Disables contract creation. Create transactions are rejected in validation,
+    /// and CREATE/CREATE2 fail with [crate::result::HaltReason::CreateNotAllowed].
+    /// By default, it is set to `false`.
+    pub disable_create: bool,
+    /// Makes RETURNDATACOPY zero-fill bytes past the end of the return data buffer
+    /// instead of halting with [crate::result::HaltReason::OutOfOffset].
+    ///
+    /// This is non-standard behaviour and should only be used for tooling.
+    /// By default, it is set to `false`.
+    pub lenient_returndatacopy: bool,
+    /// Maximum number of nested create frames. Create frame started while this many
+    /// create frames are executing fails like a too deep call, its gas is returned to the
+    /// caller. Create transaction fails with [crate::result::HaltReason::CreateDepthLimit].
+    ///
+    /// Create transaction counts as the first create frame.
+    /// By default it is `None` and create nesting is only limited by the call depth.
+    pub max_create_depth: Option<u64>,
+    /// Value returned by EXTCODEHASH for existing accounts without code.
+    ///
+    /// Only EXTCODEHASH is affected, accounts are still loaded and stored with
+    /// [crate::KECCAK_EMPTY] code hash. Non existing accounts still return zero.
+    /// By default it is `None` and [crate::KECCAK_EMPTY] is returned.
+    pub empty_extcodehash: Option<B256>,
+    /// Maximum number of nested journal checkpoints. Every call and create frame makes
+    /// a checkpoint, frame that would exceed the limit fails with
+    /// [crate::result::HaltReason::JournalDepthLimit].
+    ///
+    /// This bounds host memory used by the journal independently of the call depth limit.
+    /// By default it is `None` and there is no limit.
+    pub max_journal_depth: Option<usize>,
+    /// Gas cost of the TLOAD opcode (EIP-1153).
+    ///
+    /// By default it is `None` and the warm storage read cost of 100 gas is used.
+    pub transient_load_cost: Option<u64>,
+    /// Gas cost of the TSTORE opcode (EIP-1153).
+    ///
+    /// By default it is `None` and the warm storage read cost of 100 gas is used.
+    pub transient_store_cost: Option<u64>,
+    /// Initial capacity in bytes of the memory shared between call frames.
+    ///
+    /// Memory buffer is reallocated when it grows past its capacity, a bigger initial
+    /// capacity avoids reallocations for memory heavy transactions. It does not affect gas.
+    /// By default it is `None` and capacity of 4KiB is used.
+    pub initial_memory_capacity: Option<usize>,
+    /// Whether every call frame gets its own memory buffer from a pool of buffers kept per
+    /// call depth.
+    ///
+    /// Buffer of a returned frame is reused by the next frame at the same depth, also in the
+    /// following transactions. It does not affect memory contents or gas.
+    /// By default, it is set to `false`.
+    pub memory_pool: bool,
+    /// Maximum number of bytes returned by RETURN and REVERT opcodes in the transaction,
+    /// summed over all frames. RETURN or REVERT that would exceed the limit fails with
+    /// [crate::result::HaltReason::ReturnDataLimit].
+    ///
+    /// By default it is `None` and there is no limit.
+    pub max_return_data_size: Option<usize>,
+    /// Accounts loaded from the database before the transaction is executed, with the
+    /// access list accounts.
+    ///
+    /// Accounts are cold unless [`CfgEnv::warm_preload_accounts`] is set.
+    pub preload_accounts: Vec<Address>,
+    /// Whether accounts from [`CfgEnv::preload_accounts`] are warm, as if they were
+    /// in the access list.
+    pub warm_preload_accounts: bool,
+    /// Per-mille gas discount of precompiles at given addresses.
+    ///
+    /// Discount is applied to the cost computed by the precompile, so the call still needs
+    /// enough gas for the full cost. Discount of 1000 or more makes the precompile free.
+    ///
+    /// By default it is empty and no discount is applied.
+    pub precompile_gas_discount: BTreeMap<Address, u64>,
+    /// Whether INVALID and undefined opcodes consume all remaining gas of the frame.
+    ///
+    /// If `false`, they consume only [`CfgEnv::invalid_opcode_cost`] and the rest of the
+    /// gas is returned to the caller, execution still halts.
+    ///
+    /// This is non-standard behaviour and should only be used for tooling.
+    /// By default, it is set to `true`.
+    pub invalid_opcode_consumes_all: bool,
+    /// Gas consumed by INVALID and undefined opcodes when
+    /// [`CfgEnv::invalid_opcode_consumes_all`] is `false`.
+    ///
+    /// By default, it is set to `0`.
+    pub invalid_opcode_cost: u64,
+    /// Gas stipend added to the gas forwarded by CALL and CALLCODE that transfer value.
+    ///
+    /// By default it is `None` and the stipend of 2300 gas is used.
+    pub call_stipend: Option<u64>,
+    /// Whether a transaction that calls a precompile directly executes the precompile.
+    ///
+    /// If `false`, such transaction is a plain value transfer to the precompile address,
+    /// precompile is not executed and its gas is not charged. Calls to precompiles made
+    /// by contracts always execute the precompile.
+    ///
+    /// By default, it is set to `true`.
+    pub top_level_precompile_calls: bool,
+    /// Maximum depth of nested call and create frames. Frame started deeper than this
+    /// fails with [crate::result::HaltReason::CallTooDeep].
+    ///
+    /// Transaction frame has depth 0. Values above the consensus limit of 1024 are
+    /// treated as 1024. By default, it is set to 1024.
+    pub max_call_depth: u16,
+    /// Maximum number of blob hashes in a blob transaction (EIP-4844). Transaction with more
+    /// blobs is rejected with [InvalidTransaction::TooManyBlobs].
+    ///
+    /// By default it is `None` and [MAX_BLOB_NUMBER_PER_BLOCK] is used.
+    pub max_blobs_per_tx: Option<usize>,
+    /// Whether a reverted transaction is returned as
+    /// [crate::result::EVMError::TransactionReverted] instead of a revert result.
+    ///
+    /// State changes of the transaction are discarded. By default, it is set to `false`.
+    pub revert_is_error: bool,
+    /// Overrides of account state applied before every transaction is executed.
+    ///
+    /// See [AccountOverride].
+    pub state_overrides: BTreeMap<Address, AccountOverride>,
+    /// Whether storage slots written to zero carry an explicit deletion marker
+    /// ([crate::StorageSlot::deleted]) in the resulting state.
+    ///
+    /// Such slots are always part of the state, the flag only sets the marker.
+    /// By default, it is set to `false`.
+    pub keep_zero_storage: bool,
     /// A hard memory limit in bytes beyond which [crate::result::OutOfGasError::Memory] cannot be resized.
     ///
     /// In cases where the gas limit may be extraordinarily high, it is recommended to set this to
@@ -368,6 +558,29 @@ impl Default for CfgEnv {
             chain_id: 1,
             perf_analyse_created_bytecodes: AnalysisKind::default(),
             limit_contract_code_size: None,
+            max_logs: None,
+            disable_create: false,
+            lenient_returndatacopy: false,
+            max_create_depth: None,
+            empty_extcodehash: None,
+            max_journal_depth: None,
+            transient_load_cost: None,
+            transient_store_cost: None,
+            initial_memory_capacity: None,
+            memory_pool: false,
+            max_return_data_size: None,
+            preload_accounts: Vec::new(),
+            warm_preload_accounts: false,
+            precompile_gas_discount: BTreeMap::new(),
+            invalid_opcode_consumes_all: true,
+            invalid_opcode_cost: 0,
+            call_stipend: None,
+            top_level_precompile_calls: true,
+            max_call_depth: 1024,
+            max_blobs_per_tx: None,
+            revert_is_error: false,
+            state_overrides: BTreeMap::new(),
+            keep_zero_storage: false,
             #[cfg(feature = "c-kzg")]
             kzg_settings: crate::kzg::EnvKzgSettings::Default,
             #[cfg(feature = "memory_limit")]
@@ -388,6 +601,27 @@ impl Default for CfgEnv {
     }
 }
 
+/// Override of the state of an account, applied before the transaction is executed.
+///
+/// Overridden values are visible to the executing code and are part of the state changes
+/// of the transaction. Transaction validation sees the state from the database.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountOverride {
+    /// Balance of the account.
+    pub balance: Option<U256>,
+    /// Nonce of the account.
+    pub nonce: Option<u64>,
+    /// Code of the account.
+    pub code: Option<Bytecode>,
+    /// Replaces the whole storage of the account, slots that are not set are zero.
+    ///
+    /// Account with replaced storage is treated as created in the transaction.
+    pub state: Option<BTreeMap<U256, U256>>,
+    /// Sets listed storage slots, applied after `state`.
+    pub state_diff: Option<BTreeMap<U256, U256>>,
+}
+
 /// The block environment.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -427,6 +661,12 @@ pub struct BlockEnv {
     ///
     /// [EIP-4844]: https://eips.ethereum.org/EIPS/eip-4844
     pub blob_excess_gas_and_price: Option<BlobExcessGasAndPrice>,
+    /// Root of the parent beacon block.
+    ///
+    /// Written to the beacon roots contract by the system call of [EIP-4788].
+    ///
+    /// [EIP-4788]: https://eips.ethereum.org/EIPS/eip-4788
+    pub parent_beacon_block_root: Option<B256>,
 }
 
 impl BlockEnv {
@@ -466,6 +706,9 @@ impl BlockEnv {
     }
 }
 
+/// Defaults are chosen so that transactions pass block validation without additional setup:
+/// `timestamp` is `1`, `gas_limit` is [U256::MAX] and all other values are zero, with
+/// `prevrandao` set to [B256::ZERO], excess blob gas set to zero and no parent beacon block root.
 impl Default for BlockEnv {
     fn default() -> Self {
         Self {
@@ -477,6 +720,7 @@ impl Default for BlockEnv {
             difficulty: U256::ZERO,
             prevrandao: Some(B256::ZERO),
             blob_excess_gas_and_price: Some(BlobExcessGasAndPrice::new(0)),
+            parent_beacon_block_root: None,
         }
     }
 }
@@ -539,6 +783,14 @@ pub struct TxEnv {
     #[cfg_attr(feature = "serde", serde(flatten))]
     #[cfg(feature = "optimism")]
     pub optimism: OptimismFields,
+
+    /// Data of a custom transaction type.
+    ///
+    /// It is not used by mainnet handlers. Chains with custom transaction types can attach
+    /// extra transaction fields here and read them in handlers appended with
+    /// `append_handler_register`. It is not serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub tx_type_data: Option<TxTypeData>,
 }
 
 impl TxEnv {
@@ -555,6 +807,64 @@ impl TxEnv {
     pub fn clear(&mut self) {
         *self = Self::default();
     }
+
+    /// Sets data of a custom transaction type.
+    #[inline]
+    pub fn set_tx_type_data<T: Any + Send + Sync>(&mut self, data: T) {
+        self.tx_type_data = Some(TxTypeData::new(data));
+    }
+
+    /// Returns data of a custom transaction type if it is set and is of type `T`.
+    #[inline]
+    pub fn tx_type_data<T: Any>(&self) -> Option<&T> {
+        self.tx_type_data.as_ref()?.downcast_ref()
+    }
+}
+
+/// Type erased data of a custom transaction type, see [`TxEnv::tx_type_data`].
+///
+/// Data is shared on clone. Two values are equal if they point to the same data.
+#[derive(Clone)]
+pub struct TxTypeData(Arc<dyn Any + Send + Sync>);
+
+impl TxTypeData {
+    /// Creates new custom transaction type data.
+    #[inline]
+    pub fn new<T: Any + Send + Sync>(data: T) -> Self {
+        Self(Arc::new(data))
+    }
+
+    /// Returns reference to the data if it is of type `T`.
+    #[inline]
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+
+    /// Returns address of the data.
+    #[inline]
+    fn addr(&self) -> usize {
+        Arc::as_ptr(&self.0) as *const () as usize
+    }
+}
+
+impl fmt::Debug for TxTypeData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TxTypeData").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for TxTypeData {
+    fn eq(&self, other: &Self) -> bool {
+        self.addr() == other.addr()
+    }
+}
+
+impl Eq for TxTypeData {}
+
+impl Hash for TxTypeData {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.addr().hash(state);
+    }
 }
 
 impl Default for TxEnv {
@@ -574,6 +884,7 @@ impl Default for TxEnv {
             max_fee_per_blob_gas: None,
             #[cfg(feature = "optimism")]
             optimism: OptimismFields::default(),
+            tx_type_data: None,
         }
     }
 }
@@ -729,4 +1040,47 @@ mod tests {
             Err(InvalidTransaction::AccessListNotSupported)
         );
     }
+
+    #[test]
+    fn test_validate_tx_create_not_allowed() {
+        let mut env = Env::default();
+        env.cfg.disable_create = true;
+        env.tx.transact_to = TransactTo::create();
+        assert_eq!(
+            env.validate_tx::<crate::LatestSpec>(),
+            Err(InvalidTransaction::CreateNotAllowed)
+        );
+    }
+
+    #[test]
+    fn test_validate_tx_max_blobs_per_tx() {
+        let mut env = Env::default();
+        env.block.set_blob_excess_gas_and_price(0);
+        env.tx.transact_to = TransactTo::Call(Address::ZERO);
+        env.tx.max_fee_per_blob_gas = Some(U256::from(1));
+        let mut hash = B256::ZERO;
+        hash[0] = VERSIONED_HASH_VERSION_KZG;
+        env.tx.blob_hashes = vec![hash; 7];
+        assert_eq!(
+            env.validate_tx::<crate::CancunSpec>(),
+            Err(InvalidTransaction::TooManyBlobs { have: 7, max: 6 })
+        );
+
+        env.cfg.max_blobs_per_tx = Some(8);
+        assert_eq!(env.validate_tx::<crate::CancunSpec>(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_tx_blob_version() {
+        let mut env = Env::default();
+        env.block.set_blob_excess_gas_and_price(0);
+        env.tx.transact_to = TransactTo::Call(Address::ZERO);
+        env.tx.max_fee_per_blob_gas = Some(U256::from(1));
+        // version is checked before the number of blobs.
+        env.tx.blob_hashes = vec![B256::with_last_byte(1); 7];
+        assert_eq!(
+            env.validate_tx::<crate::CancunSpec>(),
+            Err(InvalidTransaction::BlobVersionNotSupported)
+        );
+    }
 }
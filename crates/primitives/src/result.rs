@@ -1,5 +1,5 @@
-use crate::{Address, Bytes, Log, State, U256};
-use alloc::{boxed::Box, string::String, vec::Vec};
+use crate::{Address, Bytes, HashSet, Log, State, U256};
+use alloc::{boxed::Box, format, string::String, vec::Vec};
 use core::fmt;
 
 /// Result of EVM execution.
@@ -14,7 +14,39 @@ pub struct ResultAndState {
     /// Status of execution
     pub result: ExecutionResult,
     /// State that got updated
+    #[cfg_attr(
+        all(feature = "serde", feature = "deterministic"),
+        serde(serialize_with = "crate::state::serialize_sorted")
+    )]
     pub state: State,
+    /// Addresses of contracts created by the transaction, in creation order
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub created_contracts: Vec<Address>,
+}
+
+impl ResultAndState {
+    /// Returns addresses of all contracts created by the transaction in creation order,
+    /// including contracts created by nested CREATE and CREATE2.
+    ///
+    /// Creates that failed or were reverted by a parent frame are not included.
+    pub fn created_contracts(&self) -> &[Address] {
+        &self.created_contracts
+    }
+
+    /// Returns addresses of all accounts and all storage slots accessed by the transaction.
+    ///
+    /// These are the keccak preimages of the state trie keys needed to prove the state
+    /// accesses of the transaction. Unlike an access list, it includes the sender, the
+    /// coinbase and accounts and slots that were only read or accessed by reverted frames.
+    pub fn touched_preimages(&self) -> (HashSet<Address>, HashSet<(Address, U256)>) {
+        let accounts = self.state.keys().copied().collect();
+        let slots = self
+            .state
+            .iter()
+            .flat_map(|(address, account)| account.storage.keys().map(|slot| (*address, *slot)))
+            .collect();
+        (accounts, slots)
+    }
 }
 
 /// Result of a transaction execution.
@@ -52,6 +84,19 @@ impl ExecutionResult {
         matches!(self, Self::Halt { .. })
     }
 
+    /// Returns true if execution was interrupted and the result is partial.
+    ///
+    /// See [HaltReason::Interrupted].
+    pub fn is_partial(&self) -> bool {
+        matches!(
+            self,
+            Self::Halt {
+                reason: HaltReason::Interrupted,
+                ..
+            }
+        )
+    }
+
     /// Return logs, if execution is not successful, function will return empty vec.
     pub fn logs(&self) -> Vec<Log> {
         match self {
@@ -97,6 +142,49 @@ impl ExecutionResult {
 
         *gas_used
     }
+
+    /// Returns the decoded revert reason if execution reverted.
+    ///
+    /// Revert data encoded as `Error(string)` is decoded to the string and `Panic(uint256)`
+    /// is decoded to `Panic(<code>)` with hex code. Returns `None` if execution did not revert
+    /// or revert data does not match either encoding.
+    pub fn revert_reason(&self) -> Option<String> {
+        match self {
+            Self::Revert { output, .. } => decode_revert_reason(output),
+            _ => None,
+        }
+    }
+}
+
+/// Selector of `Error(string)`.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of `Panic(uint256)`.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decodes ABI encoded `Error(string)` or `Panic(uint256)` revert data.
+fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    if data.len() < 4 {
+        return None;
+    }
+    let (selector, data) = data.split_at(4);
+    let word = |offset: usize| -> Option<U256> {
+        let bytes = data.get(offset..offset.checked_add(32)?)?;
+        Some(U256::from_be_slice(bytes))
+    };
+
+    if selector == ERROR_SELECTOR {
+        let offset: usize = word(0)?.try_into().ok()?;
+        let len: usize = word(offset)?.try_into().ok()?;
+        let start = offset.checked_add(32)?;
+        let bytes = data.get(start..start.checked_add(len)?)?;
+        String::from_utf8(bytes.to_vec()).ok()
+    } else if selector == PANIC_SELECTOR {
+        let code = word(0)?;
+        Some(format!("Panic({code:#x})"))
+    } else {
+        None
+    }
 }
 
 /// Output of a transaction execution.
@@ -139,6 +227,11 @@ pub enum EVMError<DBError> {
     ///
     /// Useful for handler registers where custom logic would want to return their own custom error.
     Custom(String),
+    /// Transaction reverted and [`CfgEnv::revert_is_error`](crate::CfgEnv::revert_is_error) is set.
+    TransactionReverted {
+        /// Output of the reverted transaction.
+        output: Bytes,
+    },
 }
 
 #[cfg(feature = "std")]
@@ -151,6 +244,9 @@ impl<DBError: fmt::Display> fmt::Display for EVMError<DBError> {
             EVMError::Header(e) => write!(f, "Header error: {e:?}"),
             EVMError::Database(e) => write!(f, "Database error: {e}"),
             EVMError::Custom(e) => write!(f, "Custom error: {e}"),
+            EVMError::TransactionReverted { output } => {
+                write!(f, "Transaction reverted with output: {output}")
+            }
         }
     }
 }
@@ -202,6 +298,8 @@ pub enum InvalidTransaction {
     },
     /// EIP-3860: Limit and meter initcode
     CreateInitCodeSizeLimit,
+    /// Contract creation is disabled by [`crate::CfgEnv::disable_create`].
+    CreateNotAllowed,
     /// Transaction chain id does not match the config chain id.
     InvalidChainId,
     /// Access list is not supported for blocks before the Berlin hardfork.
@@ -217,8 +315,14 @@ pub enum InvalidTransaction {
     /// Blob transaction can't be a create transaction.
     /// `to` must be present
     BlobCreateTransaction,
-    /// Transaction has more then [`crate::MAX_BLOB_NUMBER_PER_BLOCK`] blobs
-    TooManyBlobs,
+    /// Transaction has more blobs than [`crate::CfgEnv::max_blobs_per_tx`], by default
+    /// [`crate::MAX_BLOB_NUMBER_PER_BLOCK`].
+    TooManyBlobs {
+        /// Number of blobs in the transaction.
+        have: usize,
+        /// Maximum number of blobs.
+        max: usize,
+    },
     /// Blob transaction contains a versioned hash with an incorrect version
     BlobVersionNotSupported,
     /// System transactions are not supported post-regolith hardfork.
@@ -294,6 +398,7 @@ impl fmt::Display for InvalidTransaction {
             InvalidTransaction::CreateInitCodeSizeLimit => {
                 write!(f, "Create initcode size limit")
             }
+            InvalidTransaction::CreateNotAllowed => write!(f, "Contract creation is disabled"),
             InvalidTransaction::InvalidChainId => write!(f, "Invalid chain id"),
             InvalidTransaction::AccessListNotSupported => {
                 write!(f, "Access list not supported")
@@ -309,7 +414,9 @@ impl fmt::Display for InvalidTransaction {
             }
             InvalidTransaction::EmptyBlobs => write!(f, "Empty blobs"),
             InvalidTransaction::BlobCreateTransaction => write!(f, "Blob create transaction"),
-            InvalidTransaction::TooManyBlobs => write!(f, "Too many blobs"),
+            InvalidTransaction::TooManyBlobs { have, max } => {
+                write!(f, "Too many blobs, have {have}, max {max}")
+            }
             InvalidTransaction::BlobVersionNotSupported => write!(f, "Blob version not supported"),
             #[cfg(feature = "optimism")]
             InvalidTransaction::DepositSystemTxPostRegolith => {
@@ -371,16 +478,27 @@ pub enum SuccessReason {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HaltReason {
+    /// Execution ran out of gas, see [OutOfGasError] for the cause.
     OutOfGas(OutOfGasError),
+    /// Opcode is not defined.
     OpcodeNotFound,
+    /// Designated invalid opcode `0xFE` was executed.
     InvalidFEOpcode,
+    /// Jump destination is not a valid `JUMPDEST`.
     InvalidJump,
+    /// Opcode is not activated in the current spec.
     NotActivated,
+    /// Not enough items on the stack.
     StackUnderflow,
+    /// Stack limit of 1024 items exceeded.
     StackOverflow,
+    /// Return data offset is out of bounds.
     OutOfOffset,
+    /// Created address already has code or nonce.
     CreateCollision,
+    /// Precompile returned an error.
     PrecompileError,
+    /// Nonce overflowed on create.
     NonceOverflow,
     /// Create init code size exceeds limit (runtime).
     CreateContractSizeLimit,
@@ -388,12 +506,36 @@ pub enum HaltReason {
     CreateContractStartingWithEF,
     /// EIP-3860: Limit and meter initcode. Initcode size limit exceeded.
     CreateInitCodeSizeLimit,
+    /// Number of emitted logs exceeded [`crate::CfgEnv::max_logs`].
+    LogLimitReached,
+    /// Contract creation is disabled by [`crate::CfgEnv::disable_create`].
+    CreateNotAllowed,
+    /// Execution was interrupted before it finished, the result is partial.
+    ///
+    /// Interrupt of a frame stops all of its parent frames. Unlike other halts, state
+    /// changes made up to the interrupt are kept in the returned state and only the gas
+    /// used up to the interrupt is charged. Such state is not an outcome of the transaction
+    /// and should not be committed, see [`ExecutionResult::is_partial`].
+    Interrupted,
+    /// Create nesting depth exceeded [`crate::CfgEnv::max_create_depth`].
+    CreateDepthLimit,
+    /// Journal checkpoint depth exceeded [`crate::CfgEnv::max_journal_depth`].
+    JournalDepthLimit,
+    /// Data returned in the transaction exceeded [`crate::CfgEnv::max_return_data_size`].
+    ReturnDataLimit,
+    /// Single opcode charged more gas than the inspector allows.
+    GasBomb,
 
     /* Internal Halts that can be only found inside Inspector */
+    /// Balance overflowed on value transfer.
     OverflowPayment,
+    /// State changing opcode was executed inside static call.
     StateChangeDuringStaticCall,
+    /// Call with value was executed inside static call.
     CallNotAllowedInsideStatic,
+    /// Caller does not have enough balance for the value transfer.
     OutOfFunds,
+    /// Call depth limit exceeded.
     CallTooDeep,
 
     /* Optimism errors */
@@ -401,18 +543,19 @@ pub enum HaltReason {
     FailedDeposit,
 }
 
+/// Cause of the [HaltReason::OutOfGas] halt.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OutOfGasError {
-    // Basic OOG error
+    /// Basic OOG error
     Basic,
-    // Tried to expand past REVM limit
+    /// Tried to expand past REVM limit
     MemoryLimit,
-    // Basic OOG error from memory expansion
+    /// Basic OOG error from memory expansion
     Memory,
-    // Precompile threw OOG error
+    /// Precompile threw OOG error
     Precompile,
-    // When performing something that takes a U256 and casts down to a u64, if its too large this would fire
-    // i.e. in `as_usize_or_fail`
+    /// When performing something that takes a U256 and casts down to a u64, if its too large this would fire
+    /// i.e. in `as_usize_or_fail`
     InvalidOperand,
 }
@@ -5,6 +5,24 @@ use core::hash::{Hash, Hasher};
 /// EVM State is a mapping from addresses to accounts.
 pub type State = HashMap<Address, Account>;
 
+/// Serializes map with entries sorted by key.
+///
+/// Used with `deterministic` feature so that serialized state does not depend on
+/// the iteration order of the map.
+#[cfg(all(feature = "serde", feature = "deterministic"))]
+pub(crate) fn serialize_sorted<K, V, S>(
+    map: &HashMap<K, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    K: Ord + serde::Serialize,
+    V: serde::Serialize,
+    S: serde::Serializer,
+{
+    let sorted: alloc::collections::BTreeMap<&K, &V> = map.iter().collect();
+    serde::Serialize::serialize(&sorted, serializer)
+}
+
 /// Structure used for EIP-1153 transient storage.
 pub type TransientStorage = HashMap<(Address, U256), U256>;
 
@@ -17,6 +35,10 @@ pub struct Account {
     /// Balance, nonce, and code.
     pub info: AccountInfo,
     /// Storage cache
+    #[cfg_attr(
+        all(feature = "serde", feature = "deterministic"),
+        serde(serialize_with = "crate::state::serialize_sorted")
+    )]
     pub storage: Storage,
     /// Account status flags.
     pub status: AccountStatus,
@@ -41,6 +63,9 @@ bitflags! {
         /// used only for pre spurious dragon hardforks where existing and empty were two separate states.
         /// it became same state after EIP-161: State trie clearing
         const LoadedAsNotExisting = 0b0001000;
+        /// Storage was replaced in this transaction, storage values that are not present
+        /// are zero and are not fetched from database.
+        const StorageCleared = 0b0010000;
     }
 }
 
@@ -112,6 +137,16 @@ impl Account {
         self.status.contains(AccountStatus::Created)
     }
 
+    /// Mark account storage as cleared.
+    pub fn mark_storage_cleared(&mut self) {
+        self.status |= AccountStatus::StorageCleared;
+    }
+
+    /// Is account storage cleared in this transaction.
+    pub fn is_storage_cleared(&self) -> bool {
+        self.status.contains(AccountStatus::StorageCleared)
+    }
+
     /// Is account empty, check if nonce and balance are zero and code is empty.
     pub fn is_empty(&self) -> bool {
         self.info.is_empty()
@@ -147,6 +182,10 @@ pub struct StorageSlot {
     pub previous_or_original_value: U256,
     /// When loaded with sload present value is set to original value
     pub present_value: U256,
+    /// Explicit deletion marker of a slot changed to zero.
+    ///
+    /// Set only if [`CfgEnv::keep_zero_storage`](crate::CfgEnv::keep_zero_storage) is enabled.
+    pub deleted: bool,
 }
 
 impl StorageSlot {
@@ -155,6 +194,7 @@ impl StorageSlot {
         Self {
             previous_or_original_value: original,
             present_value: original,
+            deleted: false,
         }
     }
 
@@ -163,6 +203,7 @@ impl StorageSlot {
         Self {
             previous_or_original_value,
             present_value,
+            deleted: false,
         }
     }
 
@@ -171,6 +212,14 @@ impl StorageSlot {
         self.previous_or_original_value != self.present_value
     }
 
+    /// Returns true if the slot was changed to zero.
+    ///
+    /// Such slots are kept in the state and are deletions in the storage trie, see
+    /// [`StorageSlot::deleted`] for the explicit marker.
+    pub fn is_deleted(&self) -> bool {
+        self.is_changed() && self.present_value == U256::ZERO
+    }
+
     /// Returns the original value of the storage slot.
     pub fn original_value(&self) -> U256 {
         self.previous_or_original_value
@@ -288,7 +337,14 @@ impl AccountInfo {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Account, KECCAK_EMPTY, U256};
+    use crate::{Account, StorageSlot, KECCAK_EMPTY, U256};
+
+    #[test]
+    fn storage_slot_is_deleted() {
+        assert!(StorageSlot::new_changed(U256::from(5), U256::ZERO).is_deleted());
+        assert!(!StorageSlot::new_changed(U256::ZERO, U256::from(5)).is_deleted());
+        assert!(!StorageSlot::new(U256::ZERO).is_deleted());
+    }
 
     #[test]
     fn account_is_empty_balance() {
@@ -10,7 +10,35 @@ use revm::{
     Evm,
 };
 use revm_interpreter::{opcode::make_instruction_table, SharedMemory, EMPTY_SHARED_MEMORY};
-use std::time::Duration;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// System allocator that counts allocations and reallocations.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
 
 fn analysis(c: &mut Criterion) {
     let evm = Evm::builder()
@@ -90,6 +118,52 @@ fn transfer(c: &mut Criterion) {
     g.finish();
 }
 
+fn recursive_call(c: &mut Criterion) {
+    // calls itself `calldata[0..32]` times, every frame expands memory to 2KiB.
+    let bytecode = Bytecode::new_raw(bytes!(
+        "60003580156023576001900360005260016107e05260006000602060006000305af1505b00"
+    ));
+    let evm = Evm::builder()
+        .with_db(BenchmarkDB::new_bytecode(bytecode))
+        .modify_tx_env(|tx| {
+            tx.caller = address!("0000000000000000000000000000000000000001");
+            tx.transact_to = TransactTo::Call(address!("0000000000000000000000000000000000000000"));
+            // call depth.
+            tx.data = U256::from(512).to_be_bytes_vec().into();
+            tx.gas_limit = 30_000_000;
+        })
+        .build();
+
+    let mut g = c.benchmark_group("recursive_call");
+    g.noise_threshold(0.03).warm_up_time(Duration::from_secs(1));
+
+    let mut evm = evm.modify().with_memory_capacity(4 * 1024).build();
+    bench_allocations(&mut g, &mut evm, "transact/shared");
+
+    let mut evm = evm.modify().with_memory_pool().build();
+    bench_allocations(&mut g, &mut evm, "transact/pooled");
+
+    g.finish();
+}
+
+/// Reports allocations of one transaction and benches it.
+fn bench_allocations<EXT>(
+    g: &mut BenchmarkGroup<'_, WallTime>,
+    evm: &mut Evm<'_, EXT, BenchmarkDB>,
+    id: &str,
+) {
+    // first transaction fills the memory pool.
+    assert!(evm.transact().unwrap().result.is_success());
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    evm.transact().unwrap();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "{id}: {allocations} allocations, {} memory reallocations",
+        evm.context.evm.memory_reallocations()
+    );
+    g.bench_function(id, |b| b.iter(|| evm.transact().unwrap()));
+}
+
 fn bench_transact<EXT>(g: &mut BenchmarkGroup<'_, WallTime>, evm: &mut Evm<'_, EXT, BenchmarkDB>) {
     let state = match evm.context.evm.db.0.state {
         BytecodeState::Raw => "raw",
@@ -133,6 +207,7 @@ criterion_group!(
     analysis,
     snailtracer,
     transfer,
+    recursive_call,
 );
 criterion_main!(benches);
 
@@ -1,12 +1,15 @@
 use crate::{
-    db::{Database, DatabaseRef, EmptyDB, WrapDatabaseRef},
+    db::{Database, DatabaseCommit, DatabaseRef, EmptyDB, WrapDatabaseRef},
     handler::register,
+    interpreter::opcode::{make_instruction_table, InstructionTables},
+    precompile::{Precompile, Precompiles},
     primitives::{
-        BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg, HandlerCfg, SpecId, TxEnv,
+        AccountOverride, Address, BlockEnv, Bytes, CfgEnv, CfgEnvWithHandlerCfg, Env,
+        EnvWithHandlerCfg, HandlerCfg, LatestSpec, SpecId, State, TxEnv, B256, U256,
     },
-    Context, ContextWithHandlerCfg, Evm, Handler,
+    Context, ContextWithHandlerCfg, Evm, FrameOrResult, FrameResult, Handler, CALL_STACK_LIMIT,
 };
-use alloc::boxed::Box;
+use alloc::{boxed::Box, sync::Arc};
 use core::marker::PhantomData;
 
 /// Evm Builder allows building or modifying EVM.
@@ -314,6 +317,112 @@ impl<'a, BuilderStage, EXT, DB: Database> EvmBuilder<'a, BuilderStage, EXT, DB>
         }
     }
 
+    /// Adds a precompile at the given address on top of precompiles of the spec,
+    /// replacing the existing precompile at that address.
+    ///
+    /// Precompile addresses are warm and CREATE to them fails with a collision.
+    ///
+    /// When called, EvmBuilder will transition from SetGenericStage to HandlerStage.
+    pub fn with_precompile(
+        self,
+        address: Address,
+        precompile: Precompile,
+    ) -> EvmBuilder<'a, HandlerStage, EXT, DB> {
+        self.append_handler_register_box(Box::new(move |handler| {
+            let precompile = precompile.clone();
+            let old_handle = handler.pre_execution.load_precompiles.clone();
+            handler.pre_execution.load_precompiles = Arc::new(move || {
+                let mut precompiles = old_handle();
+                precompiles.inner.insert(address, precompile.clone());
+                precompiles
+            });
+        }))
+    }
+
+    /// Removes the precompile at the given address from precompiles of the spec.
+    ///
+    /// When called, EvmBuilder will transition from SetGenericStage to HandlerStage.
+    pub fn without_precompile(self, address: Address) -> EvmBuilder<'a, HandlerStage, EXT, DB> {
+        self.append_handler_register_box(Box::new(move |handler| {
+            let old_handle = handler.pre_execution.load_precompiles.clone();
+            handler.pre_execution.load_precompiles = Arc::new(move || {
+                let mut precompiles = old_handle();
+                precompiles.inner.remove(&address);
+                precompiles
+            });
+        }))
+    }
+
+    /// Replaces the whole precompile set, precompiles of the spec are not used.
+    ///
+    /// Precompiles added with [`EvmBuilder::with_precompile`] after this call are added
+    /// on top of the given set.
+    ///
+    /// When called, EvmBuilder will transition from SetGenericStage to HandlerStage.
+    pub fn with_precompiles(
+        self,
+        precompiles: Precompiles,
+    ) -> EvmBuilder<'a, HandlerStage, EXT, DB> {
+        self.append_handler_register_box(Box::new(move |handler| {
+            let precompiles = precompiles.clone();
+            handler.pre_execution.load_precompiles = Arc::new(move || precompiles.clone());
+        }))
+    }
+
+    /// Mocks the ECRECOVER precompile to return `address` for any signature of the
+    /// message hash `input_hash`, the first 32 bytes of the precompile input.
+    ///
+    /// ECRECOVER is still executed and charges its gas, only its output is replaced.
+    /// Inputs with other message hashes are recovered as usual.
+    ///
+    /// When called, EvmBuilder will transition from SetGenericStage to HandlerStage.
+    pub fn mock_ecrecover(
+        self,
+        input_hash: B256,
+        address: Address,
+    ) -> EvmBuilder<'a, HandlerStage, EXT, DB> {
+        let ecrecover = Address::with_last_byte(1);
+        self.append_handler_register_box(Box::new(move |handler| {
+            let old_handle = handler.execution.call.clone();
+            handler.execution.call = Arc::new(move |ctx, inputs, zero_address_call| {
+                let mut msg_hash = B256::ZERO;
+                let len = inputs.input.len().min(32);
+                msg_hash[..len].copy_from_slice(&inputs.input[..len]);
+                let is_mocked = inputs.contract == ecrecover
+                    && msg_hash == input_hash
+                    && ctx.evm.precompiles.contains(&ecrecover);
+
+                let mut frame_or_result = old_handle(ctx, inputs, zero_address_call);
+                if let FrameOrResult::Result(FrameResult::Call(outcome)) = &mut frame_or_result {
+                    if is_mocked && outcome.result.is_ok() {
+                        outcome.result.output =
+                            Bytes::copy_from_slice(address.into_word().as_slice());
+                    }
+                }
+                frame_or_result
+            });
+        }))
+    }
+
+    /// Enables every implemented opcode regardless of the configured [SpecId].
+    ///
+    /// Instructions are executed as in the latest spec, including their gas costs, while the
+    /// rest of the EVM follows the configured spec. This is non-standard behavior intended
+    /// for testing contracts that use new opcodes.
+    ///
+    /// It replaces the instruction table, so it needs to be called before registers that
+    /// modify instructions, like [`inspector_handle_register`](crate::inspector_handle_register).
+    ///
+    /// When called, EvmBuilder will transition from SetGenericStage to HandlerStage.
+    pub fn enable_all_eips(self) -> EvmBuilder<'a, HandlerStage, EXT, DB> {
+        self.append_handler_register_box(Box::new(|handler| {
+            handler.set_instruction_table(InstructionTables::Plain(make_instruction_table::<
+                _,
+                LatestSpec,
+            >()));
+        }))
+    }
+
     /// Sets specification Id , that will mark the version of EVM.
     /// It represent the hard fork of ethereum.
     ///
@@ -337,6 +446,19 @@ impl<'a, BuilderStage, EXT, DB: Database> EvmBuilder<'a, BuilderStage, EXT, DB>
         self
     }
 
+    /// Applies `state` to the database as the starting point of execution.
+    ///
+    /// State is committed with [DatabaseCommit::commit], so only touched accounts are applied.
+    /// For [crate::db::CacheDB] seeded accounts and storage are cached and override the
+    /// backing database, which is not queried for them.
+    pub fn with_state(mut self, state: State) -> Self
+    where
+        DB: DatabaseCommit,
+    {
+        self.context.evm.db.commit(state);
+        self
+    }
+
     /// Allows modification of external context.
     pub fn modify_external_context(mut self, f: impl FnOnce(&mut EXT)) -> Self {
         f(&mut self.context.external);
@@ -379,12 +501,93 @@ impl<'a, BuilderStage, EXT, DB: Database> EvmBuilder<'a, BuilderStage, EXT, DB>
         self
     }
 
+    /// Sets the most commonly used fields of Evm's Block Environment.
+    ///
+    /// Other fields are left unchanged, see [BlockEnv] for their defaults.
+    pub fn with_block(
+        mut self,
+        number: U256,
+        timestamp: U256,
+        gas_limit: U256,
+        coinbase: Address,
+        basefee: U256,
+    ) -> Self {
+        let block = &mut self.context.evm.env.block;
+        block.number = number;
+        block.timestamp = timestamp;
+        block.gas_limit = gas_limit;
+        block.coinbase = coinbase;
+        block.basefee = basefee;
+        self
+    }
+
     /// Allows modification of Evm's Config Environment.
     pub fn modify_cfg_env(mut self, f: impl FnOnce(&mut CfgEnv)) -> Self {
         f(&mut self.context.evm.env.cfg);
         self
     }
 
+    /// Sets the maximum depth of nested call and create frames, capped to the consensus
+    /// limit of [CALL_STACK_LIMIT].
+    ///
+    /// Frame that would be deeper fails the same way as a frame exceeding the consensus limit.
+    pub fn with_max_call_depth(mut self, max_depth: u16) -> Self {
+        self.context.evm.env.cfg.max_call_depth = max_depth.min(CALL_STACK_LIMIT as u16);
+        self
+    }
+
+    /// Overrides the state of the account before every transaction is executed.
+    ///
+    /// Database is not changed, overridden values are part of the transaction state.
+    /// See [AccountOverride].
+    pub fn with_state_override(
+        mut self,
+        address: Address,
+        account_override: AccountOverride,
+    ) -> Self {
+        self.context
+            .evm
+            .env
+            .cfg
+            .state_overrides
+            .insert(address, account_override);
+        self
+    }
+
+    /// Sets the initial capacity in bytes of the memory shared between call frames.
+    ///
+    /// Memory of a returned frame is kept in the shared buffer and reused by the next frame,
+    /// so the capacity only needs to cover the peak memory of all frames on the call stack.
+    /// See [`CfgEnv::initial_memory_capacity`](crate::primitives::CfgEnv::initial_memory_capacity).
+    pub fn with_memory_capacity(mut self, capacity: usize) -> Self {
+        self.context.evm.env.cfg.initial_memory_capacity = Some(capacity);
+        self
+    }
+
+    /// Gives every call frame its own memory buffer taken from a pool kept per call depth.
+    ///
+    /// Buffer of a returned frame is reused by the next frame at the same depth instead of
+    /// being dropped. See [`CfgEnv::memory_pool`](crate::primitives::CfgEnv::memory_pool).
+    pub fn with_memory_pool(mut self) -> Self {
+        self.context.evm.env.cfg.memory_pool = true;
+        self
+    }
+
+    /// Sets accounts that are loaded from the database in one batch before every
+    /// transaction is executed, with the access list accounts.
+    ///
+    /// Preloaded accounts are cold, see [`EvmBuilder::warm_preload_accounts`].
+    pub fn preload_accounts(mut self, addresses: &[Address]) -> Self {
+        self.context.evm.env.cfg.preload_accounts = addresses.to_vec();
+        self
+    }
+
+    /// Sets whether preloaded accounts are warm, as if they were in the access list.
+    pub fn warm_preload_accounts(mut self, warm: bool) -> Self {
+        self.context.evm.env.cfg.warm_preload_accounts = warm;
+        self
+    }
+
     /// Clears Environment of EVM.
     pub fn with_clear_env(mut self) -> Self {
         self.context.evm.env.clear();
@@ -412,6 +615,7 @@ impl<'a, BuilderStage, EXT, DB: Database> EvmBuilder<'a, BuilderStage, EXT, DB>
 #[cfg(test)]
 mod test {
     use super::SpecId;
+    use crate::test_utils::{contract_info, evm_calling};
     use crate::{
         db::EmptyDB, inspector::inspector_handle_register, inspectors::NoOpInspector, Context, Evm,
         EvmContext,
@@ -491,4 +695,485 @@ mod test {
             .modify_tx_env(|tx| tx.chain_id = Some(2))
             .build();
     }
+
+    #[test]
+    fn build_with_state() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{address, Account, AccountInfo, State, TransactTo, U256},
+        };
+
+        let caller = address!("1000000000000000000000000000000000000000");
+        let recipient = address!("2000000000000000000000000000000000000000");
+
+        let mut account = Account::from(AccountInfo {
+            balance: U256::from(1_000),
+            ..Default::default()
+        });
+        account.mark_touch();
+        let state = State::from_iter([(caller, account)]);
+
+        let mut evm = Evm::builder()
+            .with_db(InMemoryDB::default())
+            .with_state(state)
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = caller;
+                tx.transact_to = TransactTo::Call(recipient);
+                tx.value = U256::from(100);
+                tx.gas_limit = 21_000;
+            })
+            .build();
+
+        let state = evm.transact().unwrap().state;
+        assert_eq!(state[&caller].info.balance, U256::from(900));
+        assert_eq!(state[&recipient].info.balance, U256::from(100));
+    }
+
+    #[test]
+    fn build_with_preload_accounts() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::opcode,
+            primitives::{AccountInfo, Address, Bytecode, TransactTo, B256, U256},
+            Database,
+        };
+        use core::convert::Infallible;
+
+        /// Database that records addresses of loaded accounts.
+        struct CountingDB {
+            inner: BenchmarkDB,
+            loaded: Vec<Address>,
+        }
+
+        impl Database for CountingDB {
+            type Error = Infallible;
+
+            fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+                self.loaded.push(address);
+                self.inner.basic(address)
+            }
+
+            fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+                self.inner.code_by_hash(code_hash)
+            }
+
+            fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+                self.inner.storage(address, index)
+            }
+
+            fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+                self.inner.block_hash(number)
+            }
+        }
+
+        let first = Address::with_last_byte(0xa1);
+        let second = Address::with_last_byte(0xa2);
+        let caller = Address::with_last_byte(0x10);
+        let coinbase = Address::with_last_byte(0xc0);
+
+        // reads balances of both accounts.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0xa1,
+                opcode::BALANCE,
+                opcode::POP,
+                opcode::PUSH1,
+                0xa2,
+                opcode::BALANCE,
+                opcode::POP,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+
+        let transact = |warm: bool| {
+            let mut evm = Evm::builder()
+                .with_db(CountingDB {
+                    inner: BenchmarkDB::new_bytecode(bytecode.clone()),
+                    loaded: Vec::new(),
+                })
+                .preload_accounts(&[first, second])
+                .warm_preload_accounts(warm)
+                .modify_block_env(|block| block.coinbase = coinbase)
+                .modify_tx_env(|tx| {
+                    tx.clear();
+                    tx.caller = caller;
+                    tx.transact_to = TransactTo::Call(Address::ZERO);
+                    tx.gas_limit = 100_000;
+                })
+                .build();
+            let result = evm.transact().unwrap().result;
+            assert!(result.is_success());
+            (result.gas_used(), evm.into_context().evm.db.loaded)
+        };
+
+        let (cold_gas, loaded) = transact(false);
+        // accounts are loaded once, after the caller and before the called contract.
+        let position = |address| loaded.iter().position(|loaded| *loaded == address).unwrap();
+        assert_eq!(loaded.iter().filter(|a| **a == first).count(), 1);
+        assert_eq!(loaded.iter().filter(|a| **a == second).count(), 1);
+        assert!(position(caller) < position(first));
+        assert!(position(first) < position(second));
+        assert!(position(second) < position(Address::ZERO));
+
+        // warm accounts are cheaper to access.
+        let (warm_gas, _) = transact(true);
+        assert_eq!(cold_gas - warm_gas, 2 * 2500);
+    }
+
+    #[test]
+    fn build_with_precompile() {
+        use crate::{
+            precompile::{Precompile, PrecompileResult},
+            primitives::{address, Bytes, TransactTo},
+        };
+
+        fn static_precompile(_input: &Bytes, _gas_limit: u64) -> PrecompileResult {
+            Ok((100, Bytes::from_static(&[1, 2, 3])))
+        }
+
+        let custom = address!("00000000000000000000000000000000000000ff");
+        let ecrecover = address!("0000000000000000000000000000000000000001");
+        let mut evm = Evm::builder()
+            .with_precompile(custom, Precompile::Standard(static_precompile))
+            .with_spec_id(SpecId::CANCUN)
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TransactTo::Call(custom);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        assert_eq!(result.gas_used(), 21_100);
+        assert_eq!(result.output().unwrap().as_ref(), &[1, 2, 3]);
+        // custom precompile is added on top of the spec precompiles.
+        assert!(evm.context.evm.precompiles.contains(&ecrecover));
+
+        let mut evm = evm.modify().without_precompile(ecrecover).build();
+        evm.transact().unwrap();
+        assert!(evm.context.evm.precompiles.contains(&custom));
+        assert!(!evm.context.evm.precompiles.contains(&ecrecover));
+    }
+
+    #[test]
+    fn build_with_block() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::opcode,
+            primitives::{Address, Bytecode, TransactTo, U256},
+        };
+
+        // returns NUMBER, TIMESTAMP and CHAINID.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::NUMBER,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::TIMESTAMP,
+                opcode::PUSH1,
+                0x20,
+                opcode::MSTORE,
+                opcode::CHAINID,
+                opcode::PUSH1,
+                0x40,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x60,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ]
+            .into(),
+        );
+
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .with_block(
+                U256::from(100),
+                U256::from(1_700_000_000),
+                U256::from(30_000_000),
+                Address::with_last_byte(0xff),
+                U256::from(7),
+            )
+            .modify_cfg_env(|cfg| cfg.chain_id = 10)
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TransactTo::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+                tx.gas_price = U256::from(7);
+            })
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        let output = result.output().unwrap();
+        assert_eq!(U256::from_be_slice(&output[..32]), U256::from(100));
+        assert_eq!(
+            U256::from_be_slice(&output[32..64]),
+            U256::from(1_700_000_000)
+        );
+        assert_eq!(U256::from_be_slice(&output[64..]), U256::from(10));
+    }
+
+    #[test]
+    fn build_with_mocked_ecrecover() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::opcode,
+            primitives::{address, b256, Address, Bytecode, B256},
+        };
+
+        let msg_hash = b256!("1111111111111111111111111111111111111111111111111111111111111111");
+        let signer = address!("00000000000000000000000000000000000000aa");
+
+        // recovers the signer of `msg_hash` with v = 27 and zero r and s, and returns it.
+        let mut code = vec![opcode::PUSH32];
+        code.extend_from_slice(msg_hash.as_slice());
+        code.extend([
+            opcode::PUSH1,
+            0x00,
+            opcode::MSTORE,
+            opcode::PUSH1,
+            27,
+            opcode::PUSH1,
+            0x20,
+            opcode::MSTORE,
+            opcode::PUSH1,
+            0x20,
+            opcode::PUSH1,
+            0x80,
+            opcode::PUSH1,
+            0x80,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x01,
+            opcode::GAS,
+            opcode::STATICCALL,
+            opcode::POP,
+            opcode::PUSH1,
+            0x20,
+            opcode::PUSH1,
+            0x80,
+            opcode::RETURN,
+        ]);
+        let bytecode = Bytecode::new_raw(code.into());
+
+        let run = |mocked_hash: B256| {
+            let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode.clone()), Address::ZERO)
+                .mock_ecrecover(mocked_hash, signer)
+                .build();
+            let result = evm.transact().unwrap().result;
+            assert!(result.is_success());
+            result.output().unwrap().clone()
+        };
+
+        assert_eq!(run(msg_hash).as_ref(), signer.into_word().as_slice());
+        // signature is invalid, so nothing is recovered without the mock.
+        assert_eq!(run(B256::ZERO).as_ref(), B256::ZERO.as_slice());
+    }
+
+    #[test]
+    fn build_with_state_override() {
+        use crate::{
+            db::InMemoryDB,
+            interpreter::opcode,
+            primitives::{address, AccountOverride, Bytecode, U256},
+        };
+        use alloc::collections::BTreeMap;
+
+        let contract = address!("2000000000000000000000000000000000000000");
+        let mut db = InMemoryDB::default();
+        // stops without output.
+        let code = Bytecode::new_raw(vec![opcode::STOP].into());
+        db.insert_account_info(contract, contract_info(code));
+        db.insert_account_storage(contract, U256::from(1), U256::from(5))
+            .unwrap();
+        db.insert_account_storage(contract, U256::from(2), U256::from(6))
+            .unwrap();
+
+        // returns sum of slots 1 and 2.
+        let override_code = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x02,
+                opcode::SLOAD,
+                opcode::PUSH1,
+                0x01,
+                opcode::SLOAD,
+                opcode::ADD,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ]
+            .into(),
+        );
+        let slots = BTreeMap::from([(U256::from(1), U256::from(10))]);
+
+        let run = |account_override: AccountOverride| {
+            let mut evm = evm_calling(db.clone(), contract)
+                .with_state_override(contract, account_override)
+                .build();
+            let result = evm.transact().unwrap().result;
+            assert!(result.is_success());
+            // database is not changed.
+            assert_eq!(
+                evm.context.evm.db.accounts[&contract].storage[&U256::from(1)],
+                U256::from(5)
+            );
+            U256::from_be_slice(result.output().unwrap())
+        };
+
+        // slot 1 is patched, slot 2 is read from the database.
+        let state_diff = AccountOverride {
+            code: Some(override_code.clone()),
+            state_diff: Some(slots.clone()),
+            ..Default::default()
+        };
+        assert_eq!(run(state_diff), U256::from(10 + 6));
+
+        // slot 2 is cleared with the rest of the storage.
+        let state = AccountOverride {
+            code: Some(override_code),
+            state: Some(slots),
+            ..Default::default()
+        };
+        assert_eq!(run(state), U256::from(10));
+    }
+
+    #[test]
+    fn build_with_memory_capacity() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::opcode,
+            primitives::{Address, Bytecode},
+        };
+
+        // expands memory by 1KiB and calls itself until it runs out of gas.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH2,
+                0x04,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::ADDRESS,
+                opcode::GAS,
+                opcode::CALL,
+                opcode::POP,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+
+        let build = |capacity: Option<usize>| {
+            let builder = evm_calling(BenchmarkDB::new_bytecode(bytecode.clone()), Address::ZERO)
+                .modify_tx_env(|tx| tx.gas_limit = 1_000_000);
+            match capacity {
+                Some(capacity) => builder.with_memory_capacity(capacity).build(),
+                None => builder.build(),
+            }
+        };
+
+        let mut evm = build(None);
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        assert!(evm.context.evm.memory_reallocations() > 0);
+
+        let mut evm = build(Some(1 << 20));
+        assert_eq!(evm.cfg().initial_memory_capacity, Some(1 << 20));
+        // memory contents and gas do not depend on the capacity.
+        assert_eq!(evm.transact().unwrap().result, result);
+        assert_eq!(evm.context.evm.memory_reallocations(), 0);
+    }
+
+    #[test]
+    fn build_with_all_eips_enabled() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::opcode,
+            primitives::{Address, Bytecode, ExecutionResult, HaltReason, U256},
+        };
+
+        // copies word 0x2a with MCOPY after PUSH0, reads 7 back from transient storage
+        // and returns both words.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x2a,
+                opcode::PUSH0,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH0,
+                opcode::PUSH1,
+                0x20,
+                opcode::MCOPY,
+                opcode::PUSH1,
+                0x07,
+                opcode::PUSH1,
+                0x01,
+                opcode::TSTORE,
+                opcode::PUSH1,
+                0x01,
+                opcode::TLOAD,
+                opcode::PUSH1,
+                0x40,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x40,
+                opcode::PUSH1,
+                0x20,
+                opcode::RETURN,
+            ]
+            .into(),
+        );
+        let builder = || {
+            evm_calling(BenchmarkDB::new_bytecode(bytecode.clone()), Address::ZERO)
+                .with_spec_id(SpecId::MERGE)
+        };
+
+        // PUSH0 is not activated in Merge.
+        let result = builder().build().transact().unwrap().result;
+        assert!(matches!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::NotActivated,
+                ..
+            }
+        ));
+
+        let result = builder()
+            .enable_all_eips()
+            .build()
+            .transact()
+            .unwrap()
+            .result;
+        assert!(result.is_success());
+        let output = result.output().unwrap();
+        assert_eq!(U256::from_be_slice(&output[..32]), U256::from(0x2a));
+        assert_eq!(U256::from_be_slice(&output[32..]), U256::from(7));
+    }
 }
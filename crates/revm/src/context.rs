@@ -1,14 +1,15 @@
 use crate::{
     db::{Database, EmptyDB},
+    handler::{CreateAddressHandle, TxFees},
     interpreter::{
         analysis::to_analysed, gas, return_ok, CallInputs, Contract, CreateInputs, Gas,
         InstructionResult, Interpreter, InterpreterResult, MAX_CODE_SIZE,
     },
-    journaled_state::JournaledState,
+    journaled_state::{AccessListSnapshot, JournaledState},
     precompile::{Precompile, Precompiles},
     primitives::{
         keccak256, Address, AnalysisKind, Bytecode, Bytes, CreateScheme, EVMError, Env, HandlerCfg,
-        HashSet, Spec, SpecId, SpecId::*, B256, U256,
+        HashSet, Spec, SpecId, SpecId::*, B256, KECCAK_EMPTY, U256,
     },
     FrameOrResult, JournalCheckpoint, CALL_STACK_LIMIT,
 };
@@ -96,6 +97,14 @@ pub struct EvmContext<DB: Database> {
     pub error: Option<DB::Error>,
     /// Precompiles that are available for evm.
     pub precompiles: Precompiles,
+    /// Number of shared memory reallocations in the last transaction.
+    pub(crate) memory_reallocations: usize,
+    /// Memory buffers kept between transactions, see [`CfgEnv::memory_pool`](crate::primitives::CfgEnv::memory_pool).
+    pub(crate) memory_pool: Vec<Vec<u8>>,
+    /// Fuel used in the last transaction, see [`fuel_handle_register`](crate::handler::fuel_handle_register).
+    pub(crate) fuel_used: u64,
+    /// Fees of the current transaction, see [`PreExecutionHandler::tx_fees`](crate::handler::PreExecutionHandler::tx_fees).
+    pub(crate) tx_fees: TxFees,
     /// Used as temporary value holder to store L1 block info.
     #[cfg(feature = "optimism")]
     pub l1_block_info: Option<crate::optimism::L1BlockInfo>,
@@ -112,6 +121,10 @@ where
             db: self.db.clone(),
             error: self.error.clone(),
             precompiles: self.precompiles.clone(),
+            memory_reallocations: self.memory_reallocations,
+            memory_pool: Vec::new(),
+            fuel_used: self.fuel_used,
+            tx_fees: self.tx_fees,
             #[cfg(feature = "optimism")]
             l1_block_info: self.l1_block_info.clone(),
         }
@@ -126,6 +139,10 @@ impl<DB: Database> EvmContext<DB> {
             db,
             error: None,
             precompiles: self.precompiles,
+            memory_reallocations: self.memory_reallocations,
+            memory_pool: self.memory_pool,
+            fuel_used: self.fuel_used,
+            tx_fees: self.tx_fees,
             #[cfg(feature = "optimism")]
             l1_block_info: self.l1_block_info,
         }
@@ -138,6 +155,10 @@ impl<DB: Database> EvmContext<DB> {
             db,
             error: None,
             precompiles: Precompiles::default(),
+            memory_reallocations: 0,
+            memory_pool: Vec::new(),
+            fuel_used: 0,
+            tx_fees: TxFees::default(),
             #[cfg(feature = "optimism")]
             l1_block_info: None,
         }
@@ -151,11 +172,41 @@ impl<DB: Database> EvmContext<DB> {
             db,
             error: None,
             precompiles: Precompiles::default(),
+            memory_reallocations: 0,
+            memory_pool: Vec::new(),
+            fuel_used: 0,
+            tx_fees: TxFees::default(),
             #[cfg(feature = "optimism")]
             l1_block_info: None,
         }
     }
 
+    /// Returns the number of times the memory shared between call frames grew its capacity
+    /// in the last transaction.
+    ///
+    /// It depends only on the host allocation and does not affect gas or results.
+    pub fn memory_reallocations(&self) -> usize {
+        self.memory_reallocations
+    }
+
+    /// Returns the fuel used in the last transaction.
+    ///
+    /// One unit of fuel is charged for every executed instruction, regardless of its gas cost.
+    /// Fuel is counted only if [`fuel_handle_register`](crate::handler::fuel_handle_register)
+    /// is appended to the handler.
+    pub fn fuel_used(&self) -> u64 {
+        self.fuel_used
+    }
+
+    /// Returns fees of the current transaction.
+    ///
+    /// They are set by [`Evm::transact`](crate::Evm::transact) before the caller is validated
+    /// and used to check the caller balance, deduct and reimburse the caller and reward
+    /// the beneficiary.
+    pub fn tx_fees(&self) -> &TxFees {
+        &self.tx_fees
+    }
+
     /// Returns the configured EVM spec ID.
     pub const fn spec_id(&self) -> SpecId {
         self.journaled_state.spec
@@ -177,10 +228,101 @@ impl<DB: Database> EvmContext<DB> {
             self.journaled_state
                 .initial_account_load(*address, slots, &mut self.db)
                 .map_err(EVMError::Database)?;
+            // access list accounts are warm even if they were preloaded as cold.
+            self.journaled_state.cold_accounts.remove(address);
+        }
+        Ok(())
+    }
+
+    /// Loads accounts from [`CfgEnv::preload_accounts`](crate::primitives::CfgEnv::preload_accounts).
+    ///
+    /// Accounts that were not already loaded are marked cold unless
+    /// [`CfgEnv::warm_preload_accounts`](crate::primitives::CfgEnv::warm_preload_accounts) is set.
+    pub fn load_preload_accounts(&mut self) -> Result<(), EVMError<DB::Error>> {
+        let warm = self.env.cfg.warm_preload_accounts;
+        for address in self.env.cfg.preload_accounts.iter() {
+            let is_loaded = self.journaled_state.state.contains_key(address);
+            self.journaled_state
+                .initial_account_load(*address, &[], &mut self.db)
+                .map_err(EVMError::Database)?;
+            if !warm
+                && !is_loaded
+                && !self
+                    .journaled_state
+                    .warm_preloaded_addresses
+                    .contains(address)
+            {
+                self.journaled_state.cold_accounts.insert(*address);
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies [`CfgEnv::state_overrides`](crate::primitives::CfgEnv::state_overrides) to
+    /// the journaled state.
+    ///
+    /// Overridden accounts that were not already loaded are cold. Slots that were already
+    /// loaded stay warm. Overrides are journaled, see [`JournaledState::override_account`].
+    pub fn apply_state_overrides(&mut self) -> Result<(), EVMError<DB::Error>> {
+        for (address, account_override) in self.env.cfg.state_overrides.iter() {
+            let is_loaded = self.journaled_state.state.contains_key(address);
+            self.journaled_state
+                .initial_account_load(*address, &[], &mut self.db)
+                .map_err(EVMError::Database)?;
+            self.journaled_state
+                .override_account(*address, account_override);
+            if !is_loaded
+                && !self
+                    .journaled_state
+                    .warm_preloaded_addresses
+                    .contains(address)
+            {
+                self.journaled_state.cold_accounts.insert(*address);
+            }
         }
         Ok(())
     }
 
+    /// Returns snapshot of currently warm accounts and storage slots.
+    #[inline]
+    pub fn access_list_snapshot(&self) -> AccessListSnapshot {
+        self.journaled_state.access_list_snapshot()
+    }
+
+    /// Restores warm accounts and storage slots from the snapshot.
+    ///
+    /// Accounts and slots warmed after the snapshot are cold again. Storage and balances are not changed.
+    #[inline]
+    pub fn restore_access_list(&mut self, snapshot: AccessListSnapshot) {
+        self.journaled_state.restore_access_list(snapshot)
+    }
+
+    /// Creates a checkpoint of the journaled state, for example to speculatively execute
+    /// calls and discard their changes.
+    ///
+    /// Checkpoint is closed with [`EvmContext::commit_checkpoint`] or
+    /// [`EvmContext::revert_checkpoint`]. Checkpoints are nested and have to be closed in reverse
+    /// order. Each open checkpoint increments the call depth.
+    #[inline]
+    pub fn checkpoint(&mut self) -> JournalCheckpoint {
+        self.journaled_state.checkpoint()
+    }
+
+    /// Commits the last checkpoint, its changes are kept.
+    #[inline]
+    pub fn commit_checkpoint(&mut self) {
+        self.journaled_state.checkpoint_commit()
+    }
+
+    /// Reverts all changes made after the checkpoint, same as for a reverted sub call.
+    ///
+    /// Storage, balances, nonces, logs, selfdestructs and touched and created flags of accounts are
+    /// restored.
+    #[inline]
+    pub fn revert_checkpoint(&mut self, checkpoint: JournalCheckpoint) {
+        self.journaled_state.checkpoint_revert(checkpoint)
+    }
+
     /// Return environment.
     pub fn env(&mut self) -> &mut Env {
         &mut self.env
@@ -221,7 +363,11 @@ impl<DB: Database> EvmContext<DB> {
         Some((acc.info.code.clone().unwrap(), is_cold))
     }
 
-    /// Get code hash of address.
+    /// Get code hash of address as returned by EXTCODEHASH.
+    ///
+    /// Accounts without code return [`CfgEnv::empty_extcodehash`] if it is set.
+    ///
+    /// [`CfgEnv::empty_extcodehash`]: crate::primitives::CfgEnv::empty_extcodehash
     pub fn code_hash(&mut self, address: Address) -> Option<(B256, bool)> {
         let (acc, is_cold) = self
             .journaled_state
@@ -231,6 +377,11 @@ impl<DB: Database> EvmContext<DB> {
         if acc.is_empty() {
             return Some((B256::ZERO, is_cold));
         }
+        if acc.info.code_hash == KECCAK_EMPTY {
+            if let Some(empty_extcodehash) = self.env.cfg.empty_extcodehash {
+                return Some((empty_extcodehash, is_cold));
+            }
+        }
 
         Some((acc.info.code_hash, is_cold))
     }
@@ -268,7 +419,15 @@ impl<DB: Database> EvmContext<DB> {
     }
 
     /// Make create frame.
-    pub fn make_create_frame(&mut self, spec_id: SpecId, inputs: &CreateInputs) -> FrameOrResult {
+    ///
+    /// Address of the created contract is derived with `create_address` if it is set,
+    /// otherwise mainnet CREATE and CREATE2 rules are used.
+    pub fn make_create_frame(
+        &mut self,
+        spec_id: SpecId,
+        inputs: &CreateInputs,
+        create_address: Option<&CreateAddressHandle<'_>>,
+    ) -> FrameOrResult {
         // Prepare crate.
         let gas = Gas::new(inputs.gas_limit);
 
@@ -283,10 +442,25 @@ impl<DB: Database> EvmContext<DB> {
             )
         };
 
+        // Check if contract creation is allowed
+        if self.env.cfg.disable_create {
+            return return_error(InstructionResult::CreateNotAllowed);
+        }
+
         // Check depth
-        if self.journaled_state.depth() > CALL_STACK_LIMIT {
+        if self.call_depth_exceeded() {
             return return_error(InstructionResult::CallTooDeep);
         }
+        if self.journal_depth_exceeded() {
+            return return_error(InstructionResult::JournalDepthLimit);
+        }
+
+        // Check create nesting depth
+        if let Some(max_create_depth) = self.env.cfg.max_create_depth {
+            if self.journaled_state.create_depth as u64 >= max_create_depth {
+                return return_error(InstructionResult::CreateDepthLimit);
+            }
+        }
 
         // Fetch balance of caller.
         let Some((caller_balance, _)) = self.balance(inputs.caller) else {
@@ -308,9 +482,15 @@ impl<DB: Database> EvmContext<DB> {
 
         // Create address
         let mut init_code_hash = B256::ZERO;
-        let created_address = match inputs.scheme {
-            CreateScheme::Create => inputs.caller.create(old_nonce),
-            CreateScheme::Create2 { salt } => {
+        let created_address = match (create_address, inputs.scheme) {
+            (Some(create_address), scheme) => {
+                if let CreateScheme::Create2 { .. } = scheme {
+                    init_code_hash = keccak256(&inputs.init_code);
+                }
+                create_address(inputs.caller, old_nonce, &inputs.init_code, scheme)
+            }
+            (None, CreateScheme::Create) => inputs.caller.create(old_nonce),
+            (None, CreateScheme::Create2 { salt }) => {
                 init_code_hash = keccak256(&inputs.init_code);
                 inputs.caller.create2(salt.to_be_bytes(), init_code_hash)
             }
@@ -339,6 +519,8 @@ impl<DB: Database> EvmContext<DB> {
             }
         };
 
+        self.journaled_state.create_depth += 1;
+
         let bytecode = Bytecode::new_raw(inputs.init_code.clone());
 
         let contract = Box::new(Contract::new(
@@ -357,6 +539,25 @@ impl<DB: Database> EvmContext<DB> {
         )
     }
 
+    /// Returns true if a new frame would be deeper than the call depth limit,
+    /// [CfgEnv::max_call_depth](crate::primitives::CfgEnv::max_call_depth) capped to
+    /// [CALL_STACK_LIMIT].
+    #[inline]
+    fn call_depth_exceeded(&self) -> bool {
+        let max_depth = CALL_STACK_LIMIT.min(self.env.cfg.max_call_depth as u64);
+        self.journaled_state.depth() > max_depth
+    }
+
+    /// Returns true if a new journal checkpoint would exceed
+    /// [CfgEnv::max_journal_depth](crate::primitives::CfgEnv::max_journal_depth).
+    #[inline]
+    fn journal_depth_exceeded(&self) -> bool {
+        self.env
+            .cfg
+            .max_journal_depth
+            .is_some_and(|max| self.journaled_state.depth >= max)
+    }
+
     /// Make call frame
     pub fn make_call_frame(&mut self, inputs: &CallInputs) -> FrameOrResult {
         let gas = Gas::new(inputs.gas_limit);
@@ -373,9 +574,12 @@ impl<DB: Database> EvmContext<DB> {
         };
 
         // Check depth
-        if self.journaled_state.depth() > CALL_STACK_LIMIT {
+        if self.call_depth_exceeded() {
             return return_result(InstructionResult::CallTooDeep);
         }
+        if self.journal_depth_exceeded() {
+            return return_result(InstructionResult::JournalDepthLimit);
+        }
 
         let account = match self
             .journaled_state
@@ -390,6 +594,10 @@ impl<DB: Database> EvmContext<DB> {
         let code_hash = account.info.code_hash();
         let bytecode = account.info.code.clone().unwrap_or_default();
 
+        // transaction to a precompile can be configured to be a plain transfer.
+        let executes_precompile =
+            self.journaled_state.depth() != 0 || self.env.cfg.top_level_precompile_calls;
+
         // Create subroutine checkpoint
         let checkpoint = self.journaled_state.checkpoint();
 
@@ -410,7 +618,11 @@ impl<DB: Database> EvmContext<DB> {
             return return_result(e);
         }
 
-        if let Some(precompile) = self.precompiles.get(&inputs.contract) {
+        if let Some(precompile) = self
+            .precompiles
+            .get(&inputs.contract)
+            .filter(|_| executes_precompile)
+        {
             let result = self.call_precompile(precompile, inputs, gas);
             if matches!(result.result, return_ok!()) {
                 self.journaled_state.checkpoint_commit();
@@ -458,6 +670,13 @@ impl<DB: Database> EvmContext<DB> {
 
         match out {
             Ok((gas_used, data)) => {
+                let gas_used = match self.env.cfg.precompile_gas_discount.get(&inputs.contract) {
+                    Some(&discount) => {
+                        let charged = 1000 - discount.min(1000);
+                        (gas_used as u128 * charged as u128 / 1000) as u64
+                    }
+                    None => gas_used,
+                };
                 if result.gas.record_cost(gas_used) {
                     result.result = InstructionResult::Return;
                     result.output = data;
@@ -483,8 +702,11 @@ impl<DB: Database> EvmContext<DB> {
         interpreter_result: &InterpreterResult,
         journal_checkpoint: JournalCheckpoint,
     ) {
-        // revert changes or not.
-        if matches!(interpreter_result.result, return_ok!()) {
+        // revert changes or not. Changes of the interrupted frame are kept.
+        if matches!(
+            interpreter_result.result,
+            return_ok!() | InstructionResult::Interrupted
+        ) {
             self.journaled_state.checkpoint_commit();
         } else {
             self.journaled_state.checkpoint_revert(journal_checkpoint);
@@ -499,6 +721,12 @@ impl<DB: Database> EvmContext<DB> {
         address: Address,
         journal_checkpoint: JournalCheckpoint,
     ) {
+        self.journaled_state.create_depth = self.journaled_state.create_depth.saturating_sub(1);
+        // changes of the interrupted frame are kept, but code is not deployed.
+        if interpreter_result.result == InstructionResult::Interrupted {
+            self.journaled_state.checkpoint_commit();
+            return;
+        }
         // if return is not ok revert and return.
         if !matches!(interpreter_result.result, return_ok!()) {
             self.journaled_state.checkpoint_revert(journal_checkpoint);
@@ -631,6 +859,10 @@ pub(crate) mod test_utils {
             db,
             error: None,
             precompiles: Precompiles::default(),
+            memory_reallocations: 0,
+            memory_pool: Vec::new(),
+            fuel_used: 0,
+            tx_fees: TxFees::default(),
             #[cfg(feature = "optimism")]
             l1_block_info: None,
         }
@@ -644,6 +876,10 @@ pub(crate) mod test_utils {
             db,
             error: None,
             precompiles: Precompiles::default(),
+            memory_reallocations: 0,
+            memory_pool: Vec::new(),
+            fuel_used: 0,
+            tx_fees: TxFees::default(),
             #[cfg(feature = "optimism")]
             l1_block_info: None,
         }
@@ -654,7 +890,18 @@ pub(crate) mod test_utils {
 mod tests {
     use super::*;
     use crate::db::{CacheDB, EmptyDB};
-    use crate::primitives::address;
+    use crate::primitives::Log;
+    use crate::test_utils::{db_with_contract, evm_calling};
+    use crate::{
+        db::{BenchmarkDB, InMemoryDB},
+        inspector::inspector_handle_register,
+        interpreter::{opcode, CallInputs, CallOutcome, InstructionResult},
+        primitives::{
+            address, Address, Bytecode, Bytes, InvalidTransaction, ResultAndState, TransactTo,
+            B256, U256,
+        },
+        Evm, EvmContext, Inspector,
+    };
     use crate::{Frame, JournalEntry};
     use test_utils::*;
 
@@ -741,4 +988,411 @@ mod tests {
         };
         assert_eq!(call_frame.return_memory_range, 0..0,);
     }
+
+    #[test]
+    fn test_access_list_snapshot_restore() {
+        let env = Env::default();
+        let db = EmptyDB::default();
+        let mut evm_context = test_utils::create_empty_evm_context(Box::new(env), db);
+        let first = address!("0000000000000000000000000000000000000001");
+        let second = address!("0000000000000000000000000000000000000002");
+        let (slot1, slot2) = (U256::from(1), U256::from(2));
+
+        assert_eq!(evm_context.load_account(first), Some((true, false)));
+        assert_eq!(evm_context.sload(first, slot1), Some((U256::ZERO, true)));
+        let snapshot = evm_context.access_list_snapshot();
+
+        assert_eq!(evm_context.load_account(second), Some((true, false)));
+        assert_eq!(evm_context.sload(first, slot2), Some((U256::ZERO, true)));
+        evm_context.restore_access_list(snapshot);
+
+        // accesses before the snapshot stay warm.
+        assert_eq!(evm_context.load_account(first), Some((false, false)));
+        assert_eq!(evm_context.sload(first, slot1), Some((U256::ZERO, false)));
+        // later accesses are cold again but stay loaded.
+        assert!(evm_context.journaled_state.state.contains_key(&second));
+        assert_eq!(evm_context.load_account(second), Some((true, false)));
+        assert_eq!(evm_context.load_account(second), Some((false, false)));
+        assert_eq!(evm_context.sload(first, slot2), Some((U256::ZERO, true)));
+        assert_eq!(evm_context.sload(first, slot2), Some((U256::ZERO, false)));
+    }
+
+    #[test]
+    fn test_checkpoint_revert() {
+        let env = Env::default();
+        let db = EmptyDB::default();
+        let mut evm_context = test_utils::create_empty_evm_context(Box::new(env), db);
+        let account = address!("0000000000000000000000000000000000000001");
+        let (slot1, slot2) = (U256::from(1), U256::from(2));
+
+        evm_context.load_account(account);
+        evm_context.sstore(account, slot1, U256::from(1));
+
+        let checkpoint = evm_context.checkpoint();
+        evm_context.sstore(account, slot1, U256::from(2));
+        evm_context.sstore(account, slot2, U256::from(3));
+        evm_context.journaled_state.log(Log::default());
+        evm_context.revert_checkpoint(checkpoint);
+
+        // changes made after the checkpoint are gone.
+        assert_eq!(
+            evm_context.sload(account, slot1),
+            Some((U256::from(1), false))
+        );
+        assert_eq!(evm_context.sload(account, slot2).unwrap().0, U256::ZERO);
+        assert!(evm_context.journaled_state.logs.is_empty());
+        assert_eq!(evm_context.journaled_state.depth(), 0);
+
+        // committed changes are kept.
+        evm_context.checkpoint();
+        evm_context.sstore(account, slot2, U256::from(3));
+        evm_context.commit_checkpoint();
+        assert_eq!(evm_context.sload(account, slot2).unwrap().0, U256::from(3));
+    }
+
+    #[test]
+    fn test_disable_create() {
+        // returns the address pushed by CREATE.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::CREATE,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .modify_cfg_env(|cfg| cfg.disable_create = true)
+            .build();
+
+        // CREATE failed and pushed zero.
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        assert_eq!(result.output().unwrap().as_ref(), &[0u8; 32]);
+
+        // create transaction is rejected.
+        evm.tx_mut().transact_to = TransactTo::create();
+        assert_eq!(
+            evm.transact().unwrap_err(),
+            EVMError::Transaction(InvalidTransaction::CreateNotAllowed)
+        );
+    }
+
+    #[test]
+    fn test_max_create_depth() {
+        // constructor that returns the address pushed by nested CREATE as contract code.
+        let init_code = Bytes::from(vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::CREATE,
+            opcode::PUSH1,
+            0x00,
+            opcode::MSTORE,
+            opcode::PUSH1,
+            0x20,
+            opcode::PUSH1,
+            0x00,
+            opcode::RETURN,
+        ]);
+
+        let mut evm = Evm::builder()
+            .with_db(InMemoryDB::default())
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TransactTo::create();
+                tx.data = init_code;
+                tx.gas_limit = 100_000;
+            })
+            .build();
+
+        // nested create succeeds without the limit.
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        assert_ne!(result.output().unwrap().as_ref(), &[0u8; 32]);
+
+        // nested create fails and pushes zero.
+        evm.cfg_mut().max_create_depth = Some(1);
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        assert_eq!(result.output().unwrap().as_ref(), &[0u8; 32]);
+    }
+
+    #[test]
+    fn test_empty_extcodehash() {
+        // returns code hash of the account without code.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x01,
+                opcode::EXTCODEHASH,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO).build();
+
+        let result = evm.transact().unwrap().result;
+        assert_eq!(result.output().unwrap().as_ref(), KECCAK_EMPTY.as_slice());
+
+        let empty_extcodehash = B256::repeat_byte(0x11);
+        evm.cfg_mut().empty_extcodehash = Some(empty_extcodehash);
+        let result = evm.transact().unwrap().result;
+        assert_eq!(
+            result.output().unwrap().as_ref(),
+            empty_extcodehash.as_slice()
+        );
+    }
+
+    #[test]
+    fn test_max_journal_depth() {
+        // increments storage slot zero and calls itself.
+        let mut code = vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::SLOAD,
+            opcode::PUSH1,
+            0x01,
+            opcode::ADD,
+            opcode::PUSH1,
+            0x00,
+            opcode::SSTORE,
+        ];
+        code.extend([opcode::PUSH1, 0x00].repeat(6));
+        code.extend([opcode::GAS, opcode::CALL, opcode::POP, opcode::STOP]);
+
+        let mut evm = evm_calling(
+            BenchmarkDB::new_bytecode(Bytecode::new_raw(code.into())),
+            Address::ZERO,
+        )
+        .modify_cfg_env(|cfg| cfg.max_journal_depth = Some(5))
+        .modify_tx_env(|tx| tx.gas_limit = 1_000_000)
+        .build();
+
+        // recursion stops at the fifth frame, failed call does not revert the callers.
+        let ResultAndState { result, state, .. } = evm.transact().unwrap();
+        assert!(result.is_success());
+        assert_eq!(
+            state[&Address::ZERO].storage[&U256::ZERO].present_value,
+            U256::from(5)
+        );
+    }
+
+    #[test]
+    fn test_precompile_gas_discount() {
+        let ecrecover = address!("0000000000000000000000000000000000000001");
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(Bytecode::new()), ecrecover)
+            .modify_tx_env(|tx| tx.data = Bytes::from(vec![0u8; 128]))
+            .build();
+
+        let full = evm.transact().unwrap().result;
+        assert!(full.is_success());
+
+        evm.cfg_mut().precompile_gas_discount.insert(ecrecover, 500);
+        let discounted = evm.transact().unwrap().result;
+        assert!(discounted.is_success());
+
+        let intrinsic = 21_000 + 128 * 4;
+        // ECRECOVER costs 3000 gas.
+        assert_eq!(full.gas_used(), intrinsic + 3000);
+        assert_eq!(discounted.gas_used(), intrinsic + 1500);
+        assert_eq!(full.output(), discounted.output());
+    }
+
+    #[test]
+    fn test_top_level_precompile_calls() {
+        let identity = address!("0000000000000000000000000000000000000004");
+        let mut evm = Evm::builder()
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TransactTo::Call(identity);
+                tx.data = Bytes::from_static(&[1, 2, 3]);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+
+        // identity of one word costs 15 + 3.
+        let result = evm.transact().unwrap().result;
+        assert_eq!(result.gas_used(), 21_000 + 3 * 16 + 18);
+        assert_eq!(result.output().unwrap().as_ref(), &[1, 2, 3]);
+
+        evm.cfg_mut().top_level_precompile_calls = false;
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        assert_eq!(result.gas_used(), 21_000 + 3 * 16);
+        assert!(result.output().unwrap().is_empty());
+    }
+
+    #[derive(Default, Debug)]
+    struct CallResults {
+        results: Vec<(u64, InstructionResult, u64, u64)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for CallResults {
+        fn call_end(
+            &mut self,
+            context: &mut EvmContext<DB>,
+            inputs: &CallInputs,
+            outcome: CallOutcome,
+        ) -> CallOutcome {
+            self.results.push((
+                context.journaled_state.depth(),
+                *outcome.instruction_result(),
+                inputs.gas_limit,
+                outcome.gas().remaining(),
+            ));
+            outcome
+        }
+    }
+
+    #[test]
+    fn test_max_call_depth() {
+        let contract = address!("2000000000000000000000000000000000000000");
+        // increments slot 0 and calls itself.
+        let code = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x00,
+                opcode::SLOAD,
+                opcode::PUSH1,
+                0x01,
+                opcode::ADD,
+                opcode::PUSH1,
+                0x00,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::ADDRESS,
+                opcode::GAS,
+                opcode::CALL,
+                opcode::POP,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+        let db = db_with_contract(contract, code);
+
+        let mut evm = evm_calling(db, contract)
+            .with_external_context(CallResults::default())
+            .modify_tx_env(|tx| tx.gas_limit = 1_000_000)
+            .with_max_call_depth(5)
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap();
+        assert!(result.result.is_success());
+        // transaction frame and five nested frames are executed.
+        let counter = result.state[&contract].storage[&U256::ZERO].present_value();
+        assert_eq!(counter, U256::from(6));
+
+        // call from depth 5 fails and all forwarded gas is returned to the caller.
+        let results = &evm.context.external.results;
+        assert_eq!(results.len(), 7);
+        let (depth, instruction_result, gas_limit, remaining) = results[0];
+        assert_eq!(depth, 6);
+        assert_eq!(instruction_result, InstructionResult::CallTooDeep);
+        assert_eq!(remaining, gas_limit);
+        assert!(results[1..]
+            .iter()
+            .all(|(_, result, _, _)| *result == InstructionResult::Stop));
+
+        // limit is capped to the consensus limit, deeper calls run out of gas first.
+        evm.context.external.results.clear();
+        evm.cfg_mut().max_call_depth = u16::MAX;
+        let result = evm.transact().unwrap();
+        assert!(result.result.is_success());
+        let counter = result.state[&contract].storage[&U256::ZERO].present_value();
+        assert!(counter > U256::from(6));
+        assert!(evm
+            .context
+            .external
+            .results
+            .iter()
+            .all(|(_, result, _, _)| *result != InstructionResult::CallTooDeep));
+    }
+
+    #[test]
+    fn test_state_override_journaled() {
+        use crate::primitives::AccountOverride;
+        use alloc::collections::BTreeMap;
+
+        let contract = address!("2000000000000000000000000000000000000000");
+        let beneficiary = address!("00000000000000000000000000000000000000b0");
+        // selfdestructs to the beneficiary.
+        let code = Bytecode::new_raw(vec![opcode::PUSH1, 0xb0, opcode::SELFDESTRUCT].into());
+        let mut db = db_with_contract(contract, code);
+        db.insert_account_storage(contract, U256::from(1), U256::from(5))
+            .unwrap();
+        let account_override = AccountOverride {
+            balance: Some(U256::from(100)),
+            state: Some(BTreeMap::from([(U256::from(2), U256::from(6))])),
+            ..Default::default()
+        };
+        let mut evm = evm_calling(db, contract)
+            .with_state_override(contract, account_override)
+            .build();
+
+        // overrides are reverted with the journal.
+        let ctx = &mut evm.context.evm;
+        ctx.journaled_state
+            .initial_account_load(contract, &[], &mut ctx.db)
+            .unwrap();
+        let checkpoint = ctx.journaled_state.checkpoint();
+        ctx.apply_state_overrides().unwrap();
+        let account = &ctx.journaled_state.state[&contract];
+        assert_eq!(account.info.balance, U256::from(100));
+        assert!(account.is_storage_cleared());
+        assert!(!account.is_created());
+        assert_eq!(
+            ctx.sload(contract, U256::from(1)).unwrap(),
+            (U256::ZERO, true)
+        );
+        ctx.journaled_state.checkpoint_revert(checkpoint);
+        let account = &ctx.journaled_state.state[&contract];
+        assert_eq!(account.info.balance, U256::ZERO);
+        assert!(!account.is_storage_cleared());
+        assert!(!account.storage.contains_key(&U256::from(2)));
+        ctx.journaled_state.finalize();
+
+        // overridden contract is not destroyed by EIP-6780 selfdestruct.
+        let state = evm.transact().unwrap().state;
+        let account = &state[&contract];
+        assert!(!account.is_selfdestructed());
+        assert!(account.info.code.is_some());
+        assert_eq!(state[&beneficiary].info.balance, U256::from(100));
+    }
 }
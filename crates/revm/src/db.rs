@@ -1,16 +1,24 @@
 //! [Database] implementations.
 
+#[cfg(feature = "asyncdb")]
+pub mod async_db;
+pub mod block_hash_db;
 pub mod emptydb;
 #[cfg(feature = "ethersdb")]
 pub mod ethersdb;
 pub mod in_memory_db;
+pub mod lru_cache_db;
 pub mod states;
 
 pub use crate::primitives::db::*;
+#[cfg(feature = "asyncdb")]
+pub use async_db::{DatabaseAsync, WrapDatabaseAsync};
+pub use block_hash_db::BlockHashMapDB;
 pub use emptydb::{EmptyDB, EmptyDBTyped};
 #[cfg(feature = "ethersdb")]
 pub use ethersdb::EthersDB;
 pub use in_memory_db::*;
+pub use lru_cache_db::LruCacheDB;
 pub use states::{
     AccountRevert, AccountStatus, BundleAccount, BundleState, CacheState, DBBox,
     OriginalValuesKnown, PlainAccount, RevertToSlot, State, StateBuilder, StateDBBox,
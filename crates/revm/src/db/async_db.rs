@@ -0,0 +1,203 @@
+//! Async database and the adapter that drives it to completion on a tokio runtime.
+
+use crate::{
+    primitives::{AccountInfo, Address, Bytecode, B256, U256},
+    Database,
+};
+use core::future::Future;
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+/// Async version of [Database].
+///
+/// Use [WrapDatabaseAsync] to use it as a [Database] in the EVM.
+pub trait DatabaseAsync {
+    /// The database error type.
+    type Error: Send;
+
+    /// Get basic account information.
+    fn basic_async(
+        &mut self,
+        address: Address,
+    ) -> impl Future<Output = Result<Option<AccountInfo>, Self::Error>> + Send;
+
+    /// Get account code by its hash.
+    fn code_by_hash_async(
+        &mut self,
+        code_hash: B256,
+    ) -> impl Future<Output = Result<Bytecode, Self::Error>> + Send;
+
+    /// Get storage value of address at index.
+    fn storage_async(
+        &mut self,
+        address: Address,
+        index: U256,
+    ) -> impl Future<Output = Result<U256, Self::Error>> + Send;
+
+    /// Get block hash by block number.
+    fn block_hash_async(
+        &mut self,
+        number: U256,
+    ) -> impl Future<Output = Result<B256, Self::Error>> + Send;
+}
+
+/// Wraps a [DatabaseAsync] to provide a [Database] implementation.
+///
+/// Every call blocks the current thread until the future is completed on the given runtime.
+/// Inside a multi-threaded runtime the thread is marked as blocking with
+/// [tokio::task::block_in_place], calling from a current-thread runtime panics.
+#[derive(Debug)]
+pub struct WrapDatabaseAsync<T> {
+    db: T,
+    handle: Handle,
+}
+
+impl<T> WrapDatabaseAsync<T> {
+    /// Wraps the async database, futures are driven with the runtime of the given handle.
+    pub fn new(db: T, handle: Handle) -> Self {
+        Self { db, handle }
+    }
+
+    /// Returns the wrapped async database.
+    pub fn into_inner(self) -> T {
+        self.db
+    }
+
+    /// Blocks on the future until it is completed.
+    fn block_on<F: Future>(handle: &Handle, f: F) -> F::Output {
+        let in_multi_thread_runtime = Handle::try_current()
+            .is_ok_and(|current| current.runtime_flavor() == RuntimeFlavor::MultiThread);
+        if in_multi_thread_runtime {
+            tokio::task::block_in_place(|| handle.block_on(f))
+        } else {
+            handle.block_on(f)
+        }
+    }
+}
+
+impl<T: DatabaseAsync> Database for WrapDatabaseAsync<T> {
+    type Error = T::Error;
+
+    #[inline]
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        Self::block_on(&self.handle, self.db.basic_async(address))
+    }
+
+    #[inline]
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        Self::block_on(&self.handle, self.db.code_by_hash_async(code_hash))
+    }
+
+    #[inline]
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        Self::block_on(&self.handle, self.db.storage_async(address, index))
+    }
+
+    #[inline]
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        Self::block_on(&self.handle, self.db.block_hash_async(number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{contract_info, evm_calling};
+    use crate::{
+        db::{EmptyDB, InMemoryDB},
+        interpreter::opcode,
+        primitives::{address, EVMError, TransactTo},
+        Evm,
+    };
+    use tokio::runtime::Runtime;
+
+    /// Async database that yields on the first poll of every request.
+    struct YieldingDB {
+        db: InMemoryDB,
+        fail_storage: bool,
+    }
+
+    impl DatabaseAsync for YieldingDB {
+        type Error = &'static str;
+
+        async fn basic_async(
+            &mut self,
+            address: Address,
+        ) -> Result<Option<AccountInfo>, Self::Error> {
+            tokio::task::yield_now().await;
+            Ok(self.db.basic(address).unwrap())
+        }
+
+        async fn code_by_hash_async(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+            tokio::task::yield_now().await;
+            Ok(self.db.code_by_hash(code_hash).unwrap())
+        }
+
+        async fn storage_async(
+            &mut self,
+            address: Address,
+            index: U256,
+        ) -> Result<U256, Self::Error> {
+            tokio::task::yield_now().await;
+            if self.fail_storage {
+                return Err("storage unavailable");
+            }
+            Ok(self.db.storage(address, index).unwrap())
+        }
+
+        async fn block_hash_async(&mut self, number: U256) -> Result<B256, Self::Error> {
+            tokio::task::yield_now().await;
+            Ok(EmptyDB::default().block_hash(number).unwrap())
+        }
+    }
+
+    #[test]
+    fn test_wrap_database_async() {
+        let contract = address!("2000000000000000000000000000000000000000");
+        // returns slot 1.
+        let code = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x01,
+                opcode::SLOAD,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ]
+            .into(),
+        );
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(contract, contract_info(code));
+        db.insert_account_storage(contract, U256::from(1), U256::from(42))
+            .unwrap();
+
+        let runtime = Runtime::new().unwrap();
+        let run = |fail_storage: bool| {
+            let db = YieldingDB {
+                db: db.clone(),
+                fail_storage,
+            };
+            let mut evm = evm_calling(
+                WrapDatabaseAsync::new(db, runtime.handle().clone()),
+                contract,
+            )
+            .build();
+            evm.transact().map(|result| result.result)
+        };
+
+        let result = run(false).unwrap();
+        assert_eq!(
+            U256::from_be_slice(result.output().unwrap()),
+            U256::from(42)
+        );
+
+        assert_eq!(
+            run(true).unwrap_err(),
+            EVMError::Database("storage unavailable")
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use super::{DatabaseCommit, DatabaseRef};
+use crate::primitives::{Account, AccountInfo, Address, Bytecode, HashMap, B256, U256};
+use crate::Database;
+
+/// A [Database] wrapper that serves block hashes from a map and delegates everything
+/// else to the underlying database.
+///
+/// Block numbers missing from the map have the default hash, which is zero unless set
+/// with [BlockHashMapDB::with_default_hash]. The underlying database is never asked for
+/// block hashes.
+///
+/// BLOCKHASH still returns zero for blocks outside of the last 256 blocks, hashes of such
+/// blocks in the map are not observable by contracts.
+#[derive(Clone, Debug, Default)]
+pub struct BlockHashMapDB<ExtDB> {
+    /// Block hashes by block number.
+    pub block_hashes: HashMap<u64, B256>,
+    /// Hash of blocks missing from the map.
+    pub default_hash: B256,
+    /// The underlying database.
+    pub db: ExtDB,
+}
+
+impl<ExtDB> BlockHashMapDB<ExtDB> {
+    /// Creates a new database that serves the given block hashes.
+    pub fn from_map(db: ExtDB, block_hashes: HashMap<u64, B256>) -> Self {
+        Self {
+            block_hashes,
+            default_hash: B256::ZERO,
+            db,
+        }
+    }
+
+    /// Sets the hash of blocks missing from the map.
+    pub fn with_default_hash(mut self, default_hash: B256) -> Self {
+        self.default_hash = default_hash;
+        self
+    }
+
+    /// Inserts the hash of the block.
+    pub fn insert_block_hash(&mut self, number: u64, hash: B256) {
+        self.block_hashes.insert(number, hash);
+    }
+
+    /// Returns the underlying database.
+    pub fn into_inner(self) -> ExtDB {
+        self.db
+    }
+
+    /// Returns the hash of the block from the map or the default hash.
+    fn hash_of(&self, number: U256) -> B256 {
+        u64::try_from(number)
+            .ok()
+            .and_then(|number| self.block_hashes.get(&number))
+            .copied()
+            .unwrap_or(self.default_hash)
+    }
+}
+
+impl<ExtDB: Database> Database for BlockHashMapDB<ExtDB> {
+    type Error = ExtDB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db.basic(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db.code_by_hash(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.db.storage(address, index)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        Ok(self.hash_of(number))
+    }
+}
+
+impl<ExtDB: DatabaseRef> DatabaseRef for BlockHashMapDB<ExtDB> {
+    type Error = ExtDB::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db.basic_ref(address)
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db.code_by_hash_ref(code_hash)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.db.storage_ref(address, index)
+    }
+
+    fn block_hash_ref(&self, number: U256) -> Result<B256, Self::Error> {
+        Ok(self.hash_of(number))
+    }
+}
+
+impl<ExtDB: DatabaseCommit> DatabaseCommit for BlockHashMapDB<ExtDB> {
+    fn commit(&mut self, changes: HashMap<Address, Account>) {
+        self.db.commit(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::evm_calling;
+    use crate::{db::BenchmarkDB, interpreter::opcode, primitives::b256};
+
+    #[test]
+    fn test_block_hash_map_db() {
+        let hash = b256!("1111111111111111111111111111111111111111111111111111111111111111");
+        let default_hash =
+            b256!("2222222222222222222222222222222222222222222222222222222222222222");
+
+        // returns hashes of blocks 299, 298 and 10.
+        let mut code = Vec::new();
+        for (offset, number) in [
+            (0x00, [0x01, 0x2b]),
+            (0x20, [0x01, 0x2a]),
+            (0x40, [0x00, 0x0a]),
+        ] {
+            code.extend([
+                opcode::PUSH2,
+                number[0],
+                number[1],
+                opcode::BLOCKHASH,
+                opcode::PUSH1,
+                offset,
+                opcode::MSTORE,
+            ]);
+        }
+        code.extend([opcode::PUSH1, 0x60, opcode::PUSH1, 0x00, opcode::RETURN]);
+
+        let mut db = BlockHashMapDB::from_map(
+            BenchmarkDB::new_bytecode(Bytecode::new_raw(code.into())),
+            HashMap::from([(299, hash), (10, hash)]),
+        )
+        .with_default_hash(default_hash);
+        assert_eq!(db.block_hash(U256::from(299)).unwrap(), hash);
+        assert_eq!(db.block_hash(U256::MAX).unwrap(), default_hash);
+
+        let mut evm = evm_calling(db, Address::ZERO)
+            .modify_block_env(|block| block.number = U256::from(300))
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        let output = result.output().unwrap();
+        assert_eq!(&output[..0x20], hash.as_slice());
+        assert_eq!(&output[0x20..0x40], default_hash.as_slice());
+        // block 10 is outside of the last 256 blocks.
+        assert_eq!(&output[0x40..], B256::ZERO.as_slice());
+    }
+}
@@ -138,7 +138,8 @@ impl<ExtDB: DatabaseRef> DatabaseCommit for CacheDB<ExtDB> {
                 db_account.info = AccountInfo::default();
                 continue;
             }
-            let is_newly_created = account.is_created();
+            // overridden storage replaces the storage in the database.
+            let is_newly_created = account.is_created() || account.is_storage_cleared();
             self.insert_contract(&mut account.info);
 
             let db_account = self.accounts.entry(address).or_default();
@@ -0,0 +1,246 @@
+use super::DatabaseRef;
+use crate::primitives::{AccountInfo, Address, Bytecode, HashMap, B256, U256};
+use crate::Database;
+use alloc::collections::BTreeMap;
+use core::hash::Hash;
+
+/// A [Database] wrapper that caches accounts and storage slots read from the
+/// underlying database in bounded maps.
+///
+/// When a map grows past its capacity, the least recently used entry is evicted.
+/// On a miss the value is loaded from the underlying database.
+///
+/// This is a read cache. State written by the transactions lives in the journal and is
+/// never stored here, so eviction does not lose any state. Evictions happen only on the
+/// [Database::basic] and [Database::storage] read paths.
+#[derive(Clone, Debug)]
+pub struct LruCacheDB<ExtDB> {
+    /// Cached accounts. `None` if the account does not exist.
+    accounts: LruMap<Address, Option<AccountInfo>>,
+    /// Cached storage slots.
+    storage: LruMap<(Address, U256), U256>,
+    /// The underlying database.
+    pub db: ExtDB,
+}
+
+impl<ExtDB> LruCacheDB<ExtDB> {
+    /// Creates a new cache that holds at most `accounts_cap` accounts and `slots_cap`
+    /// storage slots.
+    pub fn with_capacity(db: ExtDB, accounts_cap: usize, slots_cap: usize) -> Self {
+        Self {
+            accounts: LruMap::new(accounts_cap),
+            storage: LruMap::new(slots_cap),
+            db,
+        }
+    }
+
+    /// Returns the maximum number of cached accounts and storage slots.
+    pub fn capacity(&self) -> (usize, usize) {
+        (self.accounts.capacity, self.storage.capacity)
+    }
+
+    /// Returns the number of cached accounts and storage slots.
+    pub fn len(&self) -> (usize, usize) {
+        (self.accounts.len(), self.storage.len())
+    }
+
+    /// Returns `true` if nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == (0, 0)
+    }
+}
+
+impl<ExtDB: Database> Database for LruCacheDB<ExtDB> {
+    type Error = ExtDB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        if let Some(info) = self.accounts.get(&address) {
+            return Ok(info.clone());
+        }
+        let info = self.db.basic(address)?;
+        self.accounts.insert(address, info.clone());
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db.code_by_hash(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        if let Some(value) = self.storage.get(&(address, index)) {
+            return Ok(*value);
+        }
+        let value = self.db.storage(address, index)?;
+        self.storage.insert((address, index), value);
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        self.db.block_hash(number)
+    }
+}
+
+impl<ExtDB: DatabaseRef> DatabaseRef for LruCacheDB<ExtDB> {
+    type Error = ExtDB::Error;
+
+    /// Cache is not updated, entries are read without changing their recency.
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        match self.accounts.peek(&address) {
+            Some(info) => Ok(info.clone()),
+            None => self.db.basic_ref(address),
+        }
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db.code_by_hash_ref(code_hash)
+    }
+
+    /// Cache is not updated, entries are read without changing their recency.
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        match self.storage.peek(&(address, index)) {
+            Some(value) => Ok(*value),
+            None => self.db.storage_ref(address, index),
+        }
+    }
+
+    fn block_hash_ref(&self, number: U256) -> Result<B256, Self::Error> {
+        self.db.block_hash_ref(number)
+    }
+}
+
+/// Bounded map that evicts the least recently used entry.
+#[derive(Clone, Debug)]
+struct LruMap<K, V> {
+    /// Maximum number of entries.
+    capacity: usize,
+    /// Incremented on every access, used as recency of the accessed entry.
+    tick: u64,
+    /// Entries with their last access tick.
+    entries: HashMap<K, (V, u64)>,
+    /// Keys ordered by their last access tick.
+    order: BTreeMap<u64, K>,
+}
+
+impl<K: Hash + Eq + Clone, V> LruMap<K, V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::default(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the entry and marks it as most recently used.
+    fn get(&mut self, key: &K) -> Option<&V> {
+        let (_, tick) = self.entries.get_mut(key)?;
+        self.order.remove(tick);
+        self.tick += 1;
+        *tick = self.tick;
+        self.order.insert(self.tick, key.clone());
+        self.entries.get(key).map(|(value, _)| value)
+    }
+
+    /// Returns the entry without changing its recency.
+    fn peek(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|(value, _)| value)
+    }
+
+    /// Inserts the entry as most recently used, evicting the least recently used
+    /// entries if the capacity is exceeded.
+    fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, tick)) = self.entries.insert(key.clone(), (value, self.tick)) {
+            self.order.remove(&tick);
+        }
+        self.order.insert(self.tick, key);
+        while self.entries.len() > self.capacity {
+            let Some((_, key)) = self.order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::EmptyDB;
+    use core::convert::Infallible;
+
+    /// Counts reads that reach the database.
+    #[derive(Debug, Default)]
+    struct CountingDB {
+        basic: usize,
+        storage: usize,
+    }
+
+    impl Database for CountingDB {
+        type Error = Infallible;
+
+        fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+            self.basic += 1;
+            EmptyDB::default().basic(address)
+        }
+
+        fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+            EmptyDB::default().code_by_hash(code_hash)
+        }
+
+        fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+            self.storage += 1;
+            EmptyDB::default().storage(address, index)
+        }
+
+        fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+            EmptyDB::default().block_hash(number)
+        }
+    }
+
+    #[test]
+    fn test_lru_cache_db_evicts_least_recently_used() {
+        let (a, b, c) = (
+            Address::with_last_byte(1),
+            Address::with_last_byte(2),
+            Address::with_last_byte(3),
+        );
+        let mut db = LruCacheDB::with_capacity(CountingDB::default(), 2, 1);
+        assert_eq!(db.capacity(), (2, 1));
+
+        for address in [a, b, a, c] {
+            db.basic(address).unwrap();
+        }
+        // `a` was used more recently than `b`, so `b` is evicted.
+        assert_eq!(db.db.basic, 3);
+        assert_eq!(db.len(), (2, 0));
+        db.basic(a).unwrap();
+        assert_eq!(db.db.basic, 3);
+        db.basic(b).unwrap();
+        assert_eq!(db.db.basic, 4);
+
+        db.storage(a, U256::from(1)).unwrap();
+        db.storage(a, U256::from(1)).unwrap();
+        assert_eq!(db.db.storage, 1);
+        db.storage(a, U256::from(2)).unwrap();
+        db.storage(a, U256::from(1)).unwrap();
+        assert_eq!(db.db.storage, 3);
+        assert_eq!(db.len(), (2, 1));
+    }
+
+    #[test]
+    fn test_lru_cache_db_zero_capacity() {
+        let mut db = LruCacheDB::with_capacity(CountingDB::default(), 0, 0);
+        db.basic(Address::ZERO).unwrap();
+        db.basic(Address::ZERO).unwrap();
+        assert_eq!(db.db.basic, 2);
+        assert!(db.is_empty());
+    }
+}
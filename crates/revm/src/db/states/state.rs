@@ -160,6 +160,23 @@ impl<DB: Database> State<DB> {
         }
     }
 
+    /// Returns addresses of accounts whose storage changed in the pending transitions,
+    /// including accounts whose storage was cleared.
+    ///
+    /// Storage roots of those accounts need to be recomputed. Transitions are only
+    /// recorded if the [State] was built with [StateBuilder::with_bundle_update] and
+    /// are cleared by [State::merge_transitions].
+    pub fn accounts_needing_storage_root_update(&self) -> impl Iterator<Item = Address> + '_ {
+        self.transition_state
+            .iter()
+            .flat_map(|state| state.transitions.iter())
+            .filter(|(_, account)| {
+                account.storage_was_destroyed
+                    || account.storage.values().any(|slot| slot.is_changed())
+            })
+            .map(|(address, _)| *address)
+    }
+
     /// Take all transitions and merge them inside bundle state.
     /// This action will create final post state and all reverts so that
     /// we at any time revert state of bundle to the state before transition
@@ -307,6 +324,40 @@ mod tests {
     };
     use revm_interpreter::primitives::{keccak256, StorageSlot};
 
+    #[test]
+    fn accounts_needing_storage_root_update() {
+        let mut state = State::builder().with_bundle_update().build();
+
+        let storage_changed = Address::with_last_byte(1);
+        let balance_changed = Address::with_last_byte(2);
+        state.insert_account(storage_changed, AccountInfo::default());
+        state.insert_account(balance_changed, AccountInfo::default());
+
+        let mut storage_account = Account::from(AccountInfo::default());
+        storage_account.storage.insert(
+            U256::from(1),
+            StorageSlot::new_changed(U256::ZERO, U256::from(10)),
+        );
+        storage_account.mark_touch();
+        let mut balance_account = Account::from(AccountInfo {
+            balance: U256::from(1),
+            ..Default::default()
+        });
+        balance_account.mark_touch();
+
+        state.commit(HashMap::from([
+            (storage_changed, storage_account),
+            (balance_changed, balance_account),
+        ]));
+
+        assert_eq!(
+            state
+                .accounts_needing_storage_root_update()
+                .collect::<Vec<_>>(),
+            vec![storage_changed]
+        );
+    }
+
     #[test]
     fn block_hash_cache() {
         let mut state = State::builder().build();
@@ -2,24 +2,58 @@ use crate::{
     builder::{EvmBuilder, HandlerStage, SetGenericStage},
     db::{Database, DatabaseCommit, EmptyDB},
     handler::Handler,
+    inspector_handle_register,
     interpreter::{
-        opcode::InstructionTables, Host, Interpreter, InterpreterAction, SelfDestructResult,
-        SharedMemory,
+        gas, opcode::InstructionTables, Contract, Host, InstructionResult, Interpreter,
+        InterpreterAction, InterpreterResult, SelfDestructResult, SharedMemory,
     },
     primitives::{
-        specification::SpecId, Address, BlockEnv, Bytecode, CfgEnv, EVMError, EVMResult, Env,
-        EnvWithHandlerCfg, ExecutionResult, HandlerCfg, Log, ResultAndState, TransactTo, TxEnv,
-        B256, U256,
+        specification::SpecId, Address, BlockEnv, Bytecode, Bytes, CfgEnv, EVMError, EVMResult,
+        Env, EnvWithHandlerCfg, ExecutionResult, HaltReason, HandlerCfg, InvalidTransaction, Log,
+        ResultAndState, TransactTo, TxEnv, B256, BEACON_ROOTS_ADDRESS, SYSTEM_ADDRESS,
+        SYSTEM_CALL_GAS_LIMIT, U256,
     },
-    Context, ContextWithHandlerCfg, Frame, FrameOrResult, FrameResult,
+    Context, ContextWithHandlerCfg, Frame, FrameOrResult, FrameResult, Inspector,
 };
-use alloc::vec::Vec;
+use alloc::{boxed::Box, vec::Vec};
 use core::fmt;
 use revm_interpreter::{CallInputs, CreateInputs};
 
 /// EVM call stack limit.
 pub const CALL_STACK_LIMIT: u64 = 1024;
 
+/// Outcome of a single gas limit attempt made by [Evm::estimate_gas_with_trace].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EstimateOutcome {
+    /// Transaction succeeded.
+    Success,
+    /// Transaction reverted.
+    Revert,
+    /// Transaction ran out of gas.
+    OutOfGas,
+    /// Transaction halted for reason other than out of gas.
+    Halt(HaltReason),
+    /// Transaction was rejected by the validation.
+    Invalid(InvalidTransaction),
+}
+
+/// Attempted gas limits with their outcomes, in the order of attempts.
+pub type EstimateTrace = Vec<(u64, EstimateOutcome)>;
+
+impl From<&ExecutionResult> for EstimateOutcome {
+    fn from(result: &ExecutionResult) -> Self {
+        match result {
+            ExecutionResult::Success { .. } => Self::Success,
+            ExecutionResult::Revert { .. } => Self::Revert,
+            ExecutionResult::Halt {
+                reason: HaltReason::OutOfGas(_),
+                ..
+            } => Self::OutOfGas,
+            ExecutionResult::Halt { reason, .. } => Self::Halt(*reason),
+        }
+    }
+}
+
 /// EVM instance containing both internal EVM context and external context
 /// and the handler that dictates the logic of EVM (or hardfork specification).
 pub struct Evm<'a, EXT, DB: Database> {
@@ -46,12 +80,104 @@ where
 impl<EXT, DB: Database + DatabaseCommit> Evm<'_, EXT, DB> {
     /// Commit the changes to the database.
     pub fn transact_commit(&mut self) -> Result<ExecutionResult, EVMError<DB::Error>> {
-        let ResultAndState { result, state } = self.transact()?;
+        let ResultAndState { result, state, .. } = self.transact()?;
         self.context.evm.db.commit(state);
         Ok(result)
     }
 }
 
+impl<EXT, DB: Database + DatabaseCommit + Clone> Evm<'_, EXT, DB> {
+    /// Transacts a bundle of transactions atomically.
+    ///
+    /// Each transaction is executed on top of the state committed by the previous one.
+    /// Database is snapshotted before the first transaction and restored if any transaction
+    /// returns an error, or if `require_success` is set and any transaction reverts or halts.
+    /// In that case execution stops and the last returned result belongs to the failed transaction.
+    ///
+    /// Note that transaction environment is overwritten with each transaction of the bundle.
+    pub fn transact_bundle(
+        &mut self,
+        txs: Vec<TxEnv>,
+        require_success: bool,
+    ) -> Result<Vec<ResultAndState>, EVMError<DB::Error>> {
+        let snapshot = self.context.evm.db.clone();
+        let mut results = Vec::with_capacity(txs.len());
+        for tx in txs {
+            self.context.evm.env.tx = tx;
+            let result_and_state = match self.transact() {
+                Ok(result_and_state) => result_and_state,
+                Err(e) => {
+                    self.context.evm.db = snapshot;
+                    return Err(e);
+                }
+            };
+            let is_success = result_and_state.result.is_success();
+            self.context.evm.db.commit(result_and_state.state.clone());
+            results.push(result_and_state);
+
+            if require_success && !is_success {
+                self.context.evm.db = snapshot;
+                break;
+            }
+        }
+        Ok(results)
+    }
+}
+
+impl<EXT, DB: Database + Clone> Evm<'_, EXT, DB> {
+    /// Transacts the transaction under each of the given specs and returns all results.
+    ///
+    /// Every run starts from the same database state, changes are not committed and database
+    /// is snapshotted before the first run and restored after each one. Handler is recreated
+    /// for each spec with all appended handle registers.
+    ///
+    /// Spec and transaction environment are restored afterwards.
+    pub fn transact_across_specs(
+        &mut self,
+        tx: TxEnv,
+        specs: &[SpecId],
+    ) -> Vec<(SpecId, EVMResult<DB::Error>)> {
+        let spec_id = self.spec_id();
+        let tx = core::mem::replace(&mut self.context.evm.env.tx, tx);
+        let snapshot = self.context.evm.db.clone();
+        let mut results = Vec::with_capacity(specs.len());
+        for &spec in specs {
+            self.modify_spec_id(spec);
+            results.push((spec, self.transact()));
+            self.context.evm.db = snapshot.clone();
+        }
+        self.modify_spec_id(spec_id);
+        self.context.evm.env.tx = tx;
+        results
+    }
+
+    /// Returns gas saved by the access list of the transaction.
+    ///
+    /// Transaction is transacted with and without its access list. Both runs start from the
+    /// same database state and changes are not committed. Result is gas used without the
+    /// access list minus gas used with it, it is negative if the access list costs more
+    /// than it saves.
+    ///
+    /// Transaction environment is restored afterwards.
+    pub fn access_list_gas_delta(&mut self, tx: TxEnv) -> Result<i64, EVMError<DB::Error>> {
+        let mut tx_without_list = tx.clone();
+        tx_without_list.access_list.clear();
+
+        let tx = core::mem::replace(&mut self.context.evm.env.tx, tx);
+        let snapshot = self.context.evm.db.clone();
+        let with_list = self.transact();
+        self.context.evm.db = snapshot.clone();
+        self.context.evm.env.tx = tx_without_list;
+        let without_list = self.transact();
+        self.context.evm.db = snapshot;
+        self.context.evm.env.tx = tx;
+
+        let gas_with_list = with_list?.result.gas_used();
+        let gas_without_list = without_list?.result.gas_used();
+        Ok(gas_without_list as i64 - gas_with_list as i64)
+    }
+}
+
 impl<'a> Evm<'a, (), EmptyDB> {
     /// Returns evm builder with empty database and empty external context.
     pub fn builder() -> EvmBuilder<'a, SetGenericStage, (), EmptyDB> {
@@ -89,9 +215,11 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
     #[inline]
     pub fn preverify_transaction(&mut self) -> Result<(), EVMError<DB::Error>> {
         self.handler.validation().env(&self.context.evm.env)?;
+        self.handler.validation().blob_hashes(&mut self.context)?;
         self.handler
             .validation()
             .initial_tx_gas(&self.context.evm.env)?;
+        self.context.evm.tx_fees = self.handler.pre_execution().tx_fees(&self.context.evm.env);
         self.handler
             .validation()
             .tx_against_state(&mut self.context)?;
@@ -103,6 +231,7 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
     /// This function will not validate the transaction.
     #[inline]
     pub fn transact_preverified(&mut self) -> EVMResult<DB::Error> {
+        self.context.evm.tx_fees = self.handler.pre_execution().tx_fees(&self.context.evm.env);
         let initial_gas_spend = self
             .handler
             .validation()
@@ -159,10 +288,12 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
     #[inline]
     pub fn transact(&mut self) -> EVMResult<DB::Error> {
         self.handler.validation().env(&self.context.evm.env)?;
+        self.handler.validation().blob_hashes(&mut self.context)?;
         let initial_gas_spend = self
             .handler
             .validation()
             .initial_tx_gas(&self.context.evm.env)?;
+        self.context.evm.tx_fees = self.handler.pre_execution().tx_fees(&self.context.evm.env);
         self.handler
             .validation()
             .tx_against_state(&mut self.context)?;
@@ -171,6 +302,239 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
         self.handler.post_execution().end(&mut self.context, output)
     }
 
+    /// Transacts the transaction with the given inspector and returns both the result
+    /// and the inspector.
+    ///
+    /// Transaction is executed on the database of this EVM with its environment and
+    /// handler configuration, but with the default handler and [inspector_handle_register].
+    /// Handler registers and external context of this EVM are not used.
+    /// Like [Evm::transact], changes are not committed to the database.
+    pub fn inspect<'b, I: Inspector<&'b mut DB>>(
+        &'b mut self,
+        inspector: I,
+    ) -> Result<(ResultAndState, I), EVMError<DB::Error>> {
+        let env_with_handler_cfg =
+            EnvWithHandlerCfg::new(self.context.evm.env.clone(), self.handler.cfg);
+        let mut evm = Evm::builder()
+            .with_db(&mut self.context.evm.db)
+            .with_external_context(inspector)
+            .with_env_with_handler_cfg(env_with_handler_cfg)
+            .append_handler_register(inspector_handle_register)
+            .build();
+        let result = evm.transact()?;
+        Ok((result, evm.into_context().external))
+    }
+
+    /// Estimates the lowest gas limit with which the transaction succeeds.
+    ///
+    /// See [Evm::estimate_gas_with_trace].
+    pub fn estimate_gas(&mut self) -> Result<Option<u64>, EVMError<DB::Error>> {
+        self.estimate_gas_with_trace().map(|(estimate, _)| estimate)
+    }
+
+    /// Estimates the lowest gas limit with which the transaction succeeds and returns
+    /// every attempted gas limit with its outcome, in the order of attempts.
+    ///
+    /// Transaction is first executed with its gas limit, which is the upper bound of the search.
+    /// If it does not succeed, the estimate is `None`. Otherwise the gas limit is binary searched
+    /// between the gas used by the first execution and the transaction gas limit.
+    ///
+    /// Attempts rejected by the transaction validation or reverted with
+    /// [`CfgEnv::revert_is_error`](crate::primitives::CfgEnv::revert_is_error) set are failed
+    /// attempts, other errors abort the estimate.
+    ///
+    /// Changes are not committed and the transaction gas limit is restored afterwards.
+    pub fn estimate_gas_with_trace(
+        &mut self,
+    ) -> Result<(Option<u64>, EstimateTrace), EVMError<DB::Error>> {
+        let gas_limit = self.context.evm.env.tx.gas_limit;
+        let mut trace = Vec::new();
+        let result = self.estimate_gas_inner(gas_limit, &mut trace);
+        self.context.evm.env.tx.gas_limit = gas_limit;
+        result.map(|estimate| (estimate, trace))
+    }
+
+    fn estimate_gas_inner(
+        &mut self,
+        gas_limit: u64,
+        trace: &mut EstimateTrace,
+    ) -> Result<Option<u64>, EVMError<DB::Error>> {
+        let (outcome, gas_used) = self.estimate_attempt()?;
+        trace.push((gas_limit, outcome.clone()));
+        if outcome != EstimateOutcome::Success {
+            return Ok(None);
+        }
+
+        // gas used is lower bound as the gas spent before refund is at least that much.
+        let mut low = gas_used.saturating_sub(1);
+        let mut high = gas_limit;
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            self.context.evm.env.tx.gas_limit = mid;
+            let (outcome, _) = self.estimate_attempt()?;
+            if outcome == EstimateOutcome::Success {
+                high = mid;
+            } else {
+                low = mid;
+            }
+            trace.push((mid, outcome));
+        }
+        Ok(Some(high))
+    }
+
+    /// Transacts the transaction and returns the outcome with the gas used.
+    fn estimate_attempt(&mut self) -> Result<(EstimateOutcome, u64), EVMError<DB::Error>> {
+        match self.transact() {
+            Ok(ResultAndState { result, .. }) => {
+                Ok((EstimateOutcome::from(&result), result.gas_used()))
+            }
+            Err(EVMError::Transaction(error)) => Ok((EstimateOutcome::Invalid(error), 0)),
+            Err(EVMError::TransactionReverted { .. }) => Ok((EstimateOutcome::Revert, 0)),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Executes a system call from [SYSTEM_ADDRESS] to `target` with given input.
+    ///
+    /// Transaction validation, fees, caller nonce and block gas limit are skipped and the call
+    /// has the full [SYSTEM_CALL_GAS_LIMIT] gas. A revert is returned as a result even if
+    /// [`CfgEnv::revert_is_error`](crate::primitives::CfgEnv::revert_is_error) is set. System
+    /// address is removed from the returned state, the block beneficiary only if the call
+    /// did not touch it. Transaction environment is restored afterwards and changes are
+    /// not committed.
+    pub fn system_call(&mut self, target: Address, data: Bytes) -> EVMResult<DB::Error> {
+        let tx = core::mem::replace(
+            &mut self.context.evm.env.tx,
+            TxEnv {
+                caller: SYSTEM_ADDRESS,
+                transact_to: TransactTo::Call(target),
+                data,
+                gas_limit: SYSTEM_CALL_GAS_LIMIT,
+                ..Default::default()
+            },
+        );
+        let result = self.system_call_inner();
+        self.context.evm.env.tx = tx;
+        let mut result = self
+            .handler
+            .post_execution()
+            .end(&mut self.context, result)?;
+
+        result.state.remove(&SYSTEM_ADDRESS);
+        let coinbase = self.context.evm.env.block.coinbase;
+        if !result
+            .state
+            .get(&coinbase)
+            .is_some_and(|account| account.is_touched())
+        {
+            result.state.remove(&coinbase);
+        }
+        Ok(result)
+    }
+
+    /// Executes the call of the transaction environment as a system call.
+    fn system_call_inner(&mut self) -> EVMResult<DB::Error> {
+        let ctx = &mut self.context;
+        let pre_exec = self.handler.pre_execution();
+
+        // load access list and beneficiary if needed.
+        if let Err(error) = pre_exec.load_accounts(ctx) {
+            ctx.evm.journaled_state.finalize();
+            return Err(error);
+        }
+        let precompiles = pre_exec.load_precompiles();
+        ctx.evm.set_precompiles(precompiles);
+
+        let exec = self.handler.execution();
+        let inputs = CallInputs::new_boxed(&ctx.evm.env.tx, SYSTEM_CALL_GAS_LIMIT).unwrap();
+        let mut result = match exec.call(ctx, inputs) {
+            FrameOrResult::Frame(first_frame) => self.start_the_loop(first_frame),
+            FrameOrResult::Result(result) => result,
+        };
+
+        let ctx = &mut self.context;
+        self.handler.execution().last_frame_return(ctx, &mut result);
+        self.handler.post_execution().output(ctx, result)
+    }
+
+    /// Executes [EIP-4788] system call that writes parent beacon block root of the block
+    /// environment to the beacon roots contract.
+    ///
+    /// Returns `None` if Cancun is not enabled or the parent beacon block root is not set.
+    ///
+    /// [EIP-4788]: https://eips.ethereum.org/EIPS/eip-4788
+    pub fn beacon_root_contract_call(
+        &mut self,
+    ) -> Result<Option<ResultAndState>, EVMError<DB::Error>> {
+        if !SpecId::enabled(self.spec_id(), SpecId::CANCUN) {
+            return Ok(None);
+        }
+        let Some(root) = self.context.evm.env.block.parent_beacon_block_root else {
+            return Ok(None);
+        };
+        self.system_call(
+            BEACON_ROOTS_ADDRESS,
+            Bytes::copy_from_slice(root.as_slice()),
+        )
+        .map(Some)
+    }
+
+    /// Runs `code` with `input` and `gas` in a single frame, without a transaction.
+    ///
+    /// Code is executed at the address the transaction calls, or zero address for creates,
+    /// with the transaction caller as caller and zero value. Validation, fee handling and
+    /// handlers around the first frame are skipped. The executing account and the caller are
+    /// loaded and warm. Sub calls are executed against the current database, state changes
+    /// are discarded.
+    pub fn run_bytecode(
+        &mut self,
+        code: Bytecode,
+        input: Bytes,
+        gas: u64,
+    ) -> Result<InterpreterResult, EVMError<DB::Error>> {
+        let ctx = &mut self.context;
+        let address = match ctx.evm.env.tx.transact_to {
+            TransactTo::Call(address) => address,
+            TransactTo::Create(_) => Address::ZERO,
+        };
+        let caller = ctx.evm.env.tx.caller;
+        ctx.evm.error = None;
+        ctx.evm
+            .journaled_state
+            .set_spec_id(self.handler.cfg.spec_id);
+        let precompiles = self.handler.pre_execution().load_precompiles();
+        ctx.evm.set_precompiles(precompiles);
+
+        // storage opcodes expect the executing account to be loaded.
+        for account in [caller, address] {
+            if let Err(error) = ctx
+                .evm
+                .journaled_state
+                .load_account(account, &mut ctx.evm.db)
+            {
+                ctx.evm.journaled_state.finalize();
+                return Err(EVMError::Database(error));
+            }
+        }
+
+        let hash = code.hash_slow();
+        let contract = Contract::new(input, code, hash, address, caller, U256::ZERO);
+        let checkpoint = ctx.evm.journaled_state.checkpoint();
+        let frame = Frame::new_call(
+            0..0,
+            checkpoint,
+            Interpreter::new(Box::new(contract), gas, false),
+        );
+        let result = self.start_the_loop(frame);
+
+        // discard state changes.
+        self.context.evm.journaled_state.finalize();
+        match self.context.evm.error.take() {
+            Some(error) => Err(EVMError::Database(error)),
+            None => Ok(result.into_interpreter_result()),
+        }
+    }
+
     /// Modify spec id, this will create new EVM that matches this spec id.
     pub fn modify_spec_id(&mut self, spec_id: SpecId) {
         self.handler.modify_spec_id(spec_id);
@@ -233,28 +597,72 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
         let mut call_stack: Vec<Frame> = Vec::with_capacity(1025);
         call_stack.push(first_frame);
 
+        let memory_pool = core::mem::take(&mut self.context.evm.memory_pool);
+        let cfg = &self.context.evm.env.cfg;
+        // pooled memory reuses the buffers of the previous transaction.
+        let capacity = if cfg.memory_pool && !memory_pool.is_empty() {
+            0
+        } else {
+            cfg.initial_memory_capacity.unwrap_or(4 * 1024)
+        };
         #[cfg(feature = "memory_limit")]
-        let mut shared_memory =
-            SharedMemory::new_with_memory_limit(self.context.evm.env.cfg.memory_limit);
+        let mut shared_memory = SharedMemory::with_capacity_and_memory_limit(
+            capacity,
+            self.context.evm.env.cfg.memory_limit,
+        );
         #[cfg(not(feature = "memory_limit"))]
-        let mut shared_memory = SharedMemory::new();
+        let mut shared_memory = SharedMemory::with_capacity(capacity);
+        if self.context.evm.env.cfg.memory_pool {
+            shared_memory.set_pool(memory_pool);
+        }
 
         shared_memory.new_context();
 
         // peek last stack frame.
         let mut stack_frame = call_stack.last_mut().unwrap();
+        // set when a sub-frame was interrupted, its parent is returned without running.
+        let mut interrupted = false;
 
         loop {
-            // run interpreter
             let interpreter = &mut stack_frame.frame_data_mut().interpreter;
-            let next_action = interpreter.run(shared_memory, instruction_table, self);
-            // take shared memory back.
-            shared_memory = interpreter.take_memory();
+            let next_action = if core::mem::take(&mut interrupted) {
+                InterpreterAction::Return {
+                    result: InterpreterResult {
+                        result: InstructionResult::Interrupted,
+                        output: Bytes::new(),
+                        gas: interpreter.gas,
+                    },
+                }
+            } else {
+                // run interpreter
+                let next_action = interpreter.run(shared_memory, instruction_table, self);
+                // take shared memory back.
+                shared_memory = interpreter.take_memory();
+                next_action
+            };
 
             let exec = &mut self.handler.execution;
             let frame_or_result = match next_action {
-                InterpreterAction::Call { inputs } => exec.call(&mut self.context, inputs),
-                InterpreterAction::Create { inputs } => exec.create(&mut self.context, inputs),
+                InterpreterAction::Call { inputs } => {
+                    // call stipend of value transfers is not paid by the frame.
+                    let stipend = if inputs.transfer.value != U256::ZERO {
+                        self.context
+                            .evm
+                            .env
+                            .cfg
+                            .call_stipend
+                            .unwrap_or(gas::CALL_STIPEND)
+                    } else {
+                        0
+                    };
+                    stack_frame.frame_data_mut().gas_forwarded +=
+                        inputs.gas_limit.saturating_sub(stipend);
+                    exec.call(&mut self.context, inputs)
+                }
+                InterpreterAction::Create { inputs } => {
+                    stack_frame.frame_data_mut().gas_forwarded += inputs.gas_limit;
+                    exec.create(&mut self.context, inputs)
+                }
                 InterpreterAction::Return { result } => {
                     // free memory context.
                     shared_memory.free_context();
@@ -288,11 +696,18 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
                 }
                 FrameOrResult::Result(result) => {
                     let Some(top_frame) = call_stack.last_mut() else {
+                        self.context.evm.memory_reallocations = shared_memory.reallocations();
+                        if shared_memory.is_pooled() {
+                            self.context.evm.memory_pool = shared_memory.take_pool();
+                        }
                         // Break the look if there are no more frames.
                         return result;
                     };
                     stack_frame = top_frame;
+                    interrupted =
+                        result.interpreter_result().result == InstructionResult::Interrupted;
                     let ctx = &mut self.context;
+                    let gas_before = stack_frame.frame_data().interpreter.gas.remaining();
                     // Insert result to the top frame.
                     match result {
                         FrameResult::Call(outcome) => {
@@ -304,6 +719,12 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
                             exec.insert_create_outcome(ctx, stack_frame, outcome)
                         }
                     }
+                    let frame_data = stack_frame.frame_data_mut();
+                    frame_data.gas_returned += frame_data
+                        .interpreter
+                        .gas
+                        .remaining()
+                        .saturating_sub(gas_before);
                 }
             }
         }
@@ -313,6 +734,8 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
     fn transact_preverified_inner(&mut self, initial_gas_spend: u64) -> EVMResult<DB::Error> {
         let ctx = &mut self.context;
         let pre_exec = self.handler.pre_execution();
+        ctx.evm.memory_reallocations = 0;
+        ctx.evm.fuel_used = 0;
 
         // load access list and beneficiary if needed.
         pre_exec.load_accounts(ctx)?;
@@ -324,6 +747,9 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
         // deduce caller balance with its limit.
         pre_exec.deduct_caller(ctx)?;
 
+        // custom setup before the first frame.
+        pre_exec.post_setup(ctx)?;
+
         let gas_limit = ctx.evm.env.tx.gas_limit - initial_gas_spend;
 
         let exec = self.handler.execution();
@@ -356,7 +782,13 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
         // Reward beneficiary
         post_exec.reward_beneficiary(ctx, result.gas())?;
         // Returns output of transaction.
-        post_exec.output(ctx, result)
+        let output = post_exec.output(ctx, result)?;
+        if ctx.evm.env.cfg.revert_is_error {
+            if let ExecutionResult::Revert { output, .. } = output.result {
+                return Err(EVMError::TransactionReverted { output });
+            }
+        }
+        Ok(output)
     }
 }
 
@@ -376,6 +808,19 @@ impl<EXT, DB: Database> Host for Evm<'_, EXT, DB> {
         self.context.evm.load_account(address)
     }
 
+    fn call_access_gas(&mut self, address: Address, is_cold: bool) -> Option<u64> {
+        let handle = self.handler.execution.call_access_gas.as_ref()?;
+        // target account is loaded by the call before its cost is computed.
+        let is_empty_code = self
+            .context
+            .evm
+            .journaled_state
+            .state
+            .get(&address)
+            .is_none_or(|account| account.info.is_empty_code_hash());
+        Some(handle(address, is_empty_code, is_cold, &self.context.evm))
+    }
+
     fn balance(&mut self, address: Address) -> Option<(U256, bool)> {
         self.context.evm.balance(address)
     }
@@ -413,6 +858,21 @@ impl<EXT, DB: Database> Host for Evm<'_, EXT, DB> {
         self.context.evm.journaled_state.log(log);
     }
 
+    fn log_count(&self) -> usize {
+        self.context.evm.journaled_state.log_count
+    }
+
+    fn record_return_data(&mut self, len: usize) -> bool {
+        let journaled_state = &mut self.context.evm.journaled_state;
+        journaled_state.return_data_size = journaled_state.return_data_size.saturating_add(len);
+        self.context
+            .evm
+            .env
+            .cfg
+            .max_return_data_size
+            .is_none_or(|limit| journaled_state.return_data_size <= limit)
+    }
+
     fn selfdestruct(&mut self, address: Address, target: Address) -> Option<SelfDestructResult> {
         self.context
             .evm
@@ -422,3 +882,1118 @@ impl<EXT, DB: Database> Host for Evm<'_, EXT, DB> {
             .ok()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{contract_info, db_with_contract, evm_calling};
+    use crate::{
+        db::{BenchmarkDB, InMemoryDB},
+        inspector::inspector_handle_register,
+        interpreter::{opcode, InstructionResult},
+        primitives::{address, AccountInfo, Bytes, HaltReason, OutOfGasError},
+        EvmContext, Inspector, StepAction,
+    };
+
+    #[derive(Default, Debug)]
+    struct LogCounter {
+        logs: usize,
+    }
+
+    impl<DB: Database> Inspector<DB> for LogCounter {
+        fn log(&mut self, _context: &mut EvmContext<DB>, _log: &Log) {
+            self.logs += 1;
+        }
+    }
+
+    #[test]
+    fn test_max_logs_halts() {
+        // emits LOG0 in an infinite loop.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::JUMPDEST,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::LOG0,
+                opcode::PUSH1,
+                0x00,
+                opcode::JUMP,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(LogCounter::default())
+            .modify_cfg_env(|cfg| cfg.max_logs = Some(10))
+            .modify_tx_env(|tx| tx.gas_limit = 1_000_000)
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert_eq!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::LogLimitReached,
+                gas_used: 1_000_000,
+            }
+        );
+        assert_eq!(evm.context.external.logs, 10);
+    }
+
+    fn transact_bytecode(code: Vec<u8>) -> ExecutionResult {
+        let mut evm = evm_calling(
+            BenchmarkDB::new_bytecode(Bytecode::new_raw(code.into())),
+            Address::ZERO,
+        )
+        .build();
+        evm.transact().unwrap().result
+    }
+
+    #[test]
+    fn test_halt_reason_out_of_gas() {
+        // infinite loop.
+        let result = transact_bytecode(vec![opcode::JUMPDEST, opcode::PUSH1, 0x00, opcode::JUMP]);
+        assert_eq!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::OutOfGas(OutOfGasError::Basic),
+                gas_used: 100_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_halt_reason_invalid_opcode() {
+        let result = transact_bytecode(vec![opcode::INVALID]);
+        assert_eq!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::InvalidFEOpcode,
+                gas_used: 100_000,
+            }
+        );
+
+        // 0x0C is not assigned to any opcode.
+        let result = transact_bytecode(vec![0x0C]);
+        assert!(matches!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::OpcodeNotFound,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_halt_reason_stack_underflow() {
+        let result = transact_bytecode(vec![opcode::ADD]);
+        assert!(matches!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::StackUnderflow,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_transact_bundle_rollback() {
+        let caller = address!("1000000000000000000000000000000000000000");
+        let recipient = address!("2000000000000000000000000000000000000000");
+        let reverter = address!("3000000000000000000000000000000000000000");
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            caller,
+            AccountInfo {
+                balance: U256::from(1_000_000),
+                ..Default::default()
+            },
+        );
+        let code = Bytecode::new_raw(
+            vec![opcode::PUSH1, 0x00, opcode::PUSH1, 0x00, opcode::REVERT].into(),
+        );
+        db.insert_account_info(reverter, contract_info(code));
+
+        let transfer = TxEnv {
+            caller,
+            transact_to: TransactTo::Call(recipient),
+            value: U256::from(100),
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+        let revert = TxEnv {
+            caller,
+            transact_to: TransactTo::Call(reverter),
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+
+        let mut evm = Evm::builder().with_db(db).build();
+        let results = evm.transact_bundle(vec![transfer, revert], true).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].result.is_success());
+        assert!(matches!(results[1].result, ExecutionResult::Revert { .. }));
+
+        // changes of the first transaction are rolled back.
+        let db = &evm.context.evm.db;
+        assert!(!db.accounts.contains_key(&recipient));
+        let caller_info = &db.accounts.get(&caller).unwrap().info;
+        assert_eq!(caller_info.balance, U256::from(1_000_000));
+        assert_eq!(caller_info.nonce, 0);
+    }
+
+    #[test]
+    fn test_estimate_gas_with_trace() {
+        use crate::{handler::mainnet, primitives::LatestSpec};
+        use alloc::sync::Arc;
+
+        // reverts if less than 50_000 gas is left.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::GAS,
+                opcode::PUSH2,
+                0xc3,
+                0x50,
+                opcode::LT,
+                opcode::PUSH1,
+                0x0d,
+                opcode::JUMPI,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::REVERT,
+                opcode::JUMPDEST,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode.clone()), Address::ZERO)
+            .modify_tx_env(|tx| tx.gas_limit = 1_000_000)
+            .build();
+
+        let (estimate, trace) = evm.estimate_gas_with_trace().unwrap();
+        let estimate = estimate.unwrap();
+        assert_eq!(estimate, 71_003);
+        assert_eq!(evm.tx().gas_limit, 1_000_000);
+
+        // first attempt is made with the transaction gas limit.
+        assert_eq!(trace[0], (1_000_000, EstimateOutcome::Success));
+        // search narrows to the estimate, failing attempts are below it.
+        assert!(trace.len() > 2);
+        assert!(trace.contains(&(estimate, EstimateOutcome::Success)));
+        assert!(trace
+            .iter()
+            .any(|(_, outcome)| *outcome == EstimateOutcome::Revert));
+        for (gas_limit, outcome) in &trace {
+            assert_eq!(*outcome == EstimateOutcome::Success, *gas_limit >= estimate);
+        }
+        assert!(trace.last().unwrap().0.abs_diff(estimate) <= 1);
+
+        // attempts rejected by the validation are failed attempts.
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .modify_tx_env(|tx| tx.gas_limit = 1_000_000)
+            .append_handler_register(|handler| {
+                handler.validation.initial_tx_gas = Arc::new(|env: &Env| {
+                    if env.tx.gas_limit < 100_000 {
+                        return Err(InvalidTransaction::CallGasCostMoreThanGasLimit.into());
+                    }
+                    mainnet::validate_initial_tx_gas::<LatestSpec, BenchmarkDB>(env)
+                });
+            })
+            .build();
+        let (estimate, trace) = evm.estimate_gas_with_trace().unwrap();
+        assert_eq!(estimate, Some(100_000));
+        assert!(trace.iter().any(|(_, outcome)| *outcome
+            == EstimateOutcome::Invalid(InvalidTransaction::CallGasCostMoreThanGasLimit)));
+    }
+
+    #[cfg(all(feature = "serde", feature = "deterministic"))]
+    #[test]
+    fn test_deterministic_serialized_state() {
+        // writes few storage slots.
+        let mut code = Vec::new();
+        for slot in 1..=8 {
+            code.extend([opcode::PUSH1, slot, opcode::PUSH1, slot, opcode::SSTORE]);
+        }
+        code.push(opcode::STOP);
+
+        let mut evm = evm_calling(
+            BenchmarkDB::new_bytecode(Bytecode::new_raw(code.into())),
+            Address::ZERO,
+        )
+        .modify_tx_env(|tx| tx.gas_limit = 1_000_000)
+        .build();
+
+        let first = serde_json::to_string(&evm.transact().unwrap()).unwrap();
+        let second = serde_json::to_string(&evm.transact().unwrap()).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_beacon_root_contract_call() {
+        use crate::primitives::{hex_literal::hex, BEACON_ROOTS_HISTORY_BUFFER_LENGTH};
+
+        // runtime code of the EIP-4788 beacon roots contract.
+        let code = Bytecode::new_raw(Bytes::from(hex!("3373fffffffffffffffffffffffffffffffffffffffe14604d57602036146024575f5ffd5b5f35801560495762001fff810690815414603c575f5ffd5b62001fff01545f5260205ff35b5f5ffd5b62001fff42064281555f359062001fff015500")));
+        let db = db_with_contract(BEACON_ROOTS_ADDRESS, code);
+
+        let timestamp = 10_000u64;
+        let root = B256::repeat_byte(0xaa);
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_block_env(|block| {
+                block.timestamp = U256::from(timestamp);
+                block.basefee = U256::from(7);
+                block.parent_beacon_block_root = Some(root);
+            })
+            .build();
+
+        let ResultAndState { result, state, .. } =
+            evm.beacon_root_contract_call().unwrap().unwrap();
+        assert!(result.is_success());
+        assert!(!state.contains_key(&SYSTEM_ADDRESS));
+
+        let storage = &state[&BEACON_ROOTS_ADDRESS].storage;
+        let timestamp_slot = U256::from(timestamp % BEACON_ROOTS_HISTORY_BUFFER_LENGTH);
+        let root_slot = timestamp_slot + U256::from(BEACON_ROOTS_HISTORY_BUFFER_LENGTH);
+        assert_eq!(
+            storage[&timestamp_slot].present_value,
+            U256::from(timestamp)
+        );
+        assert_eq!(
+            storage[&root_slot].present_value,
+            U256::from_be_bytes(root.0)
+        );
+
+        // no call before Cancun.
+        evm.modify_spec_id(SpecId::SHANGHAI);
+        assert_eq!(evm.beacon_root_contract_call().unwrap(), None);
+    }
+
+    #[test]
+    fn test_system_call() {
+        let target = Address::with_last_byte(0xaa);
+        let coinbase = Address::with_last_byte(0xc0);
+        let mut db = InMemoryDB::default();
+        let mut evm_with_code = |code: Vec<u8>| {
+            db.insert_account_info(
+                target,
+                AccountInfo {
+                    balance: U256::from(10),
+                    ..contract_info(Bytecode::new_raw(code.into()))
+                },
+            );
+            let mut evm = Evm::builder()
+                .with_db(db.clone())
+                .modify_block_env(|block| block.coinbase = coinbase)
+                .modify_cfg_env(|cfg| cfg.revert_is_error = true)
+                .build();
+            evm.system_call(target, Bytes::new())
+        };
+
+        // returns the gas left, the call has the full gas limit.
+        let ResultAndState { result, state, .. } = evm_with_code(vec![
+            opcode::GAS,
+            opcode::PUSH0,
+            opcode::MSTORE,
+            opcode::PUSH1,
+            0x20,
+            opcode::PUSH0,
+            opcode::RETURN,
+        ])
+        .unwrap();
+        assert_eq!(
+            U256::from_be_slice(result.output().unwrap()),
+            U256::from(SYSTEM_CALL_GAS_LIMIT - 2)
+        );
+        assert!(!state.contains_key(&SYSTEM_ADDRESS));
+        assert!(!state.contains_key(&coinbase));
+
+        // revert is a result.
+        let result = evm_with_code(vec![opcode::PUSH0, opcode::PUSH0, opcode::REVERT])
+            .unwrap()
+            .result;
+        assert!(matches!(result, ExecutionResult::Revert { .. }));
+
+        // beneficiary is kept if the call transfers value to it.
+        let state = evm_with_code(vec![
+            opcode::PUSH0,
+            opcode::PUSH0,
+            opcode::PUSH0,
+            opcode::PUSH0,
+            opcode::PUSH1,
+            0x01,
+            opcode::COINBASE,
+            opcode::GAS,
+            opcode::CALL,
+            opcode::STOP,
+        ])
+        .unwrap()
+        .state;
+        assert_eq!(state[&coinbase].info.balance, U256::from(1));
+        assert_eq!(state[&target].info.balance, U256::from(9));
+    }
+
+    #[test]
+    fn test_transient_storage_cost() {
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x00,
+                opcode::TSTORE,
+                opcode::PUSH1,
+                0x00,
+                opcode::TLOAD,
+                opcode::POP,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO).build();
+
+        let default_gas = evm.transact().unwrap().result.gas_used();
+
+        evm.cfg_mut().transient_load_cost = Some(10);
+        evm.cfg_mut().transient_store_cost = Some(500);
+        let custom_gas = evm.transact().unwrap().result.gas_used();
+
+        assert_eq!(custom_gas, default_gas - (100 - 10) + (500 - 100));
+    }
+
+    #[test]
+    fn test_memory_reallocations() {
+        // expands memory to 64KiB.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH3,
+                0x01,
+                0x00,
+                0x00,
+                opcode::MSTORE,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO).build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        let reallocations = evm.context.evm.memory_reallocations();
+        assert!(reallocations > 0);
+
+        evm.cfg_mut().initial_memory_capacity = Some(1 << 20);
+        assert!(evm.transact().unwrap().result.is_success());
+        assert!(evm.context.evm.memory_reallocations() < reallocations);
+        assert_eq!(evm.context.evm.memory_reallocations(), 0);
+    }
+
+    #[test]
+    fn test_memory_pool() {
+        // calls itself `calldata[0..32]` times, every frame expands memory to 2KiB.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x00,
+                opcode::CALLDATALOAD,
+                opcode::DUP1,
+                opcode::ISZERO,
+                opcode::PUSH1,
+                0x23,
+                opcode::JUMPI,
+                opcode::PUSH1,
+                0x01,
+                opcode::SWAP1,
+                opcode::SUB,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH2,
+                0x07,
+                0xe0,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::ADDRESS,
+                opcode::GAS,
+                opcode::CALL,
+                opcode::POP,
+                opcode::JUMPDEST,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .modify_tx_env(|tx| {
+                tx.data = U256::from(32).to_be_bytes_vec().into();
+                tx.gas_limit = 10_000_000;
+            })
+            .build();
+
+        let result = evm.transact().unwrap();
+        assert!(result.result.is_success());
+        let reallocations = evm.context.evm.memory_reallocations();
+        assert!(reallocations > 0);
+
+        evm = evm.modify().with_memory_pool().build();
+        // first transaction fills the pool.
+        assert_eq!(evm.transact().unwrap(), result);
+        assert_eq!(evm.context.evm.memory_pool.len(), 33);
+
+        // following transactions reuse the buffers.
+        assert_eq!(evm.transact().unwrap(), result);
+        assert_eq!(evm.context.evm.memory_reallocations(), 0);
+    }
+
+    #[test]
+    fn test_revert_reason() {
+        fn revert_with(words: &[(u8, [u8; 32])], len: u8) -> ExecutionResult {
+            let mut code = Vec::new();
+            for (offset, word) in words {
+                code.push(opcode::PUSH32);
+                code.extend_from_slice(word);
+                code.extend_from_slice(&[opcode::PUSH1, *offset, opcode::MSTORE]);
+            }
+            code.extend_from_slice(&[opcode::PUSH1, len, opcode::PUSH1, 0x00, opcode::REVERT]);
+
+            let mut evm = evm_calling(
+                BenchmarkDB::new_bytecode(Bytecode::new_raw(code.into())),
+                Address::ZERO,
+            )
+            .build();
+            evm.transact().unwrap().result
+        }
+
+        // Error("nope")
+        let mut selector = [0u8; 32];
+        selector[..4].copy_from_slice(&[0x08, 0xc3, 0x79, 0xa0]);
+        let mut reason = [0u8; 32];
+        reason[..4].copy_from_slice(b"nope");
+        let result = revert_with(
+            &[
+                (0x00, selector),
+                (0x04, U256::from(0x20).to_be_bytes()),
+                (0x24, U256::from(4).to_be_bytes()),
+                (0x44, reason),
+            ],
+            0x64,
+        );
+        assert!(matches!(result, ExecutionResult::Revert { .. }));
+        assert_eq!(result.revert_reason().as_deref(), Some("nope"));
+
+        // raw bytes
+        let result = revert_with(&[(0x00, [0xab; 32])], 0x20);
+        assert!(matches!(result, ExecutionResult::Revert { .. }));
+        assert_eq!(result.revert_reason(), None);
+    }
+
+    #[test]
+    fn test_tx_type_data() {
+        use crate::{handler::mainnet, primitives::LatestSpec};
+        use alloc::{string::ToString, sync::Arc};
+
+        #[derive(Debug, PartialEq)]
+        struct SponsoredTx {
+            sponsor: Address,
+        }
+
+        let sponsor = address!("2000000000000000000000000000000000000000");
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(Bytecode::new()), Address::ZERO)
+            .modify_tx_env(|tx| tx.set_tx_type_data(SponsoredTx { sponsor }))
+            .append_handler_register(|handler| {
+                handler.validation.tx_against_state = Arc::new(|context, caller_check| {
+                    let Some(tx) = context.evm.env.tx.tx_type_data::<SponsoredTx>() else {
+                        return Err(EVMError::Custom("missing sponsor".to_string()));
+                    };
+                    if tx.sponsor != address!("2000000000000000000000000000000000000000") {
+                        return Err(EVMError::Custom("invalid sponsor".to_string()));
+                    }
+                    mainnet::validate_tx_against_state::<LatestSpec, _, _>(context, caller_check)
+                });
+            })
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(
+            evm.tx().tx_type_data::<SponsoredTx>(),
+            Some(&SponsoredTx { sponsor })
+        );
+
+        evm.tx_mut().tx_type_data = None;
+        assert_eq!(
+            evm.transact().unwrap_err(),
+            EVMError::Custom("missing sponsor".to_string())
+        );
+    }
+
+    #[test]
+    fn test_transact_across_specs() {
+        let bytecode = Bytecode::new_raw(vec![opcode::PUSH0, opcode::STOP].into());
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .with_spec_id(SpecId::CANCUN)
+            .build();
+
+        let tx = TxEnv {
+            caller: address!("1000000000000000000000000000000000000000"),
+            transact_to: TransactTo::Call(Address::ZERO),
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+        let results = evm.transact_across_specs(tx, &[SpecId::MERGE, SpecId::SHANGHAI]);
+        assert_eq!(results.len(), 2);
+
+        let (spec, result) = &results[0];
+        assert_eq!(*spec, SpecId::MERGE);
+        assert_eq!(
+            result.as_ref().unwrap().result,
+            ExecutionResult::Halt {
+                reason: HaltReason::NotActivated,
+                gas_used: 100_000,
+            }
+        );
+
+        let (spec, result) = &results[1];
+        assert_eq!(*spec, SpecId::SHANGHAI);
+        assert!(result.as_ref().unwrap().result.is_success());
+
+        assert_eq!(evm.spec_id(), SpecId::CANCUN);
+    }
+
+    #[test]
+    fn test_max_return_data_size() {
+        let parent = address!("00000000000000000000000000000000000000a0");
+        let child = address!("00000000000000000000000000000000000000a1");
+
+        // calls child three times and stores success of each call to slots 0, 1 and 2.
+        let mut parent_code = Vec::new();
+        for slot in 0..3 {
+            parent_code.extend([
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0xa1,
+                opcode::GAS,
+                opcode::CALL,
+                opcode::PUSH1,
+                slot,
+                opcode::SSTORE,
+            ]);
+        }
+        parent_code.push(opcode::STOP);
+        // returns 1024 bytes.
+        let child_code = vec![
+            opcode::PUSH2,
+            0x04,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::RETURN,
+        ];
+
+        let mut db = InMemoryDB::default();
+        for (address, code) in [(parent, parent_code), (child, child_code)] {
+            let code = Bytecode::new_raw(code.into());
+            db.insert_account_info(address, contract_info(code));
+        }
+
+        let mut evm = evm_calling(db, parent)
+            .modify_cfg_env(|cfg| cfg.max_return_data_size = Some(2500))
+            .modify_tx_env(|tx| tx.gas_limit = 1_000_000)
+            .build();
+
+        let ResultAndState { result, state, .. } = evm.transact().unwrap();
+        assert!(result.is_success());
+        let slot = |index: u64| {
+            state[&parent]
+                .storage
+                .get(&U256::from(index))
+                .map(|slot| slot.present_value)
+                .unwrap_or_default()
+        };
+        // third call exceeds the limit.
+        assert_eq!(slot(0), U256::from(1));
+        assert_eq!(slot(1), U256::from(1));
+        assert_eq!(slot(2), U256::ZERO);
+
+        // limit is not exceeded in a new transaction.
+        evm.tx_mut().transact_to = TransactTo::Call(child);
+        assert!(evm.transact().unwrap().result.is_success());
+
+        evm.cfg_mut().max_return_data_size = Some(100);
+        assert_eq!(
+            evm.transact().unwrap().result,
+            ExecutionResult::Halt {
+                reason: HaltReason::ReturnDataLimit,
+                gas_used: 1_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_run_bytecode() {
+        // adds 1 and 2 and returns the sum.
+        let code = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x02,
+                opcode::ADD,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ]
+            .into(),
+        );
+
+        let mut evm = Evm::builder().with_db(InMemoryDB::default()).build();
+        let result = evm.run_bytecode(code, Bytes::new(), 100_000).unwrap();
+        assert_eq!(result.result, InstructionResult::Return);
+        assert_eq!(
+            result.output,
+            Bytes::copy_from_slice(&U256::from(3).to_be_bytes::<32>())
+        );
+        assert!(result.gas.spend() > 0);
+
+        // stores 7 to slot 1, loads it back and returns it.
+        let code = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x07,
+                opcode::PUSH1,
+                0x01,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x01,
+                opcode::SLOAD,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ]
+            .into(),
+        );
+        let result = evm.run_bytecode(code, Bytes::new(), 100_000).unwrap();
+        assert_eq!(result.result, InstructionResult::Return);
+        assert_eq!(
+            result.output,
+            Bytes::copy_from_slice(&U256::from(7).to_be_bytes::<32>())
+        );
+    }
+
+    #[test]
+    fn test_touched_preimages() {
+        let caller = address!("1000000000000000000000000000000000000000");
+        let contract = address!("2000000000000000000000000000000000000000");
+        let coinbase = address!("00000000000000000000000000000000000000c0");
+        let read_only = address!("00000000000000000000000000000000000000a1");
+
+        // writes slot 1, reads slot 2 and balance of `read_only`.
+        let code = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x07,
+                opcode::PUSH1,
+                0x01,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x02,
+                opcode::SLOAD,
+                opcode::POP,
+                opcode::PUSH1,
+                0xa1,
+                opcode::BALANCE,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+        let db = db_with_contract(contract, code);
+
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_block_env(|block| block.coinbase = coinbase)
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = caller;
+                tx.transact_to = TransactTo::Call(contract);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+
+        let result = evm.transact().unwrap();
+        assert!(result.result.is_success());
+        let (accounts, slots) = result.touched_preimages();
+        assert_eq!(
+            accounts,
+            [caller, contract, coinbase, read_only]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(
+            slots,
+            [(contract, U256::from(1)), (contract, U256::from(2))]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn test_revert_is_error() {
+        // reverts with one byte of output.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x2a,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE8,
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x00,
+                opcode::REVERT,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO).build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(matches!(
+            result,
+            ExecutionResult::Revert { output, .. } if output == Bytes::from_static(&[0x2a])
+        ));
+
+        evm.cfg_mut().revert_is_error = true;
+        assert_eq!(
+            evm.transact().unwrap_err(),
+            EVMError::TransactionReverted {
+                output: Bytes::from_static(&[0x2a])
+            }
+        );
+    }
+
+    #[test]
+    fn test_access_list_gas_delta() {
+        let parent = address!("00000000000000000000000000000000000000a0");
+        let child = address!("00000000000000000000000000000000000000a1");
+
+        // calls child that reads slot 0.
+        let parent_code = vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0xa1,
+            opcode::GAS,
+            opcode::CALL,
+            opcode::STOP,
+        ];
+        let child_code = vec![opcode::PUSH1, 0x00, opcode::SLOAD, opcode::STOP];
+
+        let mut db = InMemoryDB::default();
+        for (address, code) in [(parent, parent_code), (child, child_code)] {
+            let code = Bytecode::new_raw(code.into());
+            db.insert_account_info(address, contract_info(code));
+        }
+
+        let mut evm = Evm::builder().with_db(db).build();
+        let mut tx = TxEnv {
+            caller: address!("1000000000000000000000000000000000000000"),
+            transact_to: TransactTo::Call(parent),
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+
+        // warm account and slot save 2500 + 2000 and cost 2400 + 1900.
+        tx.access_list = vec![(child, vec![U256::ZERO])];
+        assert_eq!(evm.access_list_gas_delta(tx.clone()), Ok(200));
+
+        // unaccessed slot costs 1900 and saves nothing.
+        tx.access_list = vec![(child, vec![U256::from(1)])];
+        assert_eq!(evm.access_list_gas_delta(tx), Ok(100 - 1900));
+    }
+
+    #[test]
+    fn test_call_stipend() {
+        let parent = address!("2000000000000000000000000000000000000000");
+        let child = address!("00000000000000000000000000000000000000a1");
+
+        // calls the child with value 1 and zero gas, returns the success flag.
+        let parent_code = vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0xa1,
+            opcode::PUSH1,
+            0x00,
+            opcode::CALL,
+            opcode::PUSH1,
+            0x00,
+            opcode::MSTORE,
+            opcode::PUSH1,
+            0x20,
+            opcode::PUSH1,
+            0x00,
+            opcode::RETURN,
+        ];
+        // expands memory to 641 words, uses 2733 gas.
+        let child_code = vec![
+            opcode::PUSH2,
+            0x50,
+            0x00,
+            opcode::MLOAD,
+            opcode::POP,
+            opcode::STOP,
+        ];
+
+        let mut db = InMemoryDB::default();
+        for (address, code, balance) in [
+            (parent, parent_code, U256::from(1)),
+            (child, child_code, U256::ZERO),
+        ] {
+            let code = Bytecode::new_raw(code.into());
+            db.insert_account_info(
+                address,
+                AccountInfo {
+                    balance,
+                    ..contract_info(code)
+                },
+            );
+        }
+
+        let mut evm = evm_calling(db, parent).build();
+
+        let output = evm.transact().unwrap().result.into_output().unwrap();
+        // default stipend of 2300 is not enough.
+        assert_eq!(U256::from_be_slice(&output), U256::ZERO);
+
+        evm.cfg_mut().call_stipend = Some(5000);
+        let output = evm.transact().unwrap().result.into_output().unwrap();
+        assert_eq!(U256::from_be_slice(&output), U256::from(1));
+    }
+
+    #[test]
+    fn test_lenient_returndatacopy() {
+        // fills memory with non zero bytes, copies 32 bytes of empty return data over it
+        // and returns the memory.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0xff,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURNDATACOPY,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO).build();
+
+        // copy past the end of the buffer halts by default.
+        let result = evm.transact().unwrap().result;
+        assert!(matches!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::OutOfOffset,
+                ..
+            }
+        ));
+
+        // copy past the end of the buffer is zero-filled.
+        evm.cfg_mut().lenient_returndatacopy = true;
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        assert_eq!(result.output().unwrap().as_ref(), &[0u8; 32]);
+    }
+
+    #[derive(Default, Debug)]
+    struct InterruptInspector {
+        interrupt_at: usize,
+        opcodes: Vec<u8>,
+    }
+
+    impl<DB: Database> Inspector<DB> for InterruptInspector {
+        fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) -> StepAction {
+            if self.opcodes.len() == self.interrupt_at {
+                interp.instruction_result = InstructionResult::Interrupted;
+                return StepAction::Continue;
+            }
+            self.opcodes.push(interp.current_opcode());
+            StepAction::Continue
+        }
+    }
+
+    #[test]
+    fn test_interrupted_execution() {
+        let parent = address!("2000000000000000000000000000000000000000");
+        let child = address!("00000000000000000000000000000000000000a1");
+        let sstore =
+            |value: u8, slot: u8| [opcode::PUSH1, value, opcode::PUSH1, slot, opcode::SSTORE];
+        // stores to slot 0, calls the child with all gas and stores to slot 1.
+        let mut parent_code = sstore(0x01, 0x00).to_vec();
+        parent_code.extend([
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0xa1,
+            opcode::GAS,
+            opcode::CALL,
+        ]);
+        parent_code.extend(sstore(0x01, 0x01));
+        parent_code.push(opcode::STOP);
+        // stores to slot 0 and slot 1.
+        let mut child_code = sstore(0x01, 0x00).to_vec();
+        child_code.extend(sstore(0x01, 0x01));
+        child_code.push(opcode::STOP);
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(parent, contract_info(Bytecode::new_raw(parent_code.into())));
+        db.insert_account_info(child, contract_info(Bytecode::new_raw(child_code.into())));
+
+        // interrupts the second SSTORE of the child.
+        let mut evm = evm_calling(db, parent)
+            .with_external_context(InterruptInspector {
+                interrupt_at: 16,
+                ..Default::default()
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let ResultAndState { result, state, .. } = evm.transact().unwrap();
+        assert!(result.is_partial());
+        // gas used up to the interrupt is charged, two cold SSTOREs and a cold CALL.
+        assert_eq!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::Interrupted,
+                gas_used: 21_000 + 2 * (22_100 + 6) + 2_600 + 6 * 3 + 2 + 2 * 3,
+            }
+        );
+        // changes up to the interrupt are kept and the parent does not continue.
+        let slots = |address: Address| {
+            let mut slots = state[&address]
+                .storage
+                .iter()
+                .map(|(slot, value)| (*slot, value.present_value))
+                .collect::<Vec<_>>();
+            slots.sort();
+            slots
+        };
+        assert_eq!(slots(parent), vec![(U256::ZERO, U256::from(1))]);
+        assert_eq!(slots(child), vec![(U256::ZERO, U256::from(1))]);
+        // inspector saw the steps up to the interrupt.
+        let opcodes = &evm.context.external.opcodes;
+        assert_eq!(opcodes.len(), 16);
+        assert_eq!(opcodes[9..11], [opcode::GAS, opcode::CALL]);
+        assert_eq!(opcodes[13], opcode::SSTORE);
+        assert_eq!(opcodes[15], opcode::PUSH1);
+    }
+
+    #[derive(Default, Debug)]
+    struct StepCounter {
+        steps: usize,
+    }
+
+    impl<DB: Database> Inspector<DB> for StepCounter {
+        fn step(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<DB>) -> StepAction {
+            self.steps += 1;
+            StepAction::Continue
+        }
+    }
+
+    #[test]
+    fn test_inspect() {
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x02,
+                opcode::ADD,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO).build();
+
+        let (ResultAndState { result, .. }, inspector) =
+            evm.inspect(StepCounter::default()).unwrap();
+        assert!(result.is_success());
+        assert_eq!(inspector.steps, 4);
+    }
+}
@@ -30,6 +30,10 @@ pub struct FrameData {
     pub checkpoint: JournalCheckpoint,
     /// Interpreter
     pub interpreter: Interpreter,
+    /// Gas given to the sub-calls and creates of the frame, including the call stipend.
+    pub gas_forwarded: u64,
+    /// Gas returned to the frame by its sub-calls and creates.
+    pub gas_returned: u64,
 }
 
 /// Call stack frame.
@@ -128,6 +132,8 @@ impl Frame {
             frame_data: FrameData {
                 checkpoint,
                 interpreter,
+                gas_forwarded: 0,
+                gas_returned: 0,
             },
         }))
     }
@@ -142,6 +148,8 @@ impl Frame {
             frame_data: FrameData {
                 checkpoint,
                 interpreter,
+                gas_forwarded: 0,
+                gas_returned: 0,
             },
         }))
     }
@@ -217,19 +225,19 @@ impl FrameOrResult {
         interpreter_result: InterpreterResult,
         address: Option<Address>,
     ) -> Self {
-        FrameOrResult::Result(FrameResult::Create(CreateOutcome {
-            result: interpreter_result,
+        FrameOrResult::Result(FrameResult::Create(CreateOutcome::new(
+            interpreter_result,
             address,
-        }))
+        )))
     }
 
     pub fn new_call_result(
         interpreter_result: InterpreterResult,
         memory_offset: Range<usize>,
     ) -> Self {
-        FrameOrResult::Result(FrameResult::Call(CallOutcome {
-            result: interpreter_result,
+        FrameOrResult::Result(FrameResult::Call(CallOutcome::new(
+            interpreter_result,
             memory_offset,
-        }))
+        )))
     }
 }
@@ -1,21 +1,23 @@
 // Modules.
+mod fuel;
 mod handle_types;
 pub mod mainnet;
 pub mod register;
 
 // Exports.
+pub use fuel::fuel_handle_register;
 pub use handle_types::*;
 
 // Includes.
 use crate::{
     interpreter::{
-        opcode::{make_instruction_table, InstructionTables},
+        opcode::{make_instruction_table, BoxedInstruction, InstructionTables},
         Host,
     },
     primitives::{db::Database, spec_to_generic, HandlerCfg, Spec, SpecId},
     Evm,
 };
-use alloc::vec::Vec;
+use alloc::{boxed::Box, vec::Vec};
 use register::{EvmHandler, HandleRegisters};
 
 use self::register::{HandleRegister, HandleRegisterBox};
@@ -117,6 +119,38 @@ impl<'a, EXT, DB: Database> EvmHandler<'a, EXT, DB> {
         self.instruction_table = Some(table);
     }
 
+    /// Replaces instruction of the given opcode and returns the previous instruction,
+    /// so the new instruction can call it.
+    ///
+    /// Plain instruction table is converted to boxed table, other instructions are
+    /// not rebuilt.
+    ///
+    /// Override is not a handle register, it is lost when the registers are reapplied,
+    /// for example on spec change.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instruction table was taken, e.g. while the Evm is running.
+    pub fn override_instruction(
+        &mut self,
+        opcode: u8,
+        instruction: BoxedInstruction<'a, Evm<'a, EXT, DB>>,
+    ) -> BoxedInstruction<'a, Evm<'a, EXT, DB>> {
+        let table = self
+            .instruction_table
+            .take()
+            .expect("Instruction table was taken and can't be overridden");
+        let mut table = match table {
+            InstructionTables::Plain(table) => {
+                table.map(|i| -> BoxedInstruction<'a, _> { Box::new(i) })
+            }
+            InstructionTables::Boxed(table) => table,
+        };
+        let previous = core::mem::replace(&mut table[opcode as usize], instruction);
+        self.instruction_table = Some(InstructionTables::Boxed(table));
+        previous
+    }
+
     /// Returns reference to pre execution handler.
     pub fn pre_execution(&self) -> &PreExecutionHandler<'a, EXT, DB> {
         &self.pre_execution
@@ -195,12 +229,14 @@ impl<'a, EXT, DB: Database> EvmHandler<'a, EXT, DB> {
             handler.append_handler_register(register)
         }
         handler.cfg = self.cfg();
+        handler.cfg.spec_id = spec_id;
         *self = handler;
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::test_utils::evm_calling;
     use core::cell::RefCell;
 
     use crate::{db::EmptyDB, primitives::EVMError};
@@ -232,4 +268,68 @@ mod test {
         // first handler is reapplied
         assert_eq!(*test.borrow(), 3);
     }
+
+    #[test]
+    fn test_override_instruction() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::{opcode, Interpreter},
+            primitives::{Address, Bytecode, U256},
+        };
+        use core::cell::Cell;
+
+        // returns 1 + 2.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x02,
+                opcode::ADD,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ]
+            .into(),
+        );
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO).build();
+
+        // counts ADD and executes the original instruction.
+        let count = Rc::new(Cell::new(0));
+        let counter = count.clone();
+        let add = evm
+            .handler
+            .override_instruction(opcode::ADD, Box::new(|_, _| ()));
+        // previous instruction is the placeholder.
+        let _ = evm.handler.override_instruction(
+            opcode::ADD,
+            Box::new(
+                move |interpreter: &mut Interpreter, host: &mut Evm<'_, (), BenchmarkDB>| {
+                    counter.set(counter.get() + 1);
+                    add(interpreter, host);
+                },
+            ),
+        );
+        assert!(matches!(
+            evm.handler.instruction_table,
+            Some(InstructionTables::Boxed(_))
+        ));
+
+        let result = evm.transact().unwrap().result;
+        assert_eq!(U256::from_be_slice(result.output().unwrap()), U256::from(3));
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Instruction table was taken")]
+    fn test_override_instruction_without_table() {
+        let mut handler = EvmHandler::<(), EmptyDB>::new(HandlerCfg::new(SpecId::LATEST));
+        handler.take_instruction_table();
+        let _ = handler.override_instruction(0x00, Box::new(|_, _| ()));
+    }
 }
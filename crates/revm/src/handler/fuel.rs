@@ -0,0 +1,108 @@
+//! Fuel metering. Charges one unit of fuel for every executed instruction.
+
+use crate::{
+    db::Database,
+    handler::register::{EvmHandler, EvmInstructionTables},
+    interpreter::{opcode::BoxedInstruction, Interpreter},
+    Evm,
+};
+use alloc::{boxed::Box, vec::Vec};
+
+/// Register that wraps every instruction to charge one unit of fuel when it is executed.
+///
+/// Fuel is independent of gas and does not affect execution. It is reset at the start of
+/// every transaction and can be read with [`EvmContext::fuel_used`](crate::EvmContext::fuel_used).
+/// Without this register fuel is not counted.
+pub fn fuel_handle_register<'a, EXT, DB: Database>(handler: &mut EvmHandler<'a, EXT, DB>) {
+    let table = handler
+        .instruction_table
+        .take()
+        .expect("Handler must have instruction table");
+    let table = match table {
+        EvmInstructionTables::Plain(table) => table
+            .into_iter()
+            .map(|i| fuel_instruction(i))
+            .collect::<Vec<_>>(),
+        EvmInstructionTables::Boxed(table) => table
+            .into_iter()
+            .map(|i| fuel_instruction(i))
+            .collect::<Vec<_>>(),
+    };
+
+    // cast vector to array.
+    handler.instruction_table = Some(EvmInstructionTables::Boxed(
+        table.try_into().unwrap_or_else(|_| unreachable!()),
+    ));
+}
+
+/// Wraps the instruction to charge one unit of fuel before it is executed.
+fn fuel_instruction<
+    'a,
+    EXT,
+    DB: Database,
+    Instruction: Fn(&mut Interpreter, &mut Evm<'a, EXT, DB>) + 'a,
+>(
+    instruction: Instruction,
+) -> BoxedInstruction<'a, Evm<'a, EXT, DB>> {
+    Box::new(
+        move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
+            host.context.evm.fuel_used += 1;
+            instruction(interpreter, host);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::evm_calling;
+    use crate::{
+        db::BenchmarkDB,
+        inspector::inspector_handle_register,
+        interpreter::opcode,
+        primitives::{Address, Bytecode},
+        EvmContext, Inspector, StepAction,
+    };
+
+    #[derive(Default, Debug)]
+    struct StepCounter {
+        steps: u64,
+    }
+
+    impl<DB: Database> Inspector<DB> for StepCounter {
+        fn step(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<DB>) -> StepAction {
+            self.steps += 1;
+            StepAction::Continue
+        }
+    }
+
+    #[test]
+    fn test_fuel_used() {
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x02,
+                opcode::ADD,
+                opcode::POP,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(StepCounter::default())
+            .append_handler_register(inspector_handle_register)
+            .append_handler_register(fuel_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(evm.context.evm.fuel_used(), 5);
+        assert_eq!(evm.context.evm.fuel_used(), evm.context.external.steps);
+
+        // fuel is reset for every transaction.
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(evm.context.evm.fuel_used(), 5);
+    }
+}
@@ -8,16 +8,19 @@ pub mod validation;
 // Exports
 
 pub use validation::{
-    ValidateEnvHandle, ValidateInitialTxGasHandle, ValidateTxEnvAgainstState, ValidationHandler,
+    CallerCheck, CallerCheckHandle, ValidateBlobHashHandle, ValidateEnvHandle,
+    ValidateInitialTxGasHandle, ValidateTxEnvAgainstState, ValidationHandler,
 };
 
 pub use execution::{
-    ExecutionHandler, FrameCallHandle, FrameCallReturnHandle, FrameCreateHandle,
-    FrameCreateReturnHandle, InsertCallOutcomeHandle, InsertCreateOutcomeHandle,
+    CallAccessGasHandle, CreateAddressHandle, ExecutionHandler, FrameCallHandle,
+    FrameCallReturnHandle, FrameCreateHandle, FrameCreateReturnHandle, InsertCallOutcomeHandle,
+    InsertCreateOutcomeHandle, ZeroAddressCallHandle,
 };
 
 pub use pre_execution::{
-    DeductCallerHandle, LoadAccountsHandle, LoadPrecompilesHandle, PreExecutionHandler,
+    DeductCallerHandle, LoadAccountsHandle, LoadPrecompilesHandle, PostSetupHandle,
+    PreExecutionHandler, TxFees, TxFeesHandle,
 };
 
 pub use post_execution::{
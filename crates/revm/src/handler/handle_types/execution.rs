@@ -1,8 +1,8 @@
 use crate::{
     handler::mainnet,
     interpreter::{CallInputs, CreateInputs, SharedMemory},
-    primitives::{db::Database, Spec},
-    CallFrame, Context, CreateFrame, Frame, FrameOrResult, FrameResult,
+    primitives::{db::Database, Address, Bytes, CreateScheme, Spec},
+    CallFrame, Context, CreateFrame, EvmContext, Frame, FrameOrResult, FrameResult,
 };
 use alloc::{boxed::Box, sync::Arc};
 
@@ -13,8 +13,17 @@ pub type LastFrameReturnHandle<'a, EXT, DB> =
     Arc<dyn Fn(&mut Context<EXT, DB>, &mut FrameResult) + 'a>;
 
 /// Handle sub call.
-pub type FrameCallHandle<'a, EXT, DB> =
-    Arc<dyn Fn(&mut Context<EXT, DB>, Box<CallInputs>) -> FrameOrResult + 'a>;
+///
+/// Receives the custom behavior of calls to `address(0)` of
+/// [ExecutionHandler::zero_address_call] if it is set.
+pub type FrameCallHandle<'a, EXT, DB> = Arc<
+    dyn Fn(
+            &mut Context<EXT, DB>,
+            Box<CallInputs>,
+            Option<&ZeroAddressCallHandle<'a, EXT, DB>>,
+        ) -> FrameOrResult
+        + 'a,
+>;
 
 /// Handle call return
 pub type FrameCallReturnHandle<'a, EXT, DB> =
@@ -25,8 +34,16 @@ pub type InsertCallOutcomeHandle<'a, EXT, DB> =
     Arc<dyn Fn(&mut Context<EXT, DB>, &mut Frame, &mut SharedMemory, CallOutcome) + 'a>;
 
 /// Handle sub create.
-pub type FrameCreateHandle<'a, EXT, DB> =
-    Arc<dyn Fn(&mut Context<EXT, DB>, Box<CreateInputs>) -> FrameOrResult + 'a>;
+///
+/// Receives the custom address derivation of [ExecutionHandler::create_address] if it is set.
+pub type FrameCreateHandle<'a, EXT, DB> = Arc<
+    dyn Fn(
+            &mut Context<EXT, DB>,
+            Box<CreateInputs>,
+            Option<&CreateAddressHandle<'a>>,
+        ) -> FrameOrResult
+        + 'a,
+>;
 
 /// Handle create return
 pub type FrameCreateReturnHandle<'a, EXT, DB> =
@@ -36,6 +53,27 @@ pub type FrameCreateReturnHandle<'a, EXT, DB> =
 pub type InsertCreateOutcomeHandle<'a, EXT, DB> =
     Arc<dyn Fn(&mut Context<EXT, DB>, &mut Frame, CreateOutcome) + 'a>;
 
+/// Custom account access gas cost of a call.
+///
+/// Receives the call target, whether the target has no code, whether the target is cold
+/// and the context. Target account is loaded and can be inspected through the journaled
+/// state.
+pub type CallAccessGasHandle<'a, DB> =
+    Arc<dyn Fn(Address, bool, bool, &EvmContext<DB>) -> u64 + 'a>;
+
+/// Custom address derivation of created contracts.
+///
+/// Receives the caller, the caller nonce before it is bumped, the init code and the create
+/// scheme and returns the address of the created contract.
+pub type CreateAddressHandle<'a> = Arc<dyn Fn(Address, u64, &Bytes, CreateScheme) -> Address + 'a>;
+
+/// Custom behavior of calls to `address(0)`.
+///
+/// Receives the context and the call inputs and returns a new frame or the call result
+/// directly. Value transfer and checkpointing are left to the handle.
+pub type ZeroAddressCallHandle<'a, EXT, DB> =
+    Arc<dyn Fn(&mut Context<EXT, DB>, &CallInputs) -> FrameOrResult + 'a>;
+
 /// Handles related to stack frames.
 pub struct ExecutionHandler<'a, EXT, DB: Database> {
     /// Handles last frame return, modified gas for refund and
@@ -53,6 +91,15 @@ pub struct ExecutionHandler<'a, EXT, DB: Database> {
     pub create_return: FrameCreateReturnHandle<'a, EXT, DB>,
     /// Insert create outcome.
     pub insert_create_outcome: InsertCreateOutcomeHandle<'a, EXT, DB>,
+    /// Custom account access gas of CALL-like opcodes. If not set, mainnet cost is used.
+    pub call_access_gas: Option<CallAccessGasHandle<'a, DB>>,
+    /// Custom address derivation of created contracts. If not set, mainnet CREATE and
+    /// CREATE2 addresses are used.
+    ///
+    /// Collision check and caller nonce bump are done by the create handle either way.
+    pub create_address: Option<CreateAddressHandle<'a>>,
+    /// Custom behavior of calls to `address(0)`. If not set, they are plain account calls.
+    pub zero_address_call: Option<ZeroAddressCallHandle<'a, EXT, DB>>,
 }
 
 impl<'a, EXT: 'a, DB: Database + 'a> ExecutionHandler<'a, EXT, DB> {
@@ -66,6 +113,9 @@ impl<'a, EXT: 'a, DB: Database + 'a> ExecutionHandler<'a, EXT, DB> {
             create: Arc::new(mainnet::create::<SPEC, EXT, DB>),
             create_return: Arc::new(mainnet::create_return::<SPEC, EXT, DB>),
             insert_create_outcome: Arc::new(mainnet::insert_create_outcome),
+            call_access_gas: None,
+            create_address: None,
+            zero_address_call: None,
         }
     }
 }
@@ -84,7 +134,7 @@ impl<'a, EXT, DB: Database> ExecutionHandler<'a, EXT, DB> {
     /// Call frame call handler.
     #[inline]
     pub fn call(&self, context: &mut Context<EXT, DB>, inputs: Box<CallInputs>) -> FrameOrResult {
-        (self.call)(context, inputs.clone())
+        (self.call)(context, inputs, self.zero_address_call.as_ref())
     }
 
     /// Call registered handler for call return.
@@ -117,7 +167,7 @@ impl<'a, EXT, DB: Database> ExecutionHandler<'a, EXT, DB> {
         context: &mut Context<EXT, DB>,
         inputs: Box<CreateInputs>,
     ) -> FrameOrResult {
-        (self.create)(context, inputs)
+        (self.create)(context, inputs, self.create_address.as_ref())
     }
 
     /// Call handler for create return.
@@ -40,6 +40,9 @@ pub struct PostExecutionHandler<'a, EXT, DB: Database> {
     /// Reimburse the caller with ethereum it didn't spent.
     pub reimburse_caller: ReimburseCallerHandle<'a, EXT, DB>,
     /// Reward the beneficiary with caller fee.
+    ///
+    /// Mainnet credits the priority fee of the used gas to the block coinbase, base fee is
+    /// burned (EIP-1559). Wrap it to split or redirect the fee.
     pub reward_beneficiary: RewardBeneficiaryHandle<'a, EXT, DB>,
     /// Main return handle, returns the output of the transact.
     pub output: OutputHandle<'a, EXT, DB>,
@@ -1,7 +1,7 @@
 // Includes.
 use crate::{
     handler::mainnet,
-    primitives::{db::Database, EVMError, EVMResultGeneric, Spec},
+    primitives::{db::Database, EVMError, EVMResultGeneric, Env, Spec, U256},
     Context,
 };
 use alloc::sync::Arc;
@@ -16,18 +16,73 @@ pub type LoadPrecompilesHandle<'a> = Arc<dyn Fn() -> Precompiles + 'a>;
 pub type LoadAccountsHandle<'a, EXT, DB> =
     Arc<dyn Fn(&mut Context<EXT, DB>) -> Result<(), EVMError<<DB as Database>::Error>> + 'a>;
 
+/// Fees of the transaction.
+///
+/// Caller is charged `gas_price` for every gas of the gas limit and the `data_fee`,
+/// unused and refunded gas is reimbursed at `gas_price`. Beneficiary is credited
+/// `beneficiary_gas_price` for every used gas.
+///
+/// Before that the caller has to afford the [`TxFees::max_tx_cost`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TxFees {
+    /// Price of gas paid by the caller.
+    pub gas_price: U256,
+    /// Price of gas credited to the beneficiary.
+    pub beneficiary_gas_price: U256,
+    /// Fee paid by the caller on top of the gas, not reimbursed.
+    pub data_fee: U256,
+    /// Maximum price of gas the caller has to afford, for example the EIP-1559 max fee.
+    pub max_gas_price: U256,
+    /// Maximum data fee the caller has to afford, for example the EIP-4844 max blob fee.
+    pub max_data_fee: U256,
+}
+
+impl TxFees {
+    /// Returns the balance the caller needs for a transaction with the given `gas_limit`
+    /// and `value`, or `None` on overflow.
+    ///
+    /// Maximum fees lower than the charged fees are raised to them, so the caller can
+    /// always be charged.
+    pub fn max_tx_cost(&self, gas_limit: u64, value: U256) -> Option<U256> {
+        U256::from(gas_limit)
+            .checked_mul(self.max_gas_price.max(self.gas_price))?
+            .checked_add(self.max_data_fee.max(self.data_fee))?
+            .checked_add(value)
+    }
+}
+
+/// Computes fees of the transaction.
+pub type TxFeesHandle<'a> = Arc<dyn Fn(&Env) -> TxFees + 'a>;
+
 /// Deduct the caller to its limit.
 pub type DeductCallerHandle<'a, EXT, DB> =
     Arc<dyn Fn(&mut Context<EXT, DB>) -> EVMResultGeneric<(), <DB as Database>::Error> + 'a>;
 
+/// Custom setup after the caller is deducted and before the first frame is created.
+pub type PostSetupHandle<'a, EXT, DB> =
+    Arc<dyn Fn(&mut Context<EXT, DB>) -> EVMResultGeneric<(), <DB as Database>::Error> + 'a>;
+
 /// Handles related to pre execution before the stack loop is started.
 pub struct PreExecutionHandler<'a, EXT, DB: Database> {
     /// Load precompiles
     pub load_precompiles: LoadPrecompilesHandle<'a>,
     /// Main load handle
     pub load_accounts: LoadAccountsHandle<'a, EXT, DB>,
+    /// Computes fees of the transaction, they are used to validate the caller balance,
+    /// deduct the caller, reimburse it and reward the beneficiary.
+    ///
+    /// Mainnet uses the EIP-1559 effective gas price, burns the base fee since London and
+    /// charges the EIP-4844 data fee since Cancun. Chains with a different fee market can
+    /// replace it. Fees are computed after the initial gas and before the caller is
+    /// validated, and stored in [`EvmContext::tx_fees`](crate::EvmContext::tx_fees).
+    pub tx_fees: TxFeesHandle<'a>,
     /// Deduct max value from the caller.
     pub deduct_caller: DeductCallerHandle<'a, EXT, DB>,
+    /// Custom setup that runs after pre execution and before the first frame,
+    /// for example system contract calls like EIP-4788 beacon root update.
+    ///
+    /// By default it does nothing.
+    pub post_setup: PostSetupHandle<'a, EXT, DB>,
 }
 
 impl<'a, EXT: 'a, DB: Database + 'a> PreExecutionHandler<'a, EXT, DB> {
@@ -36,12 +91,19 @@ impl<'a, EXT: 'a, DB: Database + 'a> PreExecutionHandler<'a, EXT, DB> {
         Self {
             load_precompiles: Arc::new(mainnet::load_precompiles::<SPEC>),
             load_accounts: Arc::new(mainnet::load_accounts::<SPEC, EXT, DB>),
+            tx_fees: Arc::new(mainnet::tx_fees::<SPEC>),
             deduct_caller: Arc::new(mainnet::deduct_caller::<SPEC, EXT, DB>),
+            post_setup: Arc::new(mainnet::post_setup::<EXT, DB>),
         }
     }
 }
 
 impl<'a, EXT, DB: Database> PreExecutionHandler<'a, EXT, DB> {
+    /// Computes fees of the transaction.
+    pub fn tx_fees(&self, env: &Env) -> TxFees {
+        (self.tx_fees)(env)
+    }
+
     /// Deduct caller to its limit.
     pub fn deduct_caller(&self, context: &mut Context<EXT, DB>) -> Result<(), EVMError<DB::Error>> {
         (self.deduct_caller)(context)
@@ -56,4 +118,9 @@ impl<'a, EXT, DB: Database> PreExecutionHandler<'a, EXT, DB> {
     pub fn load_precompiles(&self) -> Precompiles {
         (self.load_precompiles)()
     }
+
+    /// Custom setup before the first frame.
+    pub fn post_setup(&self, context: &mut Context<EXT, DB>) -> Result<(), EVMError<DB::Error>> {
+        (self.post_setup)(context)
+    }
 }
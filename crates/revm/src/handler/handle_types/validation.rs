@@ -1,6 +1,6 @@
 use crate::{
     handler::mainnet,
-    primitives::{db::Database, EVMError, Env, Spec},
+    primitives::{db::Database, EVMError, Env, InvalidTransaction, Spec, B256, U256},
     Context,
 };
 use alloc::sync::Arc;
@@ -9,16 +9,60 @@ use alloc::sync::Arc;
 pub type ValidateEnvHandle<'a, DB> =
     Arc<dyn Fn(&Env) -> Result<(), EVMError<<DB as Database>::Error>> + 'a>;
 
+/// Value of the transaction that is checked against the caller account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CallerCheck {
+    /// Transaction nonce is compared to the caller account nonce.
+    Nonce {
+        /// Nonce of the transaction.
+        tx_nonce: u64,
+        /// Nonce of the caller account.
+        account_nonce: u64,
+    },
+    /// Balance required by the transaction is compared to the caller balance.
+    Balance {
+        /// Balance required to pay for the transaction.
+        required: U256,
+        /// Balance of the caller account.
+        available: U256,
+    },
+}
+
+/// Handle called before a value of the transaction is checked against the caller account.
+pub type CallerCheckHandle<'a, EXT, DB> = Arc<dyn Fn(&mut Context<EXT, DB>, CallerCheck) + 'a>;
+
 /// Handle that validates transaction environment against the state.
-/// Second parametar is initial gas.
-pub type ValidateTxEnvAgainstState<'a, EXT, DB> =
-    Arc<dyn Fn(&mut Context<EXT, DB>) -> Result<(), EVMError<<DB as Database>::Error>> + 'a>;
+/// Second parameter is the caller check handle to call before each caller check.
+pub type ValidateTxEnvAgainstState<'a, EXT, DB> = Arc<
+    dyn Fn(
+            &mut Context<EXT, DB>,
+            &CallerCheckHandle<'a, EXT, DB>,
+        ) -> Result<(), EVMError<<DB as Database>::Error>>
+        + 'a,
+>;
+
+/// Handle that validates single blob versioned hash of the transaction.
+/// Returns true if the hash is valid.
+pub type ValidateBlobHashHandle<'a, EXT, DB> =
+    Arc<dyn Fn(&mut Context<EXT, DB>, B256) -> bool + 'a>;
 
 /// Initial gas calculation handle
 pub type ValidateInitialTxGasHandle<'a, DB> =
     Arc<dyn Fn(&Env) -> Result<u64, EVMError<<DB as Database>::Error>> + 'a>;
 
 /// Handles related to validation.
+///
+/// Handles are called before the transaction is executed in this order:
+/// 1. [`ValidationHandler::env`] validates block, transaction and configuration fields,
+///    then [`ValidationHandler::blob_hash`] is called for every blob versioned hash of a blob transaction,
+/// 2. [`ValidationHandler::initial_tx_gas`] calculates initial gas and checks it against the gas limit,
+/// 3. [`ValidationHandler::tx_against_state`] loads the caller and checks its nonce and balance,
+///    [`ValidationHandler::caller_check`] is called before each of the checks. Balance is checked
+///    against the fees of [`PreExecutionHandler::tx_fees`](crate::handler::PreExecutionHandler::tx_fees)
+///    that are computed between the second and the third step.
+///
+/// The first error stops the validation. Handles can be replaced in a handler register,
+/// to keep the mainnet checks the register can call the replaced handle from the new one.
 pub struct ValidationHandler<'a, EXT, DB: Database> {
     /// Validate and calculate initial transaction gas.
     pub initial_tx_gas: ValidateInitialTxGasHandle<'a, DB>,
@@ -26,6 +70,14 @@ pub struct ValidationHandler<'a, EXT, DB: Database> {
     pub tx_against_state: ValidateTxEnvAgainstState<'a, EXT, DB>,
     /// Validate Env.
     pub env: ValidateEnvHandle<'a, DB>,
+    /// Validate blob versioned hash.
+    ///
+    /// By default hashes have to start with the KZG version, see
+    /// [`Env::is_valid_blob_versioned_hash`]. A rejected hash fails the transaction
+    /// with `BlobVersionNotSupported`.
+    pub blob_hash: ValidateBlobHashHandle<'a, EXT, DB>,
+    /// Called before the nonce and balance of the caller are checked, does nothing by default.
+    pub caller_check: CallerCheckHandle<'a, EXT, DB>,
 }
 
 impl<'a, EXT: 'a, DB: Database + 'a> ValidationHandler<'a, EXT, DB> {
@@ -35,6 +87,8 @@ impl<'a, EXT: 'a, DB: Database + 'a> ValidationHandler<'a, EXT, DB> {
             initial_tx_gas: Arc::new(mainnet::validate_initial_tx_gas::<SPEC, DB>),
             env: Arc::new(mainnet::validate_env::<SPEC, DB>),
             tx_against_state: Arc::new(mainnet::validate_tx_against_state::<SPEC, EXT, DB>),
+            blob_hash: Arc::new(mainnet::validate_blob_hash::<EXT, DB>),
+            caller_check: Arc::new(|_, _| {}),
         }
     }
 }
@@ -45,6 +99,20 @@ impl<'a, EXT, DB: Database> ValidationHandler<'a, EXT, DB> {
         (self.env)(env)
     }
 
+    /// Validate blob versioned hashes of the blob transaction.
+    pub fn blob_hashes(&self, context: &mut Context<EXT, DB>) -> Result<(), EVMError<DB::Error>> {
+        if context.evm.env.tx.max_fee_per_blob_gas.is_none() {
+            return Ok(());
+        }
+        for i in 0..context.evm.env.tx.blob_hashes.len() {
+            let hash = context.evm.env.tx.blob_hashes[i];
+            if !(self.blob_hash)(context, hash) {
+                return Err(InvalidTransaction::BlobVersionNotSupported.into());
+            }
+        }
+        Ok(())
+    }
+
     /// Initial gas
     pub fn initial_tx_gas(&self, env: &Env) -> Result<u64, EVMError<DB::Error>> {
         (self.initial_tx_gas)(env)
@@ -55,6 +123,58 @@ impl<'a, EXT, DB: Database> ValidationHandler<'a, EXT, DB> {
         &self,
         context: &mut Context<EXT, DB>,
     ) -> Result<(), EVMError<DB::Error>> {
-        (self.tx_against_state)(context)
+        (self.tx_against_state)(context, &self.caller_check)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::InMemoryDB,
+        primitives::{address, AccountInfo, EVMError, InvalidTransaction, TransactTo, B256, U256},
+        Evm,
+    };
+    use alloc::sync::Arc;
+
+    #[test]
+    fn test_custom_blob_hash() {
+        let caller = address!("1000000000000000000000000000000000000000");
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            caller,
+            AccountInfo::from_balance(U256::from(10).pow(U256::from(18))),
+        );
+        let hash = |version: u8| {
+            let mut hash = B256::with_last_byte(1);
+            hash[0] = version;
+            hash
+        };
+
+        // accepts hashes with version 2 only.
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_block_env(|block| block.set_blob_excess_gas_and_price(0))
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = caller;
+                tx.transact_to =
+                    TransactTo::Call(address!("0000000000000000000000000000000000000000"));
+                tx.gas_limit = 21_000;
+                tx.max_fee_per_blob_gas = Some(U256::from(1));
+                tx.blob_hashes = vec![hash(2)];
+            })
+            .append_handler_register(|handler| {
+                handler.validation.blob_hash = Arc::new(|_, hash| hash[0] == 2);
+            })
+            .build();
+        assert_eq!(evm.preverify_transaction(), Ok(()));
+
+        evm.tx_mut().blob_hashes = vec![hash(2), hash(1)];
+        assert_eq!(
+            evm.preverify_transaction(),
+            Err(EVMError::Transaction(
+                InvalidTransaction::BlobVersionNotSupported
+            ))
+        );
     }
 }
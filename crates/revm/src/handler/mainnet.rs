@@ -5,10 +5,16 @@ mod post_execution;
 mod pre_execution;
 mod validation;
 
+pub(crate) use execution::returns_halt_gas;
 pub use execution::{
     call, call_return, create, create_return, frame_return_with_refund_flag, insert_call_outcome,
     insert_create_outcome, last_frame_return,
 };
 pub use post_execution::{end, output, reimburse_caller, reward_beneficiary};
-pub use pre_execution::{deduct_caller, deduct_caller_inner, load_accounts, load_precompiles};
-pub use validation::{validate_env, validate_initial_tx_gas, validate_tx_against_state};
+pub use pre_execution::{
+    deduct_caller, deduct_caller_inner, deduct_caller_with_fees, load_accounts, load_precompiles,
+    post_setup, tx_fees,
+};
+pub use validation::{
+    validate_blob_hash, validate_env, validate_initial_tx_gas, validate_tx_against_state,
+};
@@ -1,10 +1,11 @@
 use crate::{
     db::Database,
+    handler::{CreateAddressHandle, ZeroAddressCallHandle},
     interpreter::{
         return_ok, return_revert, CallInputs, CreateInputs, CreateOutcome, Gas, InstructionResult,
         SharedMemory,
     },
-    primitives::{Env, Spec},
+    primitives::{Address, Env, Spec},
     CallFrame, Context, CreateFrame, Frame, FrameOrResult, FrameResult,
 };
 use alloc::boxed::Box;
@@ -35,6 +36,9 @@ pub fn frame_return_with_refund_flag<SPEC: Spec>(
         return_revert!() => {
             gas.erase_cost(remaining);
         }
+        result if returns_halt_gas(env, result) => {
+            gas.erase_cost(remaining);
+        }
         _ => {}
     }
     // Calculate gas refund for transaction.
@@ -47,6 +51,21 @@ pub fn frame_return_with_refund_flag<SPEC: Spec>(
     };
 }
 
+/// Returns true if the halted frame returns its remaining gas.
+///
+/// Interrupted frame always returns it, see [`crate::primitives::HaltReason::Interrupted`]
+/// and [`crate::primitives::CfgEnv::invalid_opcode_consumes_all`].
+#[inline]
+pub(crate) fn returns_halt_gas(env: &Env, result: InstructionResult) -> bool {
+    match result {
+        InstructionResult::Interrupted => true,
+        InstructionResult::InvalidFEOpcode | InstructionResult::OpcodeNotFound => {
+            !env.cfg.invalid_opcode_consumes_all
+        }
+        _ => false,
+    }
+}
+
 /// Handle output of the transaction
 #[inline]
 pub fn last_frame_return<SPEC: Spec, EXT, DB: Database>(
@@ -57,12 +76,18 @@ pub fn last_frame_return<SPEC: Spec, EXT, DB: Database>(
 }
 
 /// Handle frame sub call.
+///
+/// Calls to `address(0)` are handled by `zero_address_call` if it is set.
 #[inline]
 pub fn call<SPEC: Spec, EXT, DB: Database>(
     context: &mut Context<EXT, DB>,
     inputs: Box<CallInputs>,
+    zero_address_call: Option<&ZeroAddressCallHandle<'_, EXT, DB>>,
 ) -> FrameOrResult {
-    context.evm.make_call_frame(&inputs)
+    match zero_address_call {
+        Some(handle) if inputs.contract == Address::ZERO => handle(context, &inputs),
+        _ => context.evm.make_call_frame(&inputs),
+    }
 }
 
 #[inline]
@@ -74,20 +99,26 @@ pub fn call_return<EXT, DB: Database>(
     context
         .evm
         .call_return(&interpreter_result, frame.frame_data.checkpoint);
-    CallOutcome::new(interpreter_result, frame.return_memory_range)
+    let mut outcome = CallOutcome::new(interpreter_result, frame.return_memory_range);
+    outcome.gas_forwarded = frame.frame_data.gas_forwarded;
+    outcome.gas_returned = frame.frame_data.gas_returned;
+    outcome
 }
 
 #[inline]
 pub fn insert_call_outcome<EXT, DB: Database>(
-    _context: &mut Context<EXT, DB>,
+    context: &mut Context<EXT, DB>,
     frame: &mut Frame,
     shared_memory: &mut SharedMemory,
     outcome: CallOutcome,
 ) {
-    frame
-        .frame_data_mut()
-        .interpreter
-        .insert_call_outcome(shared_memory, outcome)
+    let returned_gas = returns_halt_gas(&context.evm.env, *outcome.instruction_result())
+        .then(|| outcome.gas().remaining());
+    let interpreter = &mut frame.frame_data_mut().interpreter;
+    interpreter.insert_call_outcome(shared_memory, outcome);
+    if let Some(remaining) = returned_gas {
+        interpreter.gas.erase_cost(remaining);
+    }
 }
 
 /// Handle frame sub create.
@@ -95,8 +126,11 @@ pub fn insert_call_outcome<EXT, DB: Database>(
 pub fn create<SPEC: Spec, EXT, DB: Database>(
     context: &mut Context<EXT, DB>,
     inputs: Box<CreateInputs>,
+    create_address: Option<&CreateAddressHandle<'_>>,
 ) -> FrameOrResult {
-    context.evm.make_create_frame(SPEC::SPEC_ID, &inputs)
+    context
+        .evm
+        .make_create_frame(SPEC::SPEC_ID, &inputs, create_address)
 }
 
 #[inline]
@@ -110,27 +144,42 @@ pub fn create_return<SPEC: Spec, EXT, DB: Database>(
         frame.created_address,
         frame.frame_data.checkpoint,
     );
-    CreateOutcome::new(interpreter_result, Some(frame.created_address))
+    // output of the successful create is the code that was set to the created account.
+    let deployed_code = interpreter_result
+        .is_ok()
+        .then(|| interpreter_result.output.clone());
+    let mut outcome = CreateOutcome::new(interpreter_result, Some(frame.created_address));
+    outcome.deployed_code = deployed_code;
+    outcome
 }
 
 #[inline]
 pub fn insert_create_outcome<EXT, DB: Database>(
-    _context: &mut Context<EXT, DB>,
+    context: &mut Context<EXT, DB>,
     frame: &mut Frame,
     outcome: CreateOutcome,
 ) {
-    frame
-        .frame_data_mut()
-        .interpreter
-        .insert_create_outcome(outcome)
+    let returned_gas = returns_halt_gas(&context.evm.env, *outcome.instruction_result())
+        .then(|| outcome.gas().remaining());
+    let interpreter = &mut frame.frame_data_mut().interpreter;
+    interpreter.insert_create_outcome(outcome);
+    if let Some(remaining) = returned_gas {
+        interpreter.gas.erase_cost(remaining);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use revm_interpreter::{primitives::CancunSpec, InterpreterResult};
-    use revm_precompile::Bytes;
 
     use super::*;
+    use crate::test_utils::{contract_info, db_with_contract, evm_calling};
+    use crate::{
+        db::{BenchmarkDB, InMemoryDB},
+        interpreter::{opcode, CallOutcome, InstructionResult},
+        primitives::{address, Address, Bytecode, Bytes, ExecutionResult, HaltReason, B256},
+        EvmContext,
+    };
 
     /// Creates frame result.
     fn call_last_frame_return(instruction_result: InstructionResult, gas: Gas) -> Gas {
@@ -181,4 +230,214 @@ mod tests {
         assert_eq!(gas.spend(), 10);
         assert_eq!(gas.refunded(), 0);
     }
+
+    #[test]
+    fn test_invalid_opcode_consumes_all() {
+        let mut evm = evm_calling(
+            BenchmarkDB::new_bytecode(Bytecode::new_raw(
+                vec![opcode::PUSH1, 0x00, opcode::INVALID].into(),
+            )),
+            Address::ZERO,
+        )
+        .build();
+
+        assert_eq!(
+            evm.transact().unwrap().result,
+            ExecutionResult::Halt {
+                reason: HaltReason::InvalidFEOpcode,
+                gas_used: 100_000,
+            }
+        );
+
+        evm.cfg_mut().invalid_opcode_consumes_all = false;
+        evm.cfg_mut().invalid_opcode_cost = 10;
+        assert_eq!(
+            evm.transact().unwrap().result,
+            ExecutionResult::Halt {
+                reason: HaltReason::InvalidFEOpcode,
+                gas_used: 21_000 + 3 + 10,
+            }
+        );
+    }
+
+    #[test]
+    fn test_custom_create_address() {
+        use crate::primitives::CreateScheme;
+        use alloc::sync::Arc;
+
+        let created = address!("00000000000000000000000000000000000000cc");
+        // creates two contracts with empty init code and returns their addresses.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::CREATE,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::CREATE,
+                opcode::PUSH1,
+                0x20,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x40,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .modify_tx_env(|tx| tx.gas_limit = 200_000)
+            .append_handler_register_box(Box::new(move |handler| {
+                handler.execution.create_address = Some(Arc::new(
+                    move |_caller: Address,
+                          _nonce: u64,
+                          _init_code: &Bytes,
+                          _scheme: CreateScheme| { created },
+                ));
+            }))
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        let output = result.output().unwrap();
+        assert_eq!(&output[12..32], created.as_slice());
+        // second create collides with the first created contract.
+        assert_eq!(&output[32..], B256::ZERO.as_slice());
+    }
+
+    #[test]
+    fn test_custom_call_access_gas() {
+        use alloc::sync::Arc;
+
+        let entry = address!("2000000000000000000000000000000000000000");
+        // 0xa1 is an EOA and 0xa2 is a contract.
+        let contract = address!("00000000000000000000000000000000000000a2");
+
+        let call_code = |target: u8| {
+            let code = Bytecode::new_raw(
+                vec![
+                    opcode::PUSH1,
+                    0x00,
+                    opcode::PUSH1,
+                    0x00,
+                    opcode::PUSH1,
+                    0x00,
+                    opcode::PUSH1,
+                    0x00,
+                    opcode::PUSH1,
+                    0x00,
+                    opcode::PUSH1,
+                    target,
+                    opcode::GAS,
+                    opcode::CALL,
+                    opcode::STOP,
+                ]
+                .into(),
+            );
+            contract_info(code)
+        };
+        let stop = Bytecode::new_raw(vec![opcode::STOP].into());
+        let db = db_with_contract(contract, stop);
+
+        // returns gas used by calling the target.
+        let gas_used = |target: u8, custom: bool| {
+            let mut db = db.clone();
+            db.insert_account_info(entry, call_code(target));
+            let builder = evm_calling(db, entry);
+            let mut evm = if custom {
+                builder
+                    .append_handler_register(|handler| {
+                        // EOAs are cheaper to access than contracts.
+                        handler.execution.call_access_gas = Some(Arc::new(
+                            |_address: Address,
+                             is_empty_code: bool,
+                             is_cold: bool,
+                             _context: &EvmContext<InMemoryDB>| {
+                                match (is_cold, is_empty_code) {
+                                    (false, _) => 100,
+                                    (true, true) => 1_000,
+                                    (true, false) => 2_600,
+                                }
+                            },
+                        ));
+                    })
+                    .build()
+            } else {
+                builder.build()
+            };
+            let result = evm.transact().unwrap().result;
+            assert!(result.is_success());
+            result.gas_used()
+        };
+
+        // mainnet charges the same for both.
+        assert_eq!(gas_used(0xa1, false), gas_used(0xa2, false));
+        // custom cost is 1_600 less for EOA.
+        assert_eq!(gas_used(0xa2, true) - gas_used(0xa1, true), 1_600);
+        assert_eq!(gas_used(0xa2, true), gas_used(0xa2, false));
+    }
+
+    #[test]
+    fn test_zero_address_call() {
+        use alloc::sync::Arc;
+
+        // default is a plain call to an empty account.
+        let mut evm = evm_calling(InMemoryDB::default(), Address::ZERO).build();
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        assert_eq!(result.gas_used(), 21_000);
+        assert!(result.output().unwrap().is_empty());
+
+        // custom behavior returns an output and charges 100 gas.
+        let mut evm = evm_calling(InMemoryDB::default(), Address::ZERO)
+            .append_handler_register(|handler| {
+                handler.execution.zero_address_call = Some(Arc::new(
+                    |_context: &mut Context<(), InMemoryDB>, inputs: &CallInputs| {
+                        let mut gas = Gas::new(inputs.gas_limit);
+                        gas.record_cost(100);
+                        FrameOrResult::new_call_result(
+                            InterpreterResult {
+                                result: InstructionResult::Return,
+                                output: Bytes::from_static(&[1, 2, 3]),
+                                gas,
+                            },
+                            inputs.return_memory_offset.clone(),
+                        )
+                    },
+                ));
+            })
+            .build();
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        assert_eq!(result.gas_used(), 21_100);
+        assert_eq!(result.output().unwrap().as_ref(), &[1, 2, 3]);
+
+        // calls to other addresses are unchanged.
+        let other = address!("00000000000000000000000000000000000000a1");
+        let mut evm = evm_calling(InMemoryDB::default(), other)
+            .append_handler_register(|handler| {
+                handler.execution.zero_address_call = Some(Arc::new(
+                    |_context: &mut Context<(), InMemoryDB>, _inputs: &CallInputs| {
+                        unreachable!("only address(0) calls are handled")
+                    },
+                ));
+            })
+            .build();
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        assert_eq!(result.gas_used(), 21_000);
+    }
 }
@@ -1,8 +1,6 @@
 use crate::{
     interpreter::{Gas, SuccessOrHalt},
-    primitives::{
-        db::Database, EVMError, ExecutionResult, Output, ResultAndState, Spec, SpecId::LONDON, U256,
-    },
+    primitives::{db::Database, EVMError, ExecutionResult, Output, ResultAndState, Spec, U256},
     Context, FrameResult,
 };
 
@@ -22,15 +20,9 @@ pub fn reward_beneficiary<SPEC: Spec, EXT, DB: Database>(
     gas: &Gas,
 ) -> Result<(), EVMError<DB::Error>> {
     let beneficiary = context.evm.env.block.coinbase;
-    let effective_gas_price = context.evm.env.effective_gas_price();
-
     // transfer fee to coinbase/beneficiary.
-    // EIP-1559 discard basefee for coinbase transfer. Basefee amount of gas is discarded.
-    let coinbase_gas_price = if SPEC::enabled(LONDON) {
-        effective_gas_price.saturating_sub(context.evm.env.block.basefee)
-    } else {
-        effective_gas_price
-    };
+    // EIP-1559 basefee is already discarded from the beneficiary gas price.
+    let coinbase_gas_price = context.evm.tx_fees.beneficiary_gas_price;
 
     let (coinbase_account, _) = context
         .evm
@@ -53,7 +45,7 @@ pub fn reimburse_caller<SPEC: Spec, EXT, DB: Database>(
     gas: &Gas,
 ) -> Result<(), EVMError<DB::Error>> {
     let caller = context.evm.env.tx.caller;
-    let effective_gas_price = context.evm.env.effective_gas_price();
+    let effective_gas_price = context.evm.tx_fees.gas_price;
 
     // return balance of not spend gas.
     let (caller_account, _) = context
@@ -82,8 +74,21 @@ pub fn output<EXT, DB: Database>(
     let output = result.output();
     let instruction_result = result.into_interpreter_result();
 
+    // created accounts are read before journal is cleared.
+    let created_contracts = context.evm.journaled_state.created_accounts().collect();
+
     // reset journal and return present state.
-    let (state, logs) = context.evm.journaled_state.finalize();
+    let (mut state, logs) = context.evm.journaled_state.finalize();
+
+    // mark slots written to zero as deletions.
+    if context.evm.env.cfg.keep_zero_storage {
+        for slot in state
+            .values_mut()
+            .flat_map(|account| account.storage.values_mut())
+        {
+            slot.deleted = slot.is_deleted();
+        }
+    }
 
     let result = match instruction_result.result.into() {
         SuccessOrHalt::Success(reason) => ExecutionResult::Success {
@@ -113,5 +118,197 @@ pub fn output<EXT, DB: Database>(
         }
     };
 
-    Ok(ResultAndState { result, state })
+    Ok(ResultAndState {
+        result,
+        state,
+        created_contracts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{contract_info, db_with_contract, evm_calling};
+    use crate::{
+        db::{BenchmarkDB, InMemoryDB},
+        interpreter::opcode,
+        primitives::{address, AccountInfo, Address, Bytecode, TransactTo, U256},
+        Evm,
+    };
+
+    #[test]
+    fn test_created_contracts() {
+        let create = |len: u8, offset: u8| {
+            [
+                opcode::PUSH1,
+                len,
+                opcode::PUSH1,
+                offset,
+                opcode::PUSH1,
+                0x00,
+                opcode::CREATE,
+                opcode::POP,
+            ]
+        };
+        // deploys two empty children and one whose init code reverts.
+        let mut code = Vec::new();
+        code.extend(create(0, 0));
+        code.extend(create(0, 0));
+        // PUSH1 0 PUSH1 0 REVERT
+        code.extend([
+            opcode::PUSH5,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::REVERT,
+            opcode::PUSH1,
+            0x00,
+            opcode::MSTORE,
+        ]);
+        code.extend(create(5, 27));
+        code.push(opcode::STOP);
+
+        let mut evm = evm_calling(
+            BenchmarkDB::new_bytecode(Bytecode::new_raw(code.into())),
+            Address::ZERO,
+        )
+        .modify_tx_env(|tx| tx.gas_limit = 1_000_000)
+        .build();
+
+        let result_and_state = evm.transact().unwrap();
+        assert!(result_and_state.result.is_success());
+        // factory starts with nonce 1.
+        assert_eq!(
+            result_and_state.created_contracts(),
+            &[Address::ZERO.create(1), Address::ZERO.create(2)]
+        );
+    }
+
+    #[test]
+    fn test_reward_beneficiary_split() {
+        use crate::{interpreter::Gas, primitives::LatestSpec};
+        use alloc::sync::Arc;
+
+        let caller = address!("1000000000000000000000000000000000000000");
+        let coinbase = address!("00000000000000000000000000000000000000c0");
+        let treasury = address!("00000000000000000000000000000000000000c1");
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(caller, AccountInfo::from_balance(U256::from(1_000_000)));
+
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_block_env(|block| {
+                block.coinbase = coinbase;
+                block.basefee = U256::from(6);
+            })
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = caller;
+                tx.transact_to =
+                    TransactTo::Call(address!("00000000000000000000000000000000000000a1"));
+                tx.gas_limit = 21_000;
+                tx.gas_price = U256::from(10);
+                tx.gas_priority_fee = Some(U256::from(4));
+            })
+            .append_handler_register_box(Box::new(move |handler| {
+                handler.post_execution.reward_beneficiary =
+                    Arc::new(move |context: &mut Context<(), InMemoryDB>, gas: &Gas| {
+                        reward_beneficiary::<LatestSpec, _, _>(context, gas)?;
+                        // move half of the priority fee from the coinbase to the treasury.
+                        let env = &context.evm.env;
+                        let tip = (env.effective_gas_price() - env.block.basefee)
+                            * U256::from(gas.spend() - gas.refunded() as u64);
+                        let half = tip / U256::from(2);
+                        let journal = &mut context.evm.journaled_state;
+                        let (account, _) = journal
+                            .load_account(coinbase, &mut context.evm.db)
+                            .map_err(EVMError::Database)?;
+                        account.info.balance -= half;
+                        let (account, _) = journal
+                            .load_account(treasury, &mut context.evm.db)
+                            .map_err(EVMError::Database)?;
+                        account.mark_touch();
+                        account.info.balance += half;
+                        Ok(())
+                    });
+            }))
+            .build();
+
+        let state = evm.transact().unwrap().state;
+        // priority fee of 4 per gas is split, base fee of 6 per gas is burned.
+        assert_eq!(state[&coinbase].info.balance, U256::from(42_000));
+        assert_eq!(state[&treasury].info.balance, U256::from(42_000));
+        assert_eq!(
+            state[&caller].info.balance,
+            U256::from(1_000_000 - 21_000 * 10)
+        );
+    }
+
+    #[test]
+    fn test_keep_zero_storage() {
+        let contract = address!("2000000000000000000000000000000000000000");
+        // sets slot 1 to zero.
+        let code = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x01,
+                opcode::SSTORE,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(contract, contract_info(code));
+        db.insert_account_storage(contract, U256::from(1), U256::from(5))
+            .unwrap();
+
+        let mut evm = evm_calling(db, contract).build();
+
+        // without the flag the cleared slot is kept without the marker.
+        let state = evm.transact().unwrap().state;
+        let slot = &state[&contract].storage[&U256::from(1)];
+        assert!(slot.is_deleted());
+        assert!(!slot.deleted);
+
+        evm.cfg_mut().keep_zero_storage = true;
+        let state = evm.transact().unwrap().state;
+        let slot = &state[&contract].storage[&U256::from(1)];
+        assert!(slot.deleted);
+        assert_eq!(slot.original_value(), U256::from(5));
+    }
+
+    #[test]
+    fn test_zero_storage_commit() {
+        let contract = address!("2000000000000000000000000000000000000000");
+        // stores the first calldata word to slot 1.
+        let code = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x00,
+                opcode::CALLDATALOAD,
+                opcode::PUSH1,
+                0x01,
+                opcode::SSTORE,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+
+        for keep_zero_storage in [false, true] {
+            let mut evm = evm_calling(db_with_contract(contract, code.clone()), contract)
+                .modify_cfg_env(|cfg| cfg.keep_zero_storage = keep_zero_storage)
+                .build();
+
+            for value in [U256::from(5), U256::ZERO] {
+                evm.tx_mut().data = value.to_be_bytes_vec().into();
+                assert!(evm.transact_commit().unwrap().is_success());
+                let stored = evm.context.evm.db.storage(contract, U256::from(1)).unwrap();
+                assert_eq!(stored, value);
+            }
+        }
+    }
 }
@@ -3,11 +3,12 @@
 //! They handle initial setup of the EVM, call loop and the final return of the EVM
 
 use crate::{
+    handler::TxFees,
     precompile::{PrecompileSpecId, Precompiles},
     primitives::{
         db::Database,
         Account, EVMError, Env, Spec,
-        SpecId::{CANCUN, SHANGHAI},
+        SpecId::{CANCUN, LONDON, SHANGHAI},
         TransactTo, U256,
     },
     Context,
@@ -30,29 +31,76 @@ pub fn load_accounts<SPEC: Spec, EXT, DB: Database>(
     // load coinbase
     // EIP-3651: Warm COINBASE. Starts the `COINBASE` address warm
     if SPEC::enabled(SHANGHAI) {
+        let coinbase = context.evm.env.block.coinbase;
         context
             .evm
             .journaled_state
-            .initial_account_load(context.evm.env.block.coinbase, &[], &mut context.evm.db)
+            .initial_account_load(coinbase, &[], &mut context.evm.db)
             .map_err(EVMError::Database)?;
+        // coinbase is warm even if it was preloaded as cold.
+        context.evm.journaled_state.cold_accounts.remove(&coinbase);
     }
 
     context.evm.load_access_list()?;
+    context.evm.load_preload_accounts()?;
+    context.evm.apply_state_overrides()?;
     Ok(())
 }
 
-/// Helper function that deducts the caller balance.
+/// Main post setup handle, does nothing.
 #[inline]
-pub fn deduct_caller_inner<SPEC: Spec>(caller_account: &mut Account, env: &Env) {
-    // Subtract gas costs from the caller's account.
-    // We need to saturate the gas cost to prevent underflow in case that `disable_balance_check` is enabled.
-    let mut gas_cost = U256::from(env.tx.gas_limit).saturating_mul(env.effective_gas_price());
+pub fn post_setup<EXT, DB: Database>(
+    _context: &mut Context<EXT, DB>,
+) -> Result<(), EVMError<DB::Error>> {
+    Ok(())
+}
+
+/// Main fees of the transaction.
+#[inline]
+pub fn tx_fees<SPEC: Spec>(env: &Env) -> TxFees {
+    let gas_price = env.effective_gas_price();
+
+    // EIP-1559 discard basefee for coinbase transfer.
+    let beneficiary_gas_price = if SPEC::enabled(LONDON) {
+        gas_price.saturating_sub(env.block.basefee)
+    } else {
+        gas_price
+    };
 
     // EIP-4844
-    if SPEC::enabled(CANCUN) {
-        let data_fee = env.calc_data_fee().expect("already checked");
-        gas_cost = gas_cost.saturating_add(data_fee);
+    let (data_fee, max_data_fee) = if SPEC::enabled(CANCUN) {
+        (
+            env.calc_data_fee().expect("already checked"),
+            env.calc_max_data_fee().unwrap_or_default(),
+        )
+    } else {
+        (U256::ZERO, U256::ZERO)
+    };
+
+    TxFees {
+        gas_price,
+        beneficiary_gas_price,
+        data_fee,
+        // EIP-1559 caller has to afford the max fee.
+        max_gas_price: env.tx.gas_price,
+        max_data_fee,
     }
+}
+
+/// Helper function that deducts the caller balance with mainnet fees.
+#[inline]
+pub fn deduct_caller_inner<SPEC: Spec>(caller_account: &mut Account, env: &Env) {
+    deduct_caller_with_fees(caller_account, env, &tx_fees::<SPEC>(env));
+}
+
+/// Helper function that deducts the caller balance with the given fees.
+#[inline]
+pub fn deduct_caller_with_fees(caller_account: &mut Account, env: &Env, fees: &TxFees) {
+    // Subtract gas costs from the caller's account.
+    // We need to saturate the gas cost to prevent underflow in case that `disable_balance_check` is enabled.
+    let gas_cost = U256::from(env.tx.gas_limit)
+        .saturating_mul(fees.gas_price)
+        .saturating_add(fees.data_fee);
 
     // set new caller account balance.
     caller_account.info.balance = caller_account.info.balance.saturating_sub(gas_cost);
@@ -80,7 +128,139 @@ pub fn deduct_caller<SPEC: Spec, EXT, DB: Database>(
         .map_err(EVMError::Database)?;
 
     // deduct gas cost from caller's account.
-    deduct_caller_inner::<SPEC>(caller_account, &context.evm.env);
+    deduct_caller_with_fees(caller_account, &context.evm.env, &context.evm.tx_fees);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::evm_calling;
+    use crate::{
+        db::{BenchmarkDB, InMemoryDB},
+        interpreter::opcode,
+        primitives::{
+            address, AccountInfo, Address, Bytecode, InvalidTransaction, TransactTo, U256,
+        },
+        Evm,
+    };
+
+    #[test]
+    fn test_post_setup() {
+        use alloc::sync::Arc;
+        use core::convert::Infallible;
+
+        // returns storage slot zero.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x00,
+                opcode::SLOAD,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .append_handler_register(|handler| {
+                handler.pre_execution.post_setup = Arc::new(
+                    |context: &mut Context<(), BenchmarkDB>| -> Result<(), EVMError<Infallible>> {
+                        let evm = &mut context.evm;
+                        evm.journaled_state
+                            .load_account(Address::ZERO, &mut evm.db)
+                            .map_err(EVMError::Database)?;
+                        evm.journaled_state
+                            .sstore(Address::ZERO, U256::ZERO, U256::from(42), &mut evm.db)
+                            .map_err(EVMError::Database)?;
+                        Ok(())
+                    },
+                );
+            })
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        assert_eq!(
+            result.output().unwrap().as_ref(),
+            U256::from(42).to_be_bytes::<32>()
+        );
+    }
+
+    #[test]
+    fn test_custom_tx_fees() {
+        use crate::primitives::LatestSpec;
+        use alloc::sync::Arc;
+
+        let caller = address!("1000000000000000000000000000000000000000");
+        let coinbase = address!("00000000000000000000000000000000000000c0");
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(caller, AccountInfo::from_balance(U256::from(1_000_000)));
+
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_block_env(|block| {
+                block.coinbase = coinbase;
+                block.basefee = U256::from(6);
+            })
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = caller;
+                tx.transact_to =
+                    TransactTo::Call(address!("00000000000000000000000000000000000000a1"));
+                tx.gas_limit = 30_000;
+                tx.gas_price = U256::from(10);
+            })
+            .append_handler_register_box(Box::new(|handler| {
+                handler.pre_execution.tx_fees = Arc::new(|env: &Env| {
+                    let fees = tx_fees::<LatestSpec>(env);
+                    // nothing is burned and a flat surcharge is paid by the caller.
+                    TxFees {
+                        beneficiary_gas_price: fees.gas_price,
+                        data_fee: fees.data_fee + U256::from(1_000),
+                        ..fees
+                    }
+                });
+            }))
+            .build();
+
+        let state = evm.transact().unwrap().state;
+        assert_eq!(
+            *evm.context.evm.tx_fees(),
+            TxFees {
+                gas_price: U256::from(10),
+                beneficiary_gas_price: U256::from(10),
+                data_fee: U256::from(1_000),
+                max_gas_price: U256::from(10),
+                max_data_fee: U256::ZERO,
+            }
+        );
+        // unused gas is reimbursed, the surcharge is not.
+        assert_eq!(
+            state[&caller].info.balance,
+            U256::from(1_000_000 - 21_000 * 10 - 1_000)
+        );
+        assert_eq!(state[&coinbase].info.balance, U256::from(21_000 * 10));
+
+        // caller has to afford the surcharge.
+        evm.context
+            .evm
+            .db
+            .insert_account_info(caller, AccountInfo::from_balance(U256::from(300_500)));
+        assert_eq!(
+            evm.transact().unwrap_err(),
+            EVMError::Transaction(InvalidTransaction::LackOfFundForMaxFee {
+                fee: Box::new(U256::from(30_000 * 10 + 1_000)),
+                balance: Box::new(U256::from(300_500)),
+            })
+        );
+    }
+}
@@ -1,21 +1,34 @@
 use revm_interpreter::gas;
 
 use crate::{
-    primitives::{db::Database, EVMError, Env, InvalidTransaction, Spec},
+    handler::{CallerCheck, CallerCheckHandle},
+    primitives::{db::Database, EVMError, Env, InvalidTransaction, Spec, B256},
     Context,
 };
 
 /// Validate environment for the mainnet.
+///
+/// Blob versioned hashes are not checked here, they are validated by the blob hash handle.
 pub fn validate_env<SPEC: Spec, DB: Database>(env: &Env) -> Result<(), EVMError<DB::Error>> {
     // Important: validate block before tx.
     env.validate_block_env::<SPEC>()?;
-    env.validate_tx::<SPEC>()?;
+    env.validate_tx_with_blob_hash_check::<SPEC>(|_| true)?;
     Ok(())
 }
 
+/// Validates that blob versioned hash starts with KZG version byte.
+pub fn validate_blob_hash<EXT, DB: Database>(_context: &mut Context<EXT, DB>, hash: B256) -> bool {
+    Env::is_valid_blob_versioned_hash(&hash)
+}
+
 /// Validates transaction against the state.
+///
+/// Caller balance is checked against [`EvmContext::tx_fees`](crate::EvmContext::tx_fees),
+/// they have to be computed before. `caller_check` is called before the nonce and the
+/// balance of the caller are checked.
 pub fn validate_tx_against_state<SPEC: Spec, EXT, DB: Database>(
     context: &mut Context<EXT, DB>,
+    caller_check: &CallerCheckHandle<'_, EXT, DB>,
 ) -> Result<(), EVMError<DB::Error>> {
     // load acc
     let tx_caller = context.evm.env.tx.caller;
@@ -24,11 +37,46 @@ pub fn validate_tx_against_state<SPEC: Spec, EXT, DB: Database>(
         .journaled_state
         .load_account(tx_caller, &mut context.evm.db)
         .map_err(EVMError::Database)?;
+    let caller_info = caller_account.info.clone();
+
+    if let Some(tx_nonce) = context.evm.env.tx.nonce {
+        caller_check(
+            context,
+            CallerCheck::Nonce {
+                tx_nonce,
+                account_nonce: caller_info.nonce,
+            },
+        );
+    }
+    context
+        .evm
+        .env
+        .validate_caller_nonce(&caller_info)
+        .map_err(EVMError::Transaction)?;
 
+    let env = &context.evm.env;
+    let required = context
+        .evm
+        .tx_fees
+        .max_tx_cost(env.tx.gas_limit, env.tx.value)
+        .ok_or(InvalidTransaction::OverflowPaymentInTransaction)?;
+    caller_check(
+        context,
+        CallerCheck::Balance {
+            required,
+            available: caller_info.balance,
+        },
+    );
+    let caller_account = context
+        .evm
+        .journaled_state
+        .state
+        .get_mut(&tx_caller)
+        .expect("caller is loaded");
     context
         .evm
         .env
-        .validate_tx_against_state::<SPEC>(caller_account)
+        .validate_caller_balance(caller_account, required)
         .map_err(EVMError::Transaction)?;
 
     Ok(())
@@ -50,3 +98,62 @@ pub fn validate_initial_tx_gas<SPEC: Spec, DB: Database>(
     }
     Ok(initial_gas_spend)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::evm_calling;
+    use crate::{
+        db::BenchmarkDB,
+        primitives::{Address, Bytecode, InvalidTransaction},
+    };
+    use alloc::boxed::Box;
+
+    #[test]
+    fn test_validation_order() {
+        use crate::primitives::LatestSpec;
+        use alloc::sync::Arc;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        // every handle checks that it is called at its position.
+        fn record(calls: &AtomicUsize, position: usize) {
+            assert_eq!(calls.fetch_add(1, Ordering::Relaxed), position);
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let register_calls = calls.clone();
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(Bytecode::new()), Address::ZERO)
+            .append_handler_register_box(Box::new(move |handler| {
+                let validation = &mut handler.validation;
+
+                let calls = register_calls.clone();
+                validation.env = Arc::new(move |env: &Env| {
+                    record(&calls, 0);
+                    validate_env::<LatestSpec, BenchmarkDB>(env)
+                });
+                let calls = register_calls.clone();
+                validation.initial_tx_gas = Arc::new(move |env: &Env| {
+                    record(&calls, 1);
+                    validate_initial_tx_gas::<LatestSpec, BenchmarkDB>(env)
+                });
+                let calls = register_calls.clone();
+                validation.tx_against_state = Arc::new(move |context, caller_check| {
+                    record(&calls, 2);
+                    validate_tx_against_state::<LatestSpec, _, _>(context, caller_check)
+                });
+            }))
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+        // validation stops at the first error.
+        calls.store(0, Ordering::Relaxed);
+        evm.tx_mut().gas_limit = 1_000;
+        assert_eq!(
+            evm.transact().unwrap_err(),
+            EVMError::Transaction(InvalidTransaction::CallGasCostMoreThanGasLimit)
+        );
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+}
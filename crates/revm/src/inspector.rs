@@ -1,17 +1,28 @@
 use crate::{
-    interpreter::{CallInputs, CreateInputs, Interpreter},
-    primitives::{db::Database, Address, Log, U256},
+    interpreter::{CallInputs, CreateInputs, InstructionResult, Interpreter},
+    primitives::{db::Database, Address, Log, B256, U256},
     EvmContext,
 };
 use auto_impl::auto_impl;
 
+#[cfg(feature = "serde")]
+mod call_tracer;
+mod call_tree;
 #[cfg(feature = "std")]
 mod customprinter;
 #[cfg(all(feature = "std", feature = "serde"))]
 mod eip3155;
+mod flamegraph;
 mod gas;
+mod gas_split;
 mod handler_register;
+mod keccak;
 mod noop;
+mod opcode_count;
+mod refund_timeline;
+mod storage_access;
+mod struct_log;
+mod write_detection;
 
 // Exports.
 
@@ -20,12 +31,37 @@ use revm_interpreter::{CallOutcome, CreateOutcome};
 
 /// [Inspector] implementations.
 pub mod inspectors {
+    #[cfg(feature = "serde")]
+    pub use super::call_tracer::{CallTracerFrame, CallTracerInspector};
+    pub use super::call_tree::{CallTraceNode, CallTreeInspector};
     #[cfg(feature = "std")]
     pub use super::customprinter::CustomPrintTracer;
     #[cfg(all(feature = "std", feature = "serde"))]
     pub use super::eip3155::TracerEip3155;
+    pub use super::flamegraph::{FlamegraphFrame, FlamegraphInspector};
     pub use super::gas::GasInspector;
+    pub use super::gas_split::GasSplitInspector;
+    pub use super::keccak::{KeccakProfileInspector, KeccakStats};
     pub use super::noop::NoOpInspector;
+    pub use super::opcode_count::OpcodeCountInspector;
+    pub use super::refund_timeline::{RefundEvent, RefundTimelineInspector};
+    pub use super::storage_access::{AccountStorageAccess, StorageAccessInspector};
+    pub use super::struct_log::{StructLog, StructLogInspector};
+    pub use super::write_detection::WriteDetectionInspector;
+}
+
+/// Action returned by [Inspector::step] that decides how the current instruction is handled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum StepAction {
+    /// Execute the instruction.
+    #[default]
+    Continue,
+    /// Skip the instruction together with its immediate bytes.
+    ///
+    /// The instruction is not executed, no gas is charged and `step_end` is not called.
+    Skip,
+    /// Halt the interpreter with the given result without executing the instruction.
+    Halt(InstructionResult),
 }
 
 /// EVM [Interpreter] callbacks.
@@ -46,13 +82,17 @@ pub trait Inspector<DB: Database> {
     /// Information about the current execution, including the memory, stack and more is available
     /// on `interp` (see [Interpreter]).
     ///
+    /// Returned [StepAction] decides whether the instruction is executed, skipped or
+    /// the execution is halted.
+    ///
     /// # Example
     ///
     /// To get the current opcode, use `interp.current_opcode()`.
     #[inline]
-    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) -> StepAction {
         let _ = interp;
         let _ = context;
+        StepAction::Continue
     }
 
     /// Called after `step` when the instruction has been executed.
@@ -72,9 +112,128 @@ pub trait Inspector<DB: Database> {
         let _ = log;
     }
 
+    /// Called for every log emitted by a LOG opcode, before [Inspector::log], as it is executed.
+    ///
+    /// `depth` is the depth of the frame that emitted the log, transaction frame has depth 0.
+    ///
+    /// The log is not final, it is discarded if the frame or any of its parent frames
+    /// reverts or halts. Logs are reported depth-first in the order of execution.
+    #[inline]
+    fn log_pre_revert(&mut self, context: &mut EvmContext<DB>, log: &Log, depth: u64) {
+        let _ = context;
+        let _ = log;
+        let _ = depth;
+    }
+
+    /// Called after TSTORE writes `value` to the transient storage slot `key` of `address`.
+    #[inline]
+    fn tstore(&mut self, context: &mut EvmContext<DB>, address: Address, key: U256, value: U256) {
+        let _ = context;
+        let _ = address;
+        let _ = key;
+        let _ = value;
+    }
+
+    /// Called after TLOAD reads `value` from the transient storage slot `key` of `address`.
+    #[inline]
+    fn tload(&mut self, context: &mut EvmContext<DB>, address: Address, key: U256, value: U256) {
+        let _ = context;
+        let _ = address;
+        let _ = key;
+        let _ = value;
+    }
+
+    /// Called after a LOG opcode emits a log with `topics` topics and `data_len` bytes of data.
+    ///
+    /// `charged` is the gas charged for the log without the memory expansion cost,
+    /// it equals `LOG + topics * LOGTOPIC + data_len * LOGDATA`.
+    #[inline]
+    fn log_gas(
+        &mut self,
+        interp: &mut Interpreter,
+        context: &mut EvmContext<DB>,
+        topics: usize,
+        data_len: usize,
+        charged: u64,
+    ) {
+        let _ = interp;
+        let _ = context;
+        let _ = topics;
+        let _ = data_len;
+        let _ = charged;
+    }
+
+    /// Called after EXP is executed with an exponent of `exponent_byte_len` bytes.
+    ///
+    /// `dynamic_cost` is the gas charged on top of the static EXP cost, it equals
+    /// `exponent_byte_len * 50` (10 before Spurious Dragon).
+    #[inline]
+    fn exp_gas(
+        &mut self,
+        interp: &mut Interpreter,
+        context: &mut EvmContext<DB>,
+        exponent_byte_len: usize,
+        dynamic_cost: u64,
+    ) {
+        let _ = interp;
+        let _ = context;
+        let _ = exponent_byte_len;
+        let _ = dynamic_cost;
+    }
+
+    /// Maximum gas a single opcode can charge before [`Inspector::gas_bomb`] is called.
+    ///
+    /// Gas forwarded to sub calls and creates is not counted. By default it is `None`
+    /// and opcodes are not checked.
+    #[inline]
+    fn max_single_opcode_gas(&self) -> Option<u64> {
+        None
+    }
+
+    /// Called after an opcode charged `cost` gas, more than
+    /// [`Inspector::max_single_opcode_gas`].
+    ///
+    /// `cost` includes the memory expansion cost and excludes gas forwarded to a sub call
+    /// or create. Returns whether the frame halts with
+    /// [`InstructionResult::GasBomb`](crate::interpreter::InstructionResult::GasBomb),
+    /// by default it does not.
+    #[inline]
+    fn gas_bomb(
+        &mut self,
+        interp: &mut Interpreter,
+        context: &mut EvmContext<DB>,
+        opcode: u8,
+        cost: u64,
+    ) -> bool {
+        let _ = interp;
+        let _ = context;
+        let _ = opcode;
+        let _ = cost;
+        false
+    }
+
+    /// Called after CALLDATALOAD or CALLDATACOPY read past the end of the calldata
+    /// starting at `offset`.
+    ///
+    /// Bytes past the end are read as zero, reading them is often a sign of a bug.
+    #[inline]
+    fn calldata_oob_read(
+        &mut self,
+        interp: &mut Interpreter,
+        context: &mut EvmContext<DB>,
+        offset: usize,
+    ) {
+        let _ = interp;
+        let _ = context;
+        let _ = offset;
+    }
+
     /// Called whenever a call to a contract is about to start.
     ///
     /// InstructionResulting anything other than [crate::interpreter::InstructionResult::Continue] overrides the result of the call.
+    ///
+    /// `inputs.gas_limit` is the gas available to the call, after the EIP-150 63/64 cap and
+    /// including the call stipend, so it can be used to set gas of a mocked [CallOutcome].
     #[inline]
     fn call(
         &mut self,
@@ -135,6 +294,123 @@ pub trait Inspector<DB: Database> {
         outcome
     }
 
+    /// Called before a create frame is made with the gas `forwarded` to it.
+    ///
+    /// For CREATE and CREATE2 opcodes this is the gas left after the 63/64 rule is applied.
+    #[inline]
+    fn create_gas(&mut self, context: &mut EvmContext<DB>, inputs: &CreateInputs, forwarded: u64) {
+        let _ = context;
+        let _ = inputs;
+        let _ = forwarded;
+    }
+
+    /// Called after TIMESTAMP, NUMBER, GASLIMIT, CHAINID, BASEFEE, COINBASE, BLOCKHASH
+    /// or GASPRICE `opcode` pushed the `default_value` read from the environment.
+    ///
+    /// Returning `Some` replaces the pushed value. Environment is not changed, so overriding
+    /// GASPRICE does not change the gas price the caller is billed with.
+    #[inline]
+    fn env_read(
+        &mut self,
+        interp: &mut Interpreter,
+        context: &mut EvmContext<DB>,
+        opcode: u8,
+        default_value: U256,
+    ) -> Option<U256> {
+        let _ = interp;
+        let _ = context;
+        let _ = opcode;
+        let _ = default_value;
+        None
+    }
+
+    /// Called when a call opcode of the contract at `address` calls `address` itself.
+    ///
+    /// `depth` is the depth of the new call frame, transaction frame has depth 0.
+    /// It is called before the call frame is made, so the call can still fail to start.
+    #[inline]
+    fn self_call(&mut self, context: &mut EvmContext<DB>, address: Address, depth: u64) {
+        let _ = context;
+        let _ = address;
+        let _ = depth;
+    }
+
+    /// Called before a call frame made by a call opcode if the `requested` gas is
+    /// more than the gas `granted` to the call.
+    ///
+    /// Since EIP-150 gas forwarded to the call is capped to all but one 64th of the
+    /// remaining gas. Granted gas does not include the call stipend.
+    #[inline]
+    fn call_gas_capped(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        requested: u64,
+        granted: u64,
+    ) {
+        let _ = context;
+        let _ = inputs;
+        let _ = requested;
+        let _ = granted;
+    }
+
+    /// Called before a call frame made by a call opcode with the gas the opcode charged
+    /// to the caller.
+    ///
+    /// `charged` is the cost of the call opcode: memory expansion, account access, value
+    /// transfer and new account costs. `forwarded` is the gas taken from the caller and
+    /// given to the call, after the EIP-150 cap of all but one 64th of the remaining gas.
+    /// `stipend` is added for free to the gas of calls that transfer value, so the gas
+    /// limit of the call is `forwarded + stipend`.
+    #[inline]
+    fn call_cost(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        charged: u64,
+        forwarded: u64,
+        stipend: u64,
+    ) {
+        let _ = context;
+        let _ = inputs;
+        let _ = charged;
+        let _ = forwarded;
+        let _ = stipend;
+    }
+
+    /// Called when the nonce of the account is changed from `old` to `new`.
+    ///
+    /// Nonce of the caller is bumped before the execution of the call transaction,
+    /// nonce of the creator is bumped by CREATE and CREATE2 and the transaction that
+    /// creates a contract, and nonce of the created account is initialized.
+    #[inline]
+    fn nonce_changed(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        address: Address,
+        old: u64,
+        new: u64,
+    ) {
+        let _ = context;
+        let _ = address;
+        let _ = old;
+        let _ = new;
+    }
+
+    /// Called before a create frame made by CREATE or CREATE2 opcode with the gas `mem_cost`
+    /// charged for memory expansion needed to read the init code.
+    #[inline]
+    fn create_memory_cost(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        mem_cost: u64,
+    ) {
+        let _ = context;
+        let _ = inputs;
+        let _ = mem_cost;
+    }
+
     /// Called when a contract has been self-destructed with funds transferred to target.
     #[inline]
     fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
@@ -142,4 +418,213 @@ pub trait Inspector<DB: Database> {
         let _ = target;
         let _ = value;
     }
+
+    /// Called after SELFDESTRUCT of `contract` is executed, `value` is the balance of the
+    /// contract that was sent to `target`.
+    ///
+    /// `was_destroyed` is false if only the balance was swept, after Cancun (EIP-6780) only
+    /// contracts created in the same transaction are destroyed. `target_existed` is whether
+    /// the target account existed before the balance was sent.
+    ///
+    /// Default implementation calls [Inspector::selfdestruct] if the contract was destroyed.
+    #[inline]
+    fn selfdestruct_detail(
+        &mut self,
+        contract: Address,
+        target: Address,
+        value: U256,
+        was_destroyed: bool,
+        target_existed: bool,
+    ) {
+        let _ = target_existed;
+        if was_destroyed {
+            self.selfdestruct(contract, target, value);
+        }
+    }
+
+    /// Called after SELFDESTRUCT of `contract` is executed and journaled.
+    ///
+    /// `transferred` is the balance credited to `target`, it is zero if the contract
+    /// selfdestructs to itself. `refund_granted` is true if the pre-London
+    /// SELFDESTRUCT gas refund was given.
+    #[inline]
+    fn selfdestruct_result(
+        &mut self,
+        contract: Address,
+        target: Address,
+        transferred: U256,
+        refund_granted: bool,
+    ) {
+        let _ = contract;
+        let _ = target;
+        let _ = transferred;
+        let _ = refund_granted;
+    }
+
+    /// Called after SELFDESTRUCT is executed with the gas `cost` charged for it.
+    ///
+    /// `cost` includes the new account cost for a `target` that did not exist (since
+    /// Spurious Dragon only if balance is sent) and the cold account access cost since
+    /// Berlin. `is_cold` is whether the target was cold before the SELFDESTRUCT.
+    #[inline]
+    fn selfdestruct_gas(
+        &mut self,
+        interp: &mut Interpreter,
+        context: &mut EvmContext<DB>,
+        target: Address,
+        cost: u64,
+        is_cold: bool,
+    ) {
+        let _ = interp;
+        let _ = context;
+        let _ = target;
+        let _ = cost;
+        let _ = is_cold;
+    }
+
+    /// Called when a REVERT opcode at `pc` is executed.
+    ///
+    /// Unlike `call_end`, this is not called for frames that revert because
+    /// of a reverted sub call or a halt.
+    #[inline]
+    fn revert_site(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>, pc: usize) {
+        let _ = interp;
+        let _ = context;
+        let _ = pc;
+    }
+
+    /// Called after an opcode writes or expands memory of the current frame, with the
+    /// range of memory that changed.
+    ///
+    /// Expansion is reported even if it only fills memory with zeros, for example MLOAD past
+    /// the end of memory. Output of a sub call copied to memory is not reported.
+    #[inline]
+    fn memory_change(&mut self, interp: &Interpreter, offset: usize, len: usize) {
+        let _ = interp;
+        let _ = offset;
+        let _ = len;
+    }
+
+    /// Called after every executed instruction with the gas it spent and the change
+    /// of the refund counter, before `step_end`.
+    ///
+    /// For calls and creates `spent` includes the gas forwarded to the new frame.
+    /// It is called for instructions that halt too. Halt consumes all gas of the frame,
+    /// so `spent` is all gas that was remaining before the instruction, unless
+    /// [`crate::primitives::CfgEnv::invalid_opcode_consumes_all`] is disabled.
+    /// `refunded_delta` is negative when the refund is reduced, for example by SSTORE.
+    #[inline]
+    fn gas_change(&mut self, interp: &Interpreter, spent: u64, refunded_delta: i64) {
+        let _ = interp;
+        let _ = spent;
+        let _ = refunded_delta;
+    }
+
+    /// Called the first time a storage slot is loaded in the transaction, with its
+    /// `original` value from before the transaction.
+    ///
+    /// Original value is the baseline of EIP-2200 and EIP-3529 gas refunds. Slots are
+    /// loaded by SLOAD and SSTORE, slots of the access list are loaded before the execution.
+    #[inline]
+    fn original_slot_loaded(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        address: Address,
+        slot: U256,
+        original: U256,
+    ) {
+        let _ = context;
+        let _ = address;
+        let _ = slot;
+        let _ = original;
+    }
+
+    /// Called after MCOPY (EIP-5656) copies `len` bytes of memory from offset `src`
+    /// to offset `dst`.
+    #[inline]
+    fn mcopy(
+        &mut self,
+        interp: &mut Interpreter,
+        context: &mut EvmContext<DB>,
+        dst: U256,
+        src: U256,
+        len: U256,
+    ) {
+        let _ = interp;
+        let _ = context;
+        let _ = dst;
+        let _ = src;
+        let _ = len;
+    }
+
+    /// Called after SSTORE writes `slot` of `storage_address` in a frame that executes
+    /// code of a different `code_address`, as in DELEGATECALL or CALLCODE.
+    #[inline]
+    fn delegate_storage_write(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        storage_address: Address,
+        code_address: Address,
+        slot: U256,
+    ) {
+        let _ = context;
+        let _ = storage_address;
+        let _ = code_address;
+        let _ = slot;
+    }
+
+    /// Called when a call fails because `from` does not have enough balance
+    /// to transfer `needed` value.
+    ///
+    /// The call is not executed and the caller continues with a failed call result.
+    #[inline]
+    fn insufficient_balance(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        from: Address,
+        needed: U256,
+        available: U256,
+    ) {
+        let _ = context;
+        let _ = from;
+        let _ = needed;
+        let _ = available;
+    }
+
+    /// Called when the transaction nonce is compared to the caller account nonce
+    /// during validation. Not called if the transaction has no nonce.
+    #[inline]
+    fn nonce_check(&mut self, context: &mut EvmContext<DB>, tx_nonce: u64, account_nonce: u64) {
+        let _ = context;
+        let _ = tx_nonce;
+        let _ = account_nonce;
+    }
+
+    /// Called when the balance `required` to pay for the transaction is compared to
+    /// the `available` caller balance during validation.
+    #[inline]
+    fn balance_check(&mut self, context: &mut EvmContext<DB>, required: U256, available: U256) {
+        let _ = context;
+        let _ = required;
+        let _ = available;
+    }
+
+    /// Called after blob versioned hash of the transaction is validated.
+    ///
+    /// `valid` is the result of the validation handle, invalid hash fails the transaction.
+    #[inline]
+    fn blob_hash_checked(&mut self, context: &mut EvmContext<DB>, hash: B256, valid: bool) {
+        let _ = context;
+        let _ = hash;
+        let _ = valid;
+    }
+
+    /// Called after the `sender` is reimbursed with `amount` for unused and refunded gas
+    /// at the end of the transaction.
+    #[inline]
+    fn gas_reimbursed(&mut self, context: &mut EvmContext<DB>, sender: Address, amount: U256) {
+        let _ = context;
+        let _ = sender;
+        let _ = amount;
+    }
 }
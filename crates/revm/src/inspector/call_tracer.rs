@@ -0,0 +1,374 @@
+//! CallTracerInspector. Builds the call frames reported by geth `callTracer`.
+
+use crate::{
+    interpreter::{
+        CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, InstructionResult,
+        InterpreterResult,
+    },
+    primitives::{
+        db::Database, Address, Bytes, CreateScheme, EVMResultGeneric, ResultAndState, U256,
+    },
+    Evm, EvmContext, Inspector,
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use serde::{Serialize, Serializer};
+
+/// Selector of the `Error(string)` revert data.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Call frame in the format of geth `callTracer`.
+///
+/// Serializes to the same JSON object as geth, gas values are hex quantities and empty
+/// fields are omitted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallTracerFrame {
+    /// Kind of the frame: `CALL`, `CALLCODE`, `DELEGATECALL`, `STATICCALL`, `CREATE`
+    /// or `CREATE2`.
+    #[serde(rename = "type")]
+    pub typ: &'static str,
+    /// Account that made the call.
+    pub from: Address,
+    /// Callee, or address of the created contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    /// Transferred value, `None` for DELEGATECALL and STATICCALL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    /// Gas available to the frame.
+    #[serde(serialize_with = "serialize_quantity")]
+    pub gas: u64,
+    /// Gas used by the frame, including gas used by its sub calls.
+    ///
+    /// Transaction frame includes the intrinsic gas.
+    #[serde(serialize_with = "serialize_quantity")]
+    pub gas_used: u64,
+    /// Call data or init code.
+    pub input: Bytes,
+    /// Returned data, or code of the created contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Bytes>,
+    /// Error of the failed frame.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Message of the `Error(string)` revert data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// Sub calls of the frame in execution order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallTracerFrame>,
+}
+
+/// [Inspector] that records call frames of a transaction in the format of geth `callTracer`.
+///
+/// Frames are reset at the start of every transaction.
+#[derive(Clone, Debug, Default)]
+pub struct CallTracerInspector {
+    /// Frames that are currently executing.
+    stack: Vec<CallTracerFrame>,
+    /// Transaction frame of the last finished transaction.
+    root: Option<CallTracerFrame>,
+}
+
+impl CallTracerInspector {
+    /// Creates new call tracer inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the transaction frame of the last transaction.
+    pub fn root(&self) -> Option<&CallTracerFrame> {
+        self.root.as_ref()
+    }
+
+    /// Starts a new frame, the transaction frame is charged the intrinsic gas.
+    fn start<DB: Database>(&mut self, context: &EvmContext<DB>, mut frame: CallTracerFrame) {
+        if context.journaled_state.depth() == 0 {
+            self.stack.clear();
+            self.root = None;
+            frame.gas = context.env.tx.gas_limit;
+        }
+        self.stack.push(frame);
+    }
+
+    /// Finishes the current frame and attaches it to its parent.
+    fn end(&mut self, result: &InterpreterResult, address: Option<Address>) {
+        let Some(mut frame) = self.stack.pop() else {
+            return;
+        };
+        if address.is_some() {
+            frame.to = address;
+        }
+        let intrinsic_gas = if self.stack.is_empty() {
+            frame.gas - result.gas.limit()
+        } else {
+            0
+        };
+        // halted frames consume all of their gas.
+        frame.gas_used = if result.is_error() {
+            frame.gas
+        } else {
+            intrinsic_gas + result.gas.spend()
+        };
+        frame.output = (!result.output.is_empty()).then(|| result.output.clone());
+        if !result.is_ok() {
+            frame.error = Some(error_message(result.result));
+            if result.result == InstructionResult::Revert {
+                frame.revert_reason = revert_reason(&result.output);
+            }
+        }
+
+        match self.stack.last_mut() {
+            Some(parent) => parent.calls.push(frame),
+            None => self.root = Some(frame),
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for CallTracerInspector {
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        let (typ, value) = match inputs.context.scheme {
+            CallScheme::Call => ("CALL", Some(inputs.transfer.value)),
+            CallScheme::CallCode => ("CALLCODE", Some(inputs.transfer.value)),
+            CallScheme::DelegateCall => ("DELEGATECALL", None),
+            CallScheme::StaticCall => ("STATICCALL", None),
+        };
+        let frame = CallTracerFrame {
+            typ,
+            from: inputs.transfer.source,
+            to: Some(inputs.contract),
+            value,
+            gas: inputs.gas_limit,
+            input: inputs.input.clone(),
+            ..Default::default()
+        };
+        self.start(context, frame);
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.end(&outcome.result, None);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        let typ = match inputs.scheme {
+            CreateScheme::Create => "CREATE",
+            CreateScheme::Create2 { .. } => "CREATE2",
+        };
+        let frame = CallTracerFrame {
+            typ,
+            from: inputs.caller,
+            value: Some(inputs.value),
+            gas: inputs.gas_limit,
+            input: inputs.init_code.clone(),
+            ..Default::default()
+        };
+        self.start(context, frame);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.end(&outcome.result, outcome.address);
+        outcome
+    }
+}
+
+impl<DB: Database> Evm<'_, CallTracerInspector, DB> {
+    /// Executes the transaction and returns its result together with the geth `callTracer`
+    /// frame of the transaction.
+    ///
+    /// Gas used by the returned frame is the gas used by the transaction after the refund.
+    /// The EVM needs to be built with [`inspector_handle_register`](crate::inspector_handle_register).
+    pub fn transact_call_trace(
+        &mut self,
+    ) -> EVMResultGeneric<(ResultAndState, CallTracerFrame), DB::Error> {
+        let result = self.transact()?;
+        let mut frame = self.context.external.root.clone().unwrap_or_default();
+        frame.gas_used = result.result.gas_used();
+        Ok((result, frame))
+    }
+}
+
+/// Serializes the value as a hex quantity.
+fn serialize_quantity<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{value:x}"))
+}
+
+/// Returns the geth error message of the failed frame.
+fn error_message(result: InstructionResult) -> String {
+    match result {
+        InstructionResult::Revert => "execution reverted",
+        InstructionResult::OutOfGas
+        | InstructionResult::MemoryOOG
+        | InstructionResult::MemoryLimitOOG
+        | InstructionResult::PrecompileOOG
+        | InstructionResult::InvalidOperandOOG => "out of gas",
+        InstructionResult::OpcodeNotFound | InstructionResult::InvalidFEOpcode => "invalid opcode",
+        InstructionResult::StackUnderflow => "stack underflow",
+        InstructionResult::StackOverflow => "stack overflow",
+        InstructionResult::InvalidJump => "invalid jump destination",
+        InstructionResult::CallNotAllowedInsideStatic
+        | InstructionResult::StateChangeDuringStaticCall => "write protection",
+        InstructionResult::CallTooDeep => "max call depth exceeded",
+        InstructionResult::OutOfFunds => "insufficient balance for transfer",
+        InstructionResult::CreateCollision => "contract address collision",
+        InstructionResult::CreateContractSizeLimit => "max code size exceeded",
+        InstructionResult::CreateContractStartingWithEF => "invalid code: must not begin with 0xef",
+        InstructionResult::NonceOverflow => "nonce uint64 overflow",
+        InstructionResult::OutOfOffset => "return data out of bounds",
+        InstructionResult::PrecompileError => "precompiled failed",
+        other => return format!("{other:?}"),
+    }
+    .to_string()
+}
+
+/// Decodes the message of the `Error(string)` revert data.
+fn revert_reason(output: &[u8]) -> Option<String> {
+    let data = output.strip_prefix(ERROR_SELECTOR.as_slice())?;
+    let word = |offset: usize| -> Option<usize> {
+        let word = data.get(offset..offset.checked_add(32)?)?;
+        usize::try_from(U256::from_be_slice(word)).ok()
+    };
+    let offset = word(0)?;
+    let len = word(offset)?;
+    let start = offset.checked_add(32)?;
+    let message = data.get(start..start.checked_add(len)?)?;
+    String::from_utf8(message.to_vec()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::contract_info;
+    use crate::{
+        db::InMemoryDB,
+        inspector::inspector_handle_register,
+        interpreter::opcode,
+        primitives::{address, Bytecode, TransactTo},
+    };
+    use serde_json::json;
+
+    #[test]
+    fn test_call_tracer() {
+        let caller = address!("1000000000000000000000000000000000000000");
+        let parent = address!("2000000000000000000000000000000000000000");
+        let reverter = address!("00000000000000000000000000000000000000a1");
+
+        // calls the reverter with 0x2a as call data.
+        let parent_code = vec![
+            opcode::PUSH1,
+            0x2a,
+            opcode::PUSH1,
+            0x00,
+            opcode::MSTORE8,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0xa1,
+            opcode::GAS,
+            opcode::CALL,
+            opcode::STOP,
+        ];
+        // reverts with `Error("fail")` copied from the end of the code.
+        let mut revert_data = ERROR_SELECTOR.to_vec();
+        revert_data.extend(U256::from(32).to_be_bytes::<32>());
+        revert_data.extend(U256::from(4).to_be_bytes::<32>());
+        revert_data.extend(b"fail");
+        revert_data.resize(4 + 3 * 32, 0);
+        let mut reverter_code = vec![
+            opcode::PUSH1,
+            revert_data.len() as u8,
+            opcode::PUSH1,
+            12,
+            opcode::PUSH1,
+            0x00,
+            opcode::CODECOPY,
+            opcode::PUSH1,
+            revert_data.len() as u8,
+            opcode::PUSH1,
+            0x00,
+            opcode::REVERT,
+        ];
+        reverter_code.extend(&revert_data);
+
+        let mut db = InMemoryDB::default();
+        for (address, code) in [(parent, parent_code), (reverter, reverter_code)] {
+            let code = Bytecode::new_raw(code.into());
+            db.insert_account_info(address, contract_info(code));
+        }
+
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .with_external_context(CallTracerInspector::new())
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = caller;
+                tx.transact_to = TransactTo::Call(parent);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let (result, frame) = evm.transact_call_trace().unwrap();
+        assert!(result.result.is_success());
+        assert_eq!(frame.gas_used, result.result.gas_used());
+
+        let sub_call = &frame.calls[0];
+        assert!(sub_call.gas_used > 0 && sub_call.gas_used < sub_call.gas);
+        assert_eq!(
+            serde_json::to_value(&frame).unwrap(),
+            json!({
+                "type": "CALL",
+                "from": caller,
+                "to": parent,
+                "value": "0x0",
+                "gas": "0x186a0",
+                "gasUsed": format!("0x{:x}", frame.gas_used),
+                "input": "0x",
+                "calls": [{
+                    "type": "CALL",
+                    "from": parent,
+                    "to": reverter,
+                    "value": "0x0",
+                    "gas": format!("0x{:x}", sub_call.gas),
+                    "gasUsed": format!("0x{:x}", sub_call.gas_used),
+                    "input": "0x2a",
+                    "output": Bytes::from(revert_data),
+                    "error": "execution reverted",
+                    "revertReason": "fail",
+                }],
+            })
+        );
+    }
+}
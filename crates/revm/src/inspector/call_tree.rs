@@ -0,0 +1,235 @@
+//! CallTreeInspector. Builds a tree of call frames with their gas usage.
+
+use crate::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, InterpreterResult},
+    primitives::{db::Database, Address},
+    EvmContext, Inspector,
+};
+use alloc::vec::Vec;
+
+/// Single call frame in the call tree.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallTraceNode {
+    /// Target of the call or address of created contract.
+    ///
+    /// For creates this is set when the frame returns.
+    pub address: Address,
+    /// Depth of the frame. Transaction frame has depth 0.
+    pub depth: usize,
+    /// Whether the frame is a create frame.
+    pub is_create: bool,
+    /// Gas limit of the frame.
+    pub gas_limit: u64,
+    /// Gas used by the frame, including gas used by its sub calls.
+    pub gas_used: u64,
+    /// Gas used by the frame excluding gas used by its sub calls.
+    pub self_gas: u64,
+    /// Whether the frame finished successfully.
+    pub success: bool,
+    /// Sub calls of the frame in execution order.
+    pub children: Vec<CallTraceNode>,
+}
+
+/// [Inspector] that records the tree of call and create frames of a transaction.
+///
+/// Tree is reset at the start of every transaction.
+#[derive(Clone, Debug, Default)]
+pub struct CallTreeInspector {
+    /// Frames that are currently executing.
+    stack: Vec<CallTraceNode>,
+    /// Transaction frame of the last finished transaction.
+    root: Option<CallTraceNode>,
+}
+
+impl CallTreeInspector {
+    /// Creates new call tree inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the call tree of the last transaction.
+    pub fn root(&self) -> Option<&CallTraceNode> {
+        self.root.as_ref()
+    }
+
+    /// Starts a new frame.
+    fn start(&mut self, address: Address, is_create: bool, gas_limit: u64, depth: usize) {
+        if depth == 0 {
+            self.stack.clear();
+            self.root = None;
+        }
+        self.stack.push(CallTraceNode {
+            address,
+            depth,
+            is_create,
+            gas_limit,
+            ..Default::default()
+        });
+    }
+
+    /// Finishes the current frame and attaches it to its parent.
+    fn end(&mut self, result: &InterpreterResult, address: Option<Address>) {
+        let Some(mut node) = self.stack.pop() else {
+            return;
+        };
+        if let Some(address) = address {
+            node.address = address;
+        }
+        node.success = result.is_ok();
+        // halted frames consume all of their gas.
+        node.gas_used = if result.is_error() {
+            node.gas_limit
+        } else {
+            result.gas.spend()
+        };
+        // gas of reverted and halted children is consumed by this frame too.
+        let children_gas = node
+            .children
+            .iter()
+            .map(|child| child.gas_used)
+            .sum::<u64>();
+        node.self_gas = node.gas_used.saturating_sub(children_gas);
+
+        match self.stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.root = Some(node),
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for CallTreeInspector {
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.start(
+            inputs.contract,
+            false,
+            inputs.gas_limit,
+            context.journaled_state.depth() as usize,
+        );
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.end(&outcome.result, None);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.start(
+            Address::ZERO,
+            true,
+            inputs.gas_limit,
+            context.journaled_state.depth() as usize,
+        );
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.end(&outcome.result, outcome.address);
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{contract_info, evm_calling};
+    use crate::{
+        db::InMemoryDB,
+        inspector::inspector_handle_register,
+        interpreter::opcode,
+        primitives::{address, Bytecode},
+    };
+
+    fn call_code(target: u8) -> Vec<u8> {
+        vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            target,
+            opcode::GAS,
+            opcode::CALL,
+            opcode::POP,
+        ]
+    }
+
+    #[test]
+    fn test_call_tree_self_gas() {
+        let parent = address!("2000000000000000000000000000000000000000");
+        let child = address!("00000000000000000000000000000000000000a1");
+        let reverter = address!("00000000000000000000000000000000000000a2");
+
+        let mut parent_code = call_code(0xa1);
+        parent_code.extend(call_code(0xa2));
+        parent_code.push(opcode::STOP);
+        let child_code = vec![
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x00,
+            opcode::MSTORE,
+            opcode::STOP,
+        ];
+        let reverter_code = vec![opcode::PUSH1, 0x00, opcode::PUSH1, 0x00, opcode::REVERT];
+
+        let mut db = InMemoryDB::default();
+        for (address, code) in [
+            (parent, parent_code),
+            (child, child_code),
+            (reverter, reverter_code),
+        ] {
+            let code = Bytecode::new_raw(code.into());
+            db.insert_account_info(address, contract_info(code));
+        }
+
+        let mut evm = evm_calling(db, parent)
+            .with_external_context(CallTreeInspector::new())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+
+        let root = evm.context.external.root().unwrap();
+        assert_eq!(root.address, parent);
+        assert!(root.success);
+        assert_eq!(root.children.len(), 2);
+
+        let (first, second) = (&root.children[0], &root.children[1]);
+        assert_eq!(first.address, child);
+        assert!(first.success);
+        assert_eq!(first.self_gas, first.gas_used);
+        assert_eq!(second.address, reverter);
+        assert!(!second.success);
+        assert!(second.gas_used > 0);
+
+        assert_eq!(
+            root.self_gas,
+            root.gas_used - first.gas_used - second.gas_used
+        );
+    }
+}
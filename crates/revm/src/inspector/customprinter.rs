@@ -8,7 +8,7 @@ use crate::{
     inspectors::GasInspector,
     interpreter::{opcode, CallInputs, CreateInputs, Interpreter},
     primitives::{Address, U256},
-    Database, EvmContext, Inspector,
+    Database, EvmContext, Inspector, StepAction,
 };
 
 /// Custom print [Inspector], it has step level information of execution.
@@ -26,7 +26,7 @@ impl<DB: Database> Inspector<DB> for CustomPrintTracer {
 
     // get opcode by calling `interp.contract.opcode(interp.program_counter())`.
     // all other information can be obtained from interp.
-    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) -> StepAction {
         let opcode = interp.current_opcode();
         let opcode_str = opcode::OPCODE_JUMPMAP[opcode as usize];
 
@@ -48,7 +48,7 @@ impl<DB: Database> Inspector<DB> for CustomPrintTracer {
             memory_size,
         );
 
-        self.gas_inspector.step(interp, context);
+        self.gas_inspector.step(interp, context)
     }
 
     fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
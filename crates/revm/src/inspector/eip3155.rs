@@ -1,21 +1,20 @@
 use crate::{
     inspectors::GasInspector,
-    interpreter::{opcode, CallInputs, CreateInputs, Interpreter},
+    interpreter::{opcode, CallInputs, CreateInputs, Interpreter, InterpreterResult},
     primitives::{db::Database, hex, U256},
-    EvmContext, Inspector,
+    EvmContext, Inspector, StepAction,
 };
 
 use revm_interpreter::CallOutcome;
 use revm_interpreter::CreateOutcome;
 use serde_json::json;
-use std::io::Write;
+use std::{io::Write, time::Instant};
 
 /// [EIP-3155](https://eips.ethereum.org/EIPS/eip-3155) tracer [Inspector].
 pub struct TracerEip3155 {
     output: Box<dyn Write>,
     gas_inspector: GasInspector,
 
-    #[allow(dead_code)]
     trace_mem: bool,
     #[allow(dead_code)]
     trace_return_data: bool,
@@ -24,8 +23,15 @@ pub struct TracerEip3155 {
     pc: usize,
     opcode: u8,
     gas: u64,
+    refund: i64,
     mem_size: usize,
+    memory: Option<String>,
     skip: bool,
+
+    /// Intrinsic gas of the transaction.
+    intrinsic_gas: u64,
+    /// Start of the transaction execution.
+    start: Option<Instant>,
 }
 
 impl TracerEip3155 {
@@ -46,9 +52,56 @@ impl TracerEip3155 {
             pc: 0,
             opcode: 0,
             gas: 0,
+            refund: 0,
             mem_size: 0,
+            memory: None,
             skip: false,
+            intrinsic_gas: 0,
+            start: None,
+        }
+    }
+
+    /// Includes memory of the frame before every opcode in the trace.
+    pub fn with_memory(mut self) -> Self {
+        self.trace_mem = true;
+        self
+    }
+
+    /// Starts the transaction if the frame is the transaction frame.
+    fn start<DB: Database>(&mut self, context: &EvmContext<DB>, gas_limit: u64) {
+        if context.journaled_state.depth() == 0 {
+            self.intrinsic_gas = context.env.tx.gas_limit.saturating_sub(gas_limit);
+            self.start = Some(Instant::now());
+        }
+    }
+
+    /// Prints the summary line if the frame is the transaction frame.
+    fn end<DB: Database>(&mut self, context: &EvmContext<DB>, result: &InterpreterResult) {
+        if context.journaled_state.depth() != 0 {
+            return;
         }
+        // halt consumes all gas.
+        let gas_used = if result.is_error() {
+            context.env.tx.gas_limit
+        } else {
+            self.intrinsic_gas + result.gas.spend()
+        };
+        let time = self
+            .start
+            .take()
+            .map(|start| start.elapsed().as_nanos() as u64)
+            .unwrap_or_default();
+        let log_line = json!({
+            //stateroot
+            "output": format!("0x{}", hex::encode(result.output.as_ref())),
+            "gasUsed": format!("0x{gas_used:x}"),
+            "pass": result.is_ok(),
+            "time": time,
+            //fork
+        });
+
+        writeln!(self.output, "{}", serde_json::to_string(&log_line).unwrap())
+            .expect("If output fails we can ignore the logging");
     }
 }
 
@@ -59,13 +112,18 @@ impl<DB: Database> Inspector<DB> for TracerEip3155 {
 
     // get opcode by calling `interp.contract.opcode(interp.program_counter())`.
     // all other information can be obtained from interp.
-    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) -> StepAction {
         self.gas_inspector.step(interp, context);
         self.stack = interp.stack.data().clone();
         self.pc = interp.program_counter();
         self.opcode = interp.current_opcode();
         self.mem_size = interp.shared_memory.len();
+        self.memory = self
+            .trace_mem
+            .then(|| format!("0x{}", hex::encode(interp.shared_memory.context_memory())));
         self.gas = interp.gas.remaining();
+        self.refund = interp.gas.refunded();
+        StepAction::Continue
     }
 
     fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
@@ -75,17 +133,15 @@ impl<DB: Database> Inspector<DB> for TracerEip3155 {
             return;
         };
 
-        self.print_log_line(
-            context.journaled_state.depth(),
-            interp.shared_memory.context_memory(),
-        );
+        self.print_log_line(context.journaled_state.depth());
     }
 
     fn call(
         &mut self,
-        _context: &mut EvmContext<DB>,
-        _inputs: &mut CallInputs,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
     ) -> Option<CallOutcome> {
+        self.start(context, inputs.gas_limit);
         None
     }
 
@@ -96,26 +152,16 @@ impl<DB: Database> Inspector<DB> for TracerEip3155 {
         outcome: CallOutcome,
     ) -> CallOutcome {
         let outcome = self.gas_inspector.call_end(context, inputs, outcome);
-        if context.journaled_state.depth() == 0 {
-            let log_line = json!({
-                //stateroot
-                "output": format!("0x{}", hex::encode(outcome.result.output.as_ref())),
-                "gasUsed": format!("0x{:x}", self.gas_inspector.gas_remaining()),
-                //time
-                //fork
-            });
-
-            writeln!(self.output, "{}", serde_json::to_string(&log_line).unwrap())
-                .expect("If output fails we can ignore the logging");
-        }
+        self.end(context, &outcome.result);
         outcome
     }
 
     fn create(
         &mut self,
-        _context: &mut EvmContext<DB>,
-        _inputs: &mut CreateInputs,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
     ) -> Option<CreateOutcome> {
+        self.start(context, inputs.gas_limit);
         None
     }
 
@@ -125,30 +171,33 @@ impl<DB: Database> Inspector<DB> for TracerEip3155 {
         inputs: &CreateInputs,
         outcome: CreateOutcome,
     ) -> CreateOutcome {
-        self.gas_inspector.create_end(context, inputs, outcome)
+        let outcome = self.gas_inspector.create_end(context, inputs, outcome);
+        self.end(context, &outcome.result);
+        outcome
     }
 }
 
 impl TracerEip3155 {
-    fn print_log_line(&mut self, depth: u64, _memory: &[u8]) {
+    fn print_log_line(&mut self, depth: u64) {
         let short_stack: Vec<String> = self.stack.iter().map(|&b| short_hex(b)).collect();
-        let log_line = json!({
+        let mut log_line = json!({
             "depth": depth,
             "pc": self.pc,
             "opName": opcode::OPCODE_JUMPMAP[self.opcode as usize],
             "op": self.opcode,
             "gas": format!("0x{:x}", self.gas),
             "gasCost": format!("0x{:x}", self.gas_inspector.last_gas_cost()),
-            //memory?
-            //"memory": format!("{}", hex::encode(memory)),
             "memSize": self.mem_size,
             "stack": short_stack,
             //returnData
-            //refund
+            "refund": self.refund,
             //error
             //storage
             //returnStack
         });
+        if let Some(memory) = self.memory.take() {
+            log_line["memory"] = memory.into();
+        }
 
         writeln!(self.output, "{}", serde_json::to_string(&log_line).unwrap())
             .expect("If output fails we can ignore the logging");
@@ -164,3 +213,90 @@ fn short_hex(b: U256) -> String {
         format!("0x{s}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::evm_calling;
+    use crate::{
+        db::BenchmarkDB,
+        inspector::inspector_handle_register,
+        primitives::{Address, Bytecode},
+    };
+    use serde_json::Value;
+    use std::{cell::RefCell, rc::Rc};
+
+    /// Writer that shares written bytes with the test.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_eip3155_trace() {
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+        let buffer = SharedBuffer::default();
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(
+                TracerEip3155::new(Box::new(buffer.clone()), false, false).with_memory(),
+            )
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+
+        let output = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 5);
+
+        let push = &lines[0];
+        assert_eq!(push["pc"], 0);
+        assert_eq!(push["op"], opcode::PUSH1);
+        assert_eq!(push["gas"], format!("0x{:x}", 100_000 - 21_000));
+        assert_eq!(push["gasCost"], "0x3");
+        assert_eq!(push["depth"], 1);
+        assert_eq!(push["refund"], 0);
+
+        let mstore = &lines[2];
+        assert_eq!(mstore["op"], opcode::MSTORE);
+        assert_eq!(mstore["stack"], serde_json::json!(["0x1", "0x0"]));
+        // static cost and one word of memory expansion.
+        assert_eq!(mstore["gasCost"], "0x6");
+        assert_eq!(mstore["memSize"], 0);
+        assert_eq!(mstore["memory"], "0x");
+
+        let stop = &lines[3];
+        assert_eq!(stop["memSize"], 32);
+        assert_eq!(stop["memory"], format!("0x{:064x}", 1));
+
+        let summary = &lines[4];
+        assert_eq!(summary["output"], "0x");
+        assert_eq!(summary["gasUsed"], format!("0x{:x}", result.gas_used()));
+        assert_eq!(summary["pass"], true);
+        assert!(summary["time"].is_u64());
+    }
+}
@@ -0,0 +1,264 @@
+//! FlamegraphInspector. Collects gas used by every call path for gas flamegraphs.
+
+use crate::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, InterpreterResult},
+    primitives::{db::Database, Address},
+    EvmContext, Inspector,
+};
+use alloc::vec::Vec;
+
+/// Single call frame of the flamegraph.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FlamegraphFrame {
+    /// Addresses of the frames from the transaction frame to this frame.
+    ///
+    /// For creates the address of the created contract is set when the frame returns.
+    pub path: Vec<Address>,
+    /// First four bytes of the call data, `None` for creates and shorter call data.
+    pub selector: Option<[u8; 4]>,
+    /// Depth of the frame. Transaction frame has depth 0.
+    pub depth: usize,
+    /// Gas used by the frame, including gas used by its sub calls.
+    pub gas_used: u64,
+    /// Gas used by the frame excluding gas used by its sub calls.
+    pub self_gas: u64,
+}
+
+/// [Inspector] that records gas used by every call frame together with its call path.
+///
+/// Frames are reset at the start of every transaction.
+#[derive(Clone, Debug, Default)]
+pub struct FlamegraphInspector {
+    /// Frames in the order they were started.
+    frames: Vec<FlamegraphFrame>,
+    /// Index and gas limit of the frames that are currently executing.
+    stack: Vec<(usize, u64)>,
+}
+
+impl FlamegraphInspector {
+    /// Creates new flamegraph inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns frames of the last transaction in the order they were started.
+    pub fn frames(&self) -> &[FlamegraphFrame] {
+        &self.frames
+    }
+
+    /// Returns the call path and the self gas of every frame of the last transaction.
+    ///
+    /// Entries are in the order frames were started, paths of repeated calls are not merged.
+    /// Every entry is a line of the folded stack format used by flamegraph tools.
+    pub fn folded_stacks(&self) -> Vec<(Vec<Address>, u64)> {
+        self.frames
+            .iter()
+            .map(|frame| (frame.path.clone(), frame.self_gas))
+            .collect()
+    }
+
+    /// Starts a new frame.
+    fn start<DB: Database>(
+        &mut self,
+        context: &EvmContext<DB>,
+        address: Address,
+        selector: Option<[u8; 4]>,
+        gas_limit: u64,
+    ) {
+        let depth = context.journaled_state.depth() as usize;
+        if depth == 0 {
+            self.frames.clear();
+            self.stack.clear();
+        }
+        let mut path = self
+            .stack
+            .last()
+            .map(|(index, _)| self.frames[*index].path.clone())
+            .unwrap_or_default();
+        path.push(address);
+        self.stack.push((self.frames.len(), gas_limit));
+        self.frames.push(FlamegraphFrame {
+            path,
+            selector,
+            depth,
+            ..Default::default()
+        });
+    }
+
+    /// Finishes the current frame and computes its gas.
+    fn end(&mut self, result: &InterpreterResult, address: Option<Address>) {
+        let Some((index, gas_limit)) = self.stack.pop() else {
+            return;
+        };
+        let depth = self.frames[index].depth;
+        // set created address in paths of the frame and its sub calls.
+        if let Some(address) = address {
+            for frame in &mut self.frames[index..] {
+                frame.path[depth] = address;
+            }
+        }
+        // halted frames consume all of their gas.
+        let gas_used = if result.is_error() {
+            gas_limit
+        } else {
+            result.gas.spend()
+        };
+        let children_gas = self.frames[index + 1..]
+            .iter()
+            .filter(|frame| frame.depth == depth + 1)
+            .map(|frame| frame.gas_used)
+            .sum::<u64>();
+        let frame = &mut self.frames[index];
+        frame.gas_used = gas_used;
+        frame.self_gas = gas_used.saturating_sub(children_gas);
+    }
+}
+
+impl<DB: Database> Inspector<DB> for FlamegraphInspector {
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        let selector = inputs
+            .input
+            .get(..4)
+            .map(|selector| selector.try_into().unwrap());
+        self.start(context, inputs.contract, selector, inputs.gas_limit);
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.end(&outcome.result, None);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.start(context, Address::ZERO, None, inputs.gas_limit);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.end(&outcome.result, outcome.address);
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{contract_info, evm_calling};
+    use crate::{
+        db::InMemoryDB,
+        inspector::inspector_handle_register,
+        interpreter::opcode,
+        primitives::{address, Bytecode},
+    };
+
+    /// Calls `target` with the last four bytes of the first memory word as call data.
+    fn call_code(target: u8) -> Vec<u8> {
+        vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x04,
+            opcode::PUSH1,
+            0x1c,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            target,
+            opcode::GAS,
+            opcode::CALL,
+            opcode::POP,
+        ]
+    }
+
+    #[test]
+    fn test_flamegraph_folded_stacks() {
+        let root = address!("2000000000000000000000000000000000000000");
+        let middle = address!("00000000000000000000000000000000000000a1");
+        let leaf = address!("00000000000000000000000000000000000000a2");
+
+        // stores selector 0x12345678 in memory and calls the middle contract.
+        let mut root_code = vec![
+            opcode::PUSH4,
+            0x12,
+            0x34,
+            0x56,
+            0x78,
+            opcode::PUSH1,
+            0x00,
+            opcode::MSTORE,
+        ];
+        root_code.extend(call_code(0xa1));
+        root_code.push(opcode::STOP);
+        // calls the leaf twice with empty memory as call data.
+        let mut middle_code = call_code(0xa2);
+        middle_code.extend(call_code(0xa2));
+        middle_code.push(opcode::STOP);
+        let leaf_code = vec![opcode::PUSH1, 0x01, opcode::POP, opcode::STOP];
+
+        let mut db = InMemoryDB::default();
+        for (address, code) in [(root, root_code), (middle, middle_code), (leaf, leaf_code)] {
+            let code = Bytecode::new_raw(code.into());
+            db.insert_account_info(address, contract_info(code));
+        }
+
+        let mut evm = evm_calling(db, root)
+            .with_external_context(FlamegraphInspector::new())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+
+        let inspector = &evm.context.external;
+        let frames = inspector.frames();
+        assert_eq!(
+            frames
+                .iter()
+                .map(|frame| (frame.depth, frame.selector))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, None),
+                (1, Some([0x12, 0x34, 0x56, 0x78])),
+                (2, Some([0; 4])),
+                (2, Some([0; 4])),
+            ]
+        );
+
+        // leaf executes PUSH1, POP and STOP.
+        let stacks = inspector.folded_stacks();
+        assert_eq!(
+            stacks,
+            vec![
+                (vec![root], frames[0].self_gas),
+                (vec![root, middle], frames[1].self_gas),
+                (vec![root, middle, leaf], 5),
+                (vec![root, middle, leaf], 5),
+            ]
+        );
+        assert_eq!(frames[1].gas_used, frames[1].self_gas + 10);
+        // self gas of all paths adds up to the gas used by the transaction frame.
+        let total = stacks.iter().map(|(_, gas)| gas).sum::<u64>();
+        assert_eq!(total, frames[0].gas_used);
+        assert_eq!(total + 21_000, result.gas_used());
+    }
+}
@@ -41,7 +41,7 @@ impl<DB: Database> Inspector<DB> for GasInspector {
         _context: &mut EvmContext<DB>,
     ) {
         let last_gas = core::mem::replace(&mut self.gas_remaining, interp.gas.remaining());
-        self.last_gas_cost = last_gas.saturating_sub(self.last_gas_cost);
+        self.last_gas_cost = last_gas.saturating_sub(self.gas_remaining);
     }
 
     fn call_end(
@@ -80,7 +80,7 @@ mod tests {
         inspectors::GasInspector,
         interpreter::{CallInputs, CreateInputs, Interpreter},
         primitives::Log,
-        Database, EvmContext, Inspector,
+        Database, EvmContext, Inspector, StepAction,
     };
 
     #[derive(Default, Debug)]
@@ -95,9 +95,9 @@ mod tests {
             self.gas_inspector.initialize_interp(interp, context);
         }
 
-        fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) -> StepAction {
             self.pc = interp.program_counter();
-            self.gas_inspector.step(interp, context);
+            self.gas_inspector.step(interp, context)
         }
 
         fn log(&mut self, context: &mut EvmContext<DB>, log: &Log) {
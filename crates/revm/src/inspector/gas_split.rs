@@ -0,0 +1,156 @@
+//! GasSplitInspector. Splits transaction gas between precompiles and EVM execution.
+
+use crate::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Gas},
+    primitives::db::Database,
+    EvmContext, Inspector,
+};
+
+/// [Inspector] that splits gas of a transaction into gas spent in precompiles
+/// and gas spent in EVM execution.
+///
+/// EVM gas contains intrinsic gas and everything that was not spent inside a precompile,
+/// including the cost of the calls to precompiles. Refunds are not deducted,
+/// so the sum of both is the gas used by the transaction before refunds.
+///
+/// Gas is reset at the start of every transaction.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GasSplitInspector {
+    /// Intrinsic gas of the transaction.
+    intrinsic_gas: u64,
+    /// Gas spent in precompiles.
+    precompile_gas: u64,
+    /// Total gas spent by the transaction before refunds.
+    total_gas: u64,
+}
+
+impl GasSplitInspector {
+    /// Creates new gas split inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns gas spent in precompiles.
+    pub fn precompile_gas(&self) -> u64 {
+        self.precompile_gas
+    }
+
+    /// Returns gas spent in EVM execution, including intrinsic gas.
+    pub fn evm_gas(&self) -> u64 {
+        self.total_gas.saturating_sub(self.precompile_gas)
+    }
+
+    /// Resets the split at the start of the transaction.
+    fn start<DB: Database>(&mut self, context: &EvmContext<DB>, gas_limit: u64) {
+        if context.journaled_state.depth() == 0 {
+            *self = Self {
+                intrinsic_gas: context.env.tx.gas_limit.saturating_sub(gas_limit),
+                ..Default::default()
+            };
+        }
+    }
+
+    /// Records total gas at the end of the transaction frame.
+    fn end<DB: Database>(&mut self, context: &EvmContext<DB>, gas: &Gas) {
+        if context.journaled_state.depth() == 0 {
+            self.total_gas = self.intrinsic_gas + gas.spend();
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for GasSplitInspector {
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.start(context, inputs.gas_limit);
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        if context.precompiles.contains(&inputs.contract) {
+            self.precompile_gas += outcome.result.gas.spend();
+        }
+        self.end(context, &outcome.result.gas);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.start(context, inputs.gas_limit);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.end(context, &outcome.result.gas);
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::evm_calling;
+    use crate::{
+        db::BenchmarkDB,
+        inspector::inspector_handle_register,
+        interpreter::opcode,
+        primitives::{Address, Bytecode},
+    };
+
+    #[test]
+    fn test_gas_split() {
+        // hashes 32 bytes of memory with SHA256 precompile.
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x02,
+                opcode::GAS,
+                opcode::CALL,
+                opcode::POP,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(GasSplitInspector::new())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+
+        let inspector = &evm.context.external;
+        // SHA256 of one word costs 60 + 12.
+        assert_eq!(inspector.precompile_gas(), 72);
+        assert_eq!(
+            inspector.precompile_gas() + inspector.evm_gas(),
+            result.gas_used()
+        );
+    }
+}
@@ -1,10 +1,18 @@
-use core::cell::RefCell;
+use core::cell::{Cell, RefCell};
 
 use crate::{
     db::Database,
-    handler::register::{EvmHandler, EvmInstructionTables},
-    interpreter::{opcode, opcode::BoxedInstruction, InstructionResult, Interpreter},
-    Evm, FrameOrResult, FrameResult, Inspector, JournalEntry,
+    handler::{
+        mainnet::returns_halt_gas,
+        register::{EvmHandler, EvmInstructionTables},
+        CallerCheck,
+    },
+    interpreter::{
+        gas, opcode, opcode::BoxedInstruction, CallInputs, InstructionResult, Interpreter,
+        InterpreterAction, Stack,
+    },
+    primitives::{Account, Address, Env, HashSet, SpecId, State, B256, U256},
+    Evm, EvmContext, FrameOrResult, FrameResult, Inspector, JournalEntry, StepAction,
 };
 use alloc::{boxed::Box, rc::Rc, sync::Arc, vec::Vec};
 
@@ -19,6 +27,19 @@ impl<DB: Database, INSP: Inspector<DB>> GetInspector<DB> for INSP {
     }
 }
 
+/// Gas of the call opcode that made the call frame.
+#[derive(Clone, Copy, Debug)]
+struct CallGas {
+    /// Gas requested on the stack.
+    requested: u64,
+    /// Gas taken from the caller and forwarded to the call, without the stipend.
+    granted: u64,
+    /// Gas charged to the caller, without the forwarded gas.
+    charged: u64,
+    /// Call stipend added to the forwarded gas.
+    stipend: u64,
+}
+
 /// Register Inspector handles that interact with Inspector instance.
 ///
 ///
@@ -28,12 +49,22 @@ impl<DB: Database, INSP: Inspector<DB>> GetInspector<DB> for INSP {
 /// calls them before (or after) calling Inspector. This means that it is safe
 /// to use this register with any other register.
 ///
-/// A few instructions handlers are wrapped twice once for `step` and `step_end`
-/// and in case of Logs and Selfdestruct wrapper is wrapped again for the
-/// `log` and `selfdestruct` calls.
+/// A few instructions handlers are wrapped twice, once for opcode specific calls like
+/// `log` and `selfdestruct` and once for `step` and `step_end`. The `step` wrapper is
+/// the outermost one, so instructions that are skipped or halted by `step` do not
+/// reach the opcode specific calls.
 pub fn inspector_handle_register<'a, DB: Database, EXT: GetInspector<DB>>(
     handler: &mut EvmHandler<'a, EXT, DB>,
 ) {
+    // call and create input stack shared between handlers. They are used to share
+    // inputs in *_end Inspector calls.
+    let call_input_stack = Rc::<RefCell<Vec<Box<CallInputs>>>>::new(RefCell::new(Vec::new()));
+    let create_input_stack = Rc::<RefCell<Vec<_>>>::new(RefCell::new(Vec::new()));
+    // memory expansion cost of the last CREATE or CREATE2 opcode, shared with create handle.
+    let create_memory_cost = Rc::new(Cell::new(None));
+    // gas of the last call opcode, shared with call handle.
+    let call_gas = Rc::new(Cell::new(None::<CallGas>));
+
     // Every instruction inside flat table that is going to be wrapped by inspector calls.
     let table = handler
         .instruction_table
@@ -42,12 +73,9 @@ pub fn inspector_handle_register<'a, DB: Database, EXT: GetInspector<DB>>(
     let mut table = match table {
         EvmInstructionTables::Plain(table) => table
             .into_iter()
-            .map(|i| inspector_instruction(i))
-            .collect::<Vec<_>>(),
-        EvmInstructionTables::Boxed(table) => table
-            .into_iter()
-            .map(|i| inspector_instruction(i))
+            .map(|i| -> BoxedInstruction<'a, Evm<'a, EXT, DB>> { Box::new(i) })
             .collect::<Vec<_>>(),
+        EvmInstructionTables::Boxed(table) => table.into_iter().collect::<Vec<_>>(),
     };
 
     // Register inspector Log instruction.
@@ -57,6 +85,7 @@ pub fn inspector_handle_register<'a, DB: Database, EXT: GetInspector<DB>>(
             *i = Box::new(
                 move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
                     let old_log_len = host.context.evm.journaled_state.logs.len();
+                    let old_gas = interpreter.gas;
                     old(interpreter, host);
                     // check if log was added. It is possible that revert happened
                     // cause of gas or stack underflow.
@@ -73,10 +102,23 @@ pub fn inspector_handle_register<'a, DB: Database, EXT: GetInspector<DB>>(
                             .unwrap()
                             .clone();
                         // call Inspector
-                        host.context
-                            .external
-                            .get_inspector()
-                            .log(&mut host.context.evm, &last_log);
+                        let inspector = host.context.external.get_inspector();
+                        // journal depth is incremented when the transaction frame is entered.
+                        let depth = host.context.evm.journaled_state.depth() - 1;
+                        inspector.log_pre_revert(&mut host.context.evm, &last_log, depth);
+                        inspector.log(&mut host.context.evm, &last_log);
+
+                        // gas charged for the log without memory expansion.
+                        let gas = interpreter.gas;
+                        let charged =
+                            (gas.spend() - old_gas.spend()) - (gas.memory() - old_gas.memory());
+                        inspector.log_gas(
+                            interpreter,
+                            &mut host.context.evm,
+                            last_log.data.topics().len(),
+                            last_log.data.data.len(),
+                            charged,
+                        );
                     }
                 },
             )
@@ -89,52 +131,492 @@ pub fn inspector_handle_register<'a, DB: Database, EXT: GetInspector<DB>>(
     inspect_log(opcode::LOG3);
     inspect_log(opcode::LOG4);
 
+    // register transient storage instructions.
+    if let Some(i) = table.get_mut(opcode::TSTORE as usize) {
+        let old = core::mem::replace(i, Box::new(|_, _| ()));
+        *i = Box::new(
+            move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
+                let key = interpreter.stack.peek(0).unwrap_or_default();
+                let value = interpreter.stack.peek(1).unwrap_or_default();
+                old(interpreter, host);
+                // write is not done on stack underflow or out of gas.
+                if interpreter.instruction_result == InstructionResult::Continue {
+                    host.context.external.get_inspector().tstore(
+                        &mut host.context.evm,
+                        interpreter.contract.address,
+                        key,
+                        value,
+                    );
+                }
+            },
+        )
+    }
+    if let Some(i) = table.get_mut(opcode::TLOAD as usize) {
+        let old = core::mem::replace(i, Box::new(|_, _| ()));
+        *i = Box::new(
+            move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
+                let key = interpreter.stack.peek(0).unwrap_or_default();
+                old(interpreter, host);
+                if interpreter.instruction_result == InstructionResult::Continue {
+                    // loaded value replaces the key on the top of the stack.
+                    let value = interpreter.stack.peek(0).unwrap_or_default();
+                    host.context.external.get_inspector().tload(
+                        &mut host.context.evm,
+                        interpreter.contract.address,
+                        key,
+                        value,
+                    );
+                }
+            },
+        )
+    }
+
+    // register EXP instruction that charges gas by the exponent byte length.
+    if let Some(i) = table.get_mut(opcode::EXP as usize) {
+        let old = core::mem::replace(i, Box::new(|_, _| ()));
+        *i = Box::new(
+            move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
+                let exponent = interpreter.stack.peek(1).unwrap_or_default();
+                let old_gas = interpreter.gas;
+                old(interpreter, host);
+                if interpreter.instruction_result == InstructionResult::Continue {
+                    let exponent_byte_len = exponent.bit_len().div_ceil(8);
+                    let dynamic_cost = interpreter.gas.spend() - old_gas.spend() - gas::EXP;
+                    host.context.external.get_inspector().exp_gas(
+                        interpreter,
+                        &mut host.context.evm,
+                        exponent_byte_len,
+                        dynamic_cost,
+                    );
+                }
+            },
+        )
+    }
+
+    // register calldata instructions that zero-pad reads past the end of calldata.
+    let mut inspect_calldata_read = |index: u8| {
+        if let Some(i) = table.get_mut(index as usize) {
+            let old = core::mem::replace(i, Box::new(|_, _| ()));
+            *i = Box::new(
+                move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
+                    // offset and length of the read.
+                    let read = if index == opcode::CALLDATALOAD {
+                        interpreter
+                            .stack
+                            .peek(0)
+                            .map(|offset| (offset, U256::from(32)))
+                    } else {
+                        interpreter
+                            .stack
+                            .peek(1)
+                            .and_then(|offset| Ok((offset, interpreter.stack.peek(2)?)))
+                    };
+                    old(interpreter, host);
+                    let Ok((offset, len)) = read else {
+                        return;
+                    };
+                    if interpreter.instruction_result != InstructionResult::Continue
+                        || len == U256::ZERO
+                    {
+                        return;
+                    }
+                    let input_len = U256::from(interpreter.contract.input.len());
+                    if offset.saturating_add(len) > input_len {
+                        let offset = usize::try_from(offset).unwrap_or(usize::MAX);
+                        host.context.external.get_inspector().calldata_oob_read(
+                            interpreter,
+                            &mut host.context.evm,
+                            offset,
+                        );
+                    }
+                },
+            )
+        }
+    };
+
+    inspect_calldata_read(opcode::CALLDATALOAD);
+    inspect_calldata_read(opcode::CALLDATACOPY);
+
     // // register selfdestruct function.
     if let Some(i) = table.get_mut(opcode::SELFDESTRUCT as usize) {
         let old = core::mem::replace(i, Box::new(|_, _| ()));
         *i = Box::new(
             move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
-                // execute selfdestruct
-                old(interpreter, host);
-                // check if selfdestruct was successful and if journal entry is made.
-                if let Some(JournalEntry::AccountDestroyed {
-                    address,
-                    target,
-                    had_balance,
-                    ..
-                }) = host
+                let contract = interpreter.contract.address;
+                let target = interpreter
+                    .stack
+                    .peek(0)
+                    .map(|target| Address::from_word(B256::from(target)))
+                    .unwrap_or_default();
+                let balance = host
                     .context
                     .evm
                     .journaled_state
-                    .journal
-                    .last()
-                    .unwrap()
+                    .state
+                    .get(&contract)
+                    .map(|account| account.info.balance)
+                    .unwrap_or_default();
+                let spec_id = host.context.evm.journaled_state.spec;
+                let target_existed =
+                    host.context
+                        .evm
+                        .journaled_state
+                        .state
+                        .get(&target)
+                        .map(|account| {
+                            selfdestruct_target_exists(
+                                spec_id,
+                                account,
+                                U256::ZERO,
+                                account.is_touched(),
+                            )
+                        });
+                let journaled_state = &host.context.evm.journaled_state;
+                let is_cold = if journaled_state.state.contains_key(&target) {
+                    journaled_state.cold_accounts.contains(&target)
+                } else {
+                    !journaled_state.warm_preloaded_addresses.contains(&target)
+                };
+                let refunded = interpreter.gas.refunded();
+                let old_gas = interpreter.gas;
+                // execute selfdestruct
+                old(interpreter, host);
+                if interpreter.instruction_result == InstructionResult::SelfDestruct {
+                    // balance of the contract is burned if it is the target.
+                    let transferred = if target == contract {
+                        U256::ZERO
+                    } else {
+                        balance
+                    };
+                    let journaled_state = &host.context.evm.journaled_state;
+                    // destroyed contract has its journal entry, otherwise the balance was swept.
+                    let was_destroyed = matches!(
+                        journaled_state.journal.last().and_then(|entries| entries.last()),
+                        Some(JournalEntry::AccountDestroyed { address, .. }) if *address == contract
+                    );
+                    // target that was not loaded before is loaded by the selfdestruct.
+                    let target_existed = target_existed.unwrap_or_else(|| {
+                        journaled_state.state.get(&target).is_some_and(|account| {
+                            selfdestruct_target_exists(spec_id, account, transferred, false)
+                        })
+                    });
+                    let cost = interpreter.gas.spend() - old_gas.spend();
+                    host.context.external.get_inspector().selfdestruct_gas(
+                        interpreter,
+                        &mut host.context.evm,
+                        target,
+                        cost,
+                        is_cold,
+                    );
+                    host.context.external.get_inspector().selfdestruct_detail(
+                        contract,
+                        target,
+                        balance,
+                        was_destroyed,
+                        target_existed,
+                    );
+                    host.context.external.get_inspector().selfdestruct_result(
+                        contract,
+                        target,
+                        transferred,
+                        interpreter.gas.refunded() > refunded,
+                    );
+                }
+            },
+        )
+    }
+
+    // register revert function.
+    if let Some(i) = table.get_mut(opcode::REVERT as usize) {
+        let old = core::mem::replace(i, Box::new(|_, _| ()));
+        *i = Box::new(
+            move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
+                // PC is already incremented.
+                let pc = interpreter.program_counter() - 1;
+                old(interpreter, host);
+                // check if revert was executed. It can fail cause of gas or stack underflow.
+                if interpreter.instruction_result == InstructionResult::Revert {
+                    host.context.external.get_inspector().revert_site(
+                        interpreter,
+                        &mut host.context.evm,
+                        pc,
+                    );
+                }
+            },
+        )
+    }
+
+    // register mcopy function.
+    if let Some(i) = table.get_mut(opcode::MCOPY as usize) {
+        let old = core::mem::replace(i, Box::new(|_, _| ()));
+        *i = Box::new(
+            move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
+                // read operands before they are popped.
+                let dst = interpreter.stack.peek(0).unwrap_or_default();
+                let src = interpreter.stack.peek(1).unwrap_or_default();
+                let len = interpreter.stack.peek(2).unwrap_or_default();
+                old(interpreter, host);
+                if interpreter.instruction_result == InstructionResult::Continue {
+                    host.context.external.get_inspector().mcopy(
+                        interpreter,
+                        &mut host.context.evm,
+                        dst,
+                        src,
+                        len,
+                    );
+                }
+            },
+        )
+    }
+
+    // register create functions.
+    for opcode in [opcode::CREATE, opcode::CREATE2] {
+        let create_memory_cost = create_memory_cost.clone();
+        if let Some(i) = table.get_mut(opcode as usize) {
+            let old = core::mem::replace(i, Box::new(|_, _| ()));
+            *i = Box::new(
+                move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
+                    let memory_before = interpreter.gas.memory();
+                    old(interpreter, host);
+                    if let InterpreterAction::Create { .. } = interpreter.next_action {
+                        create_memory_cost.set(Some(interpreter.gas.memory() - memory_before));
+                    }
+                },
+            )
+        }
+    }
+
+    // register environment read functions.
+    for opcode in [
+        opcode::TIMESTAMP,
+        opcode::NUMBER,
+        opcode::GASLIMIT,
+        opcode::CHAINID,
+        opcode::BASEFEE,
+        opcode::COINBASE,
+        opcode::BLOCKHASH,
+        opcode::GASPRICE,
+    ] {
+        if let Some(i) = table.get_mut(opcode as usize) {
+            let old = core::mem::replace(i, Box::new(|_, _| ()));
+            *i = Box::new(
+                move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
+                    old(interpreter, host);
+                    if interpreter.instruction_result != InstructionResult::Continue {
+                        return;
+                    }
+                    let Ok(default_value) = interpreter.stack.peek(0) else {
+                        return;
+                    };
+                    if let Some(value) = host.context.external.get_inspector().env_read(
+                        interpreter,
+                        &mut host.context.evm,
+                        opcode,
+                        default_value,
+                    ) {
+                        // value was pushed by the instruction so the stack is not empty.
+                        let _ = interpreter.stack.set(0, value);
+                    }
+                },
+            )
+        }
+    }
+
+    // register call functions.
+    for opcode in [
+        opcode::CALL,
+        opcode::CALLCODE,
+        opcode::DELEGATECALL,
+        opcode::STATICCALL,
+    ] {
+        let call_gas = call_gas.clone();
+        if let Some(i) = table.get_mut(opcode as usize) {
+            let old = core::mem::replace(i, Box::new(|_, _| ()));
+            *i = Box::new(
+                move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
+                    let requested = interpreter
+                        .stack
+                        .peek(0)
+                        .map(|gas| u64::try_from(gas).unwrap_or(u64::MAX))
+                        .unwrap_or_default();
+                    let gas_before = interpreter.gas.remaining();
+                    old(interpreter, host);
+                    if let InterpreterAction::Call { inputs } = &interpreter.next_action {
+                        // stipend is added only to calls that transfer value.
+                        let stipend = if inputs.transfer.value == U256::ZERO {
+                            0
+                        } else {
+                            host.context
+                                .evm
+                                .env
+                                .cfg
+                                .call_stipend
+                                .unwrap_or(gas::CALL_STIPEND)
+                        };
+                        let granted = inputs.gas_limit - stipend;
+                        // spent gas contains the granted gas that is forwarded to the call.
+                        let spent = gas_before - interpreter.gas.remaining();
+                        call_gas.set(Some(CallGas {
+                            requested,
+                            granted,
+                            charged: spent - granted,
+                            stipend,
+                        }));
+                        if inputs.contract == interpreter.contract.address {
+                            // journal depth of the current frame is the depth of the new frame.
+                            let depth = host.context.evm.journaled_state.depth();
+                            host.context.external.get_inspector().self_call(
+                                &mut host.context.evm,
+                                inputs.contract,
+                                depth,
+                            );
+                        }
+                    }
+                },
+            )
+        }
+    }
+
+    // register memory changing functions.
+    for opcode in [
+        opcode::MLOAD,
+        opcode::MSTORE,
+        opcode::MSTORE8,
+        opcode::KECCAK256,
+        opcode::CALLDATACOPY,
+        opcode::CODECOPY,
+        opcode::EXTCODECOPY,
+        opcode::RETURNDATACOPY,
+        opcode::MCOPY,
+        opcode::LOG0,
+        opcode::LOG1,
+        opcode::LOG2,
+        opcode::LOG3,
+        opcode::LOG4,
+        opcode::CREATE,
+        opcode::CREATE2,
+        opcode::CALL,
+        opcode::CALLCODE,
+        opcode::DELEGATECALL,
+        opcode::STATICCALL,
+        opcode::RETURN,
+        opcode::REVERT,
+    ] {
+        if let Some(i) = table.get_mut(opcode as usize) {
+            let old = core::mem::replace(i, Box::new(|_, _| ()));
+            *i = Box::new(
+                move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
+                    let write = memory_write_range(opcode, &interpreter.stack);
+                    let len_before = interpreter.shared_memory.len();
+                    old(interpreter, host);
+                    if interpreter.instruction_result.is_error() {
+                        return;
+                    }
+                    let len_after = interpreter.shared_memory.len();
+
+                    let (mut start, mut end) = match write {
+                        Some((offset, len)) if len != 0 => (offset, offset.saturating_add(len)),
+                        _ => (usize::MAX, 0),
+                    };
+                    // expanded memory is zeroed.
+                    if len_after > len_before {
+                        start = start.min(len_before);
+                        end = end.max(len_after);
+                    }
+                    if start < end {
+                        host.context.external.get_inspector().memory_change(
+                            interpreter,
+                            start,
+                            end - start,
+                        );
+                    }
+                },
+            )
+        }
+    }
+
+    // register storage load functions.
+    for opcode in [opcode::SLOAD, opcode::SSTORE] {
+        if let Some(i) = table.get_mut(opcode as usize) {
+            let old = core::mem::replace(i, Box::new(|_, _| ()));
+            *i = Box::new(
+                move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
+                    let address = interpreter.contract.address;
+                    let Ok(slot) = interpreter.stack.peek(0) else {
+                        return old(interpreter, host);
+                    };
+                    let was_loaded =
+                        original_slot_value(&host.context.evm, address, slot).is_some();
+                    old(interpreter, host);
+                    if was_loaded {
+                        return;
+                    }
+                    if let Some(original) = original_slot_value(&host.context.evm, address, slot) {
+                        host.context.external.get_inspector().original_slot_loaded(
+                            &mut host.context.evm,
+                            address,
+                            slot,
+                            original,
+                        );
+                    }
+                },
+            )
+        }
+    }
+
+    // register sstore function.
+    let call_input_stack_inner = call_input_stack.clone();
+    if let Some(i) = table.get_mut(opcode::SSTORE as usize) {
+        let old = core::mem::replace(i, Box::new(|_, _| ()));
+        *i = Box::new(
+            move |interpreter: &mut Interpreter, host: &mut Evm<'a, EXT, DB>| {
+                let slot = interpreter.stack.peek(0).unwrap_or_default();
+                old(interpreter, host);
+                if interpreter.instruction_result != InstructionResult::Continue {
+                    return;
+                }
+                // current frame is the last call, if it is not a create frame.
+                let Some(context) = call_input_stack_inner
+                    .borrow()
                     .last()
+                    .map(|inputs| inputs.context.clone())
+                else {
+                    return;
+                };
+                if context.address == interpreter.contract.address
+                    && context.code_address != context.address
                 {
-                    host.context.external.get_inspector().selfdestruct(
-                        *address,
-                        *target,
-                        *had_balance,
-                    );
+                    host.context
+                        .external
+                        .get_inspector()
+                        .delegate_storage_write(
+                            &mut host.context.evm,
+                            context.address,
+                            context.code_address,
+                            slot,
+                        );
                 }
             },
         )
     }
 
+    // wrap every instruction with `step` and `step_end` calls last so that opcode specific
+    // calls are not made for skipped instructions.
+    let table = table
+        .into_iter()
+        .map(|i| inspector_instruction(i))
+        .collect::<Vec<_>>();
+
     // cast vector to array.
     handler.instruction_table = Some(EvmInstructionTables::Boxed(
         table.try_into().unwrap_or_else(|_| unreachable!()),
     ));
 
-    // call and create input stack shared between handlers. They are used to share
-    // inputs in *_end Inspector calls.
-    let call_input_stack = Rc::<RefCell<Vec<_>>>::new(RefCell::new(Vec::new()));
-    let create_input_stack = Rc::<RefCell<Vec<_>>>::new(RefCell::new(Vec::new()));
-
     // Create handle
     let create_input_stack_inner = create_input_stack.clone();
     let old_handle = handler.execution.create.clone();
-    handler.execution.create = Arc::new(move |ctx, mut inputs| -> FrameOrResult {
+    handler.execution.create = Arc::new(move |ctx, mut inputs, create_address| -> FrameOrResult {
+        let mem_cost = create_memory_cost.take();
         let inspector = ctx.external.get_inspector();
         // call inspector create to change input or return outcome.
         if let Some(outcome) = inspector.create(&mut ctx.evm, &mut inputs) {
@@ -142,11 +624,29 @@ pub fn inspector_handle_register<'a, DB: Database, EXT: GetInspector<DB>>(
             return FrameOrResult::Result(FrameResult::Create(outcome));
         }
         create_input_stack_inner.borrow_mut().push(inputs.clone());
+        inspector.create_gas(&mut ctx.evm, &inputs, inputs.gas_limit);
+        if let Some(mem_cost) = mem_cost {
+            inspector.create_memory_cost(&mut ctx.evm, &inputs, mem_cost);
+        }
+
+        let creator = inputs.caller;
+        let creator_nonce = account_nonce(&ctx.evm, creator);
 
-        let mut frame_or_result = old_handle(ctx, inputs);
+        let mut frame_or_result = old_handle(ctx, inputs, create_address);
 
         let inspector = ctx.external.get_inspector();
+        // creator nonce is bumped and created account nonce is initialized.
+        if let (Some(old), Some(new)) = (creator_nonce, account_nonce(&ctx.evm, creator)) {
+            if old != new {
+                inspector.nonce_changed(&mut ctx.evm, creator, old, new);
+            }
+        }
         if let FrameOrResult::Frame(frame) = &mut frame_or_result {
+            if let Some(created) = frame.created_address() {
+                if let Some(new) = account_nonce(&ctx.evm, created).filter(|&new| new != 0) {
+                    inspector.nonce_changed(&mut ctx.evm, created, 0, new);
+                }
+            }
             inspector.initialize_interp(&mut frame.frame_data_mut().interpreter, &mut ctx.evm)
         }
         frame_or_result
@@ -155,7 +655,8 @@ pub fn inspector_handle_register<'a, DB: Database, EXT: GetInspector<DB>>(
     // Call handler
     let call_input_stack_inner = call_input_stack.clone();
     let old_handle = handler.execution.call.clone();
-    handler.execution.call = Arc::new(move |ctx, mut inputs| -> FrameOrResult {
+    handler.execution.call = Arc::new(move |ctx, mut inputs, zero_address_call| -> FrameOrResult {
+        let call_gas = call_gas.take();
         let inspector = ctx.external.get_inspector();
         let _mems = inputs.return_memory_offset.clone();
         // call inspector callto change input or return outcome.
@@ -164,12 +665,51 @@ pub fn inspector_handle_register<'a, DB: Database, EXT: GetInspector<DB>>(
             return FrameOrResult::Result(FrameResult::Call(outcome));
         }
         call_input_stack_inner.borrow_mut().push(inputs.clone());
+        if let Some(call_gas) = call_gas {
+            if call_gas.requested > call_gas.granted {
+                inspector.call_gas_capped(
+                    &mut ctx.evm,
+                    &inputs,
+                    call_gas.requested,
+                    call_gas.granted,
+                );
+            }
+            inspector.call_cost(
+                &mut ctx.evm,
+                &inputs,
+                call_gas.charged,
+                call_gas.granted,
+                call_gas.stipend,
+            );
+        }
+        let transfer = inputs.transfer.clone();
 
-        let mut frame_or_result = old_handle(ctx, inputs);
+        let mut frame_or_result = old_handle(ctx, inputs, zero_address_call);
 
         let inspector = ctx.external.get_inspector();
-        if let FrameOrResult::Frame(frame) = &mut frame_or_result {
-            inspector.initialize_interp(&mut frame.frame_data_mut().interpreter, &mut ctx.evm)
+        match &mut frame_or_result {
+            FrameOrResult::Frame(frame) => {
+                inspector.initialize_interp(&mut frame.frame_data_mut().interpreter, &mut ctx.evm)
+            }
+            FrameOrResult::Result(FrameResult::Call(outcome))
+                if outcome.result.result == InstructionResult::OutOfFunds =>
+            {
+                // source account is loaded by the failed transfer.
+                let available = ctx
+                    .evm
+                    .journaled_state
+                    .state
+                    .get(&transfer.source)
+                    .map(|acc| acc.info.balance)
+                    .unwrap_or_default();
+                inspector.insufficient_balance(
+                    &mut ctx.evm,
+                    transfer.source,
+                    transfer.value,
+                    available,
+                );
+            }
+            _ => {}
         }
         frame_or_result
     });
@@ -195,6 +735,98 @@ pub fn inspector_handle_register<'a, DB: Database, EXT: GetInspector<DB>>(
         old_handle(ctx, frame, outcome)
     });
 
+    // deduct caller, nonce of the caller is bumped for calls.
+    let old_handle = handler.pre_execution.deduct_caller.clone();
+    handler.pre_execution.deduct_caller = Arc::new(move |ctx| {
+        let caller = ctx.evm.env.tx.caller;
+        let old = ctx
+            .evm
+            .journaled_state
+            .load_account(caller, &mut ctx.evm.db)
+            .ok()
+            .map(|(account, _)| account.info.nonce);
+        old_handle(ctx)?;
+        if let (Some(old), Some(new)) = (old, account_nonce(&ctx.evm, caller)) {
+            if old != new {
+                ctx.external
+                    .get_inspector()
+                    .nonce_changed(&mut ctx.evm, caller, old, new);
+            }
+        }
+        Ok(())
+    });
+
+    // load accounts
+    let old_handle = handler.pre_execution.load_accounts.clone();
+    handler.pre_execution.load_accounts = Arc::new(move |ctx| {
+        old_handle(ctx)?;
+        // slots of the access list are loaded before the execution.
+        let access_list = ctx.evm.env.tx.access_list.clone();
+        let mut reported = HashSet::new();
+        for (address, slots) in access_list {
+            for slot in slots {
+                if !reported.insert((address, slot)) {
+                    continue;
+                }
+                if let Some(original) = original_slot_value(&ctx.evm, address, slot) {
+                    ctx.external.get_inspector().original_slot_loaded(
+                        &mut ctx.evm,
+                        address,
+                        slot,
+                        original,
+                    );
+                }
+            }
+        }
+        Ok(())
+    });
+
+    // blob hash validation
+    let old_handle = handler.validation.blob_hash.clone();
+    handler.validation.blob_hash = Arc::new(move |ctx, hash| {
+        let valid = old_handle(ctx, hash);
+        ctx.external
+            .get_inspector()
+            .blob_hash_checked(&mut ctx.evm, hash, valid);
+        valid
+    });
+
+    // caller nonce and balance checks
+    let old_handle = handler.validation.caller_check.clone();
+    handler.validation.caller_check = Arc::new(move |ctx, check| {
+        old_handle(ctx, check);
+        let inspector = ctx.external.get_inspector();
+        match check {
+            CallerCheck::Nonce {
+                tx_nonce,
+                account_nonce,
+            } => inspector.nonce_check(&mut ctx.evm, tx_nonce, account_nonce),
+            CallerCheck::Balance {
+                required,
+                available,
+            } => inspector.balance_check(&mut ctx.evm, required, available),
+        }
+    });
+
+    // caller reimbursement
+    let old_handle = handler.post_execution.reimburse_caller.clone();
+    handler.post_execution.reimburse_caller = Arc::new(move |ctx, gas| {
+        let caller = ctx.evm.env.tx.caller;
+        let balance = |state: &State| {
+            state
+                .get(&caller)
+                .map(|account| account.info.balance)
+                .unwrap_or_default()
+        };
+        let balance_before = balance(&ctx.evm.journaled_state.state);
+        old_handle(ctx, gas)?;
+        let amount = balance(&ctx.evm.journaled_state.state).saturating_sub(balance_before);
+        ctx.external
+            .get_inspector()
+            .gas_reimbursed(&mut ctx.evm, caller, amount);
+        Ok(())
+    });
+
     // last frame outcome
     let old_handle = handler.execution.last_frame_return.clone();
     handler.execution.last_frame_return = Arc::new(move |ctx, frame_result| {
@@ -214,6 +846,47 @@ pub fn inspector_handle_register<'a, DB: Database, EXT: GetInspector<DB>>(
     });
 }
 
+/// Returns original value of the storage slot if the slot is loaded.
+fn original_slot_value<DB: Database>(
+    context: &EvmContext<DB>,
+    address: Address,
+    slot: U256,
+) -> Option<U256> {
+    context
+        .journaled_state
+        .state
+        .get(&address)?
+        .storage
+        .get(&slot)
+        .map(|slot| slot.original_value())
+}
+
+/// Returns nonce of the account if the account is loaded.
+fn account_nonce<DB: Database>(context: &EvmContext<DB>, address: Address) -> Option<u64> {
+    context
+        .journaled_state
+        .state
+        .get(&address)
+        .map(|account| account.info.nonce)
+}
+
+/// Returns offset and length of memory written by the `opcode`, read from the stack before
+/// the opcode is executed.
+///
+/// Returns `None` if the opcode does not write memory or the stack is invalid.
+fn memory_write_range(opcode: u8, stack: &Stack) -> Option<(usize, usize)> {
+    let (offset, len) = match opcode {
+        opcode::MSTORE => (stack.peek(0).ok()?, U256::from(32)),
+        opcode::MSTORE8 => (stack.peek(0).ok()?, U256::from(1)),
+        opcode::CALLDATACOPY | opcode::CODECOPY | opcode::RETURNDATACOPY | opcode::MCOPY => {
+            (stack.peek(0).ok()?, stack.peek(2).ok()?)
+        }
+        opcode::EXTCODECOPY => (stack.peek(1).ok()?, stack.peek(3).ok()?),
+        _ => return None,
+    };
+    Some((offset.try_into().ok()?, len.try_into().ok()?))
+}
+
 /// Outer closure that calls Inspector for every instruction.
 pub fn inspector_instruction<
     'a,
@@ -229,37 +902,138 @@ pub fn inspector_instruction<
             // old Inspector behavior.
             interpreter.instruction_pointer = unsafe { interpreter.instruction_pointer.sub(1) };
 
-            host.context
+            let action = host
+                .context
                 .external
                 .get_inspector()
                 .step(interpreter, &mut host.context.evm);
+            match action {
+                StepAction::Continue => {}
+                StepAction::Skip => {
+                    // skip the opcode and push data if there is any.
+                    let opcode = interpreter.current_opcode();
+                    let immediate = if (opcode::PUSH1..=opcode::PUSH32).contains(&opcode) {
+                        (opcode - opcode::PUSH0) as usize
+                    } else {
+                        0
+                    };
+                    // SAFETY: legacy bytecode is padded so push data is always in bounds.
+                    interpreter.instruction_pointer =
+                        unsafe { interpreter.instruction_pointer.add(1 + immediate) };
+                    return;
+                }
+                StepAction::Halt(result) => {
+                    interpreter.instruction_result = result;
+                    return;
+                }
+            }
             if interpreter.instruction_result != InstructionResult::Continue {
                 return;
             }
 
+            let opcode = interpreter.current_opcode();
             // return PC to old value
             interpreter.instruction_pointer = unsafe { interpreter.instruction_pointer.add(1) };
 
+            let gas_remaining = interpreter.gas.remaining();
+            let gas_refunded = interpreter.gas.refunded();
+
             // execute instruction.
             instruction(interpreter, host);
 
-            host.context
+            if let Some(max_gas) = host
+                .context
                 .external
                 .get_inspector()
-                .step_end(interpreter, &mut host.context.evm);
+                .max_single_opcode_gas()
+            {
+                let cost = gas_remaining
+                    .saturating_sub(interpreter.gas.remaining())
+                    .saturating_sub(forwarded_gas(
+                        &host.context.evm.env,
+                        &interpreter.next_action,
+                    ));
+                if cost > max_gas {
+                    let halt = host.context.external.get_inspector().gas_bomb(
+                        interpreter,
+                        &mut host.context.evm,
+                        opcode,
+                        cost,
+                    );
+                    let result = interpreter.instruction_result;
+                    if halt
+                        && (result == InstructionResult::Continue
+                            || result == InstructionResult::CallOrCreate)
+                    {
+                        // drop the sub call or create that the opcode started.
+                        interpreter.next_action = InterpreterAction::None;
+                        interpreter.instruction_result = InstructionResult::GasBomb;
+                    }
+                }
+            }
+
+            // halt consumes all gas of the frame.
+            let result = interpreter.instruction_result;
+            let spent = if result.is_error() && !returns_halt_gas(&host.context.evm.env, result) {
+                gas_remaining
+            } else {
+                gas_remaining.saturating_sub(interpreter.gas.remaining())
+            };
+            let inspector = host.context.external.get_inspector();
+            inspector.gas_change(
+                interpreter,
+                spent,
+                interpreter.gas.refunded() - gas_refunded,
+            );
+            inspector.step_end(interpreter, &mut host.context.evm);
         },
     )
 }
 
+/// Returns whether the SELFDESTRUCT `target` account existed before it received `received`
+/// balance, with the same rules as [`JournaledState::load_account_exist`](crate::JournaledState::load_account_exist).
+fn selfdestruct_target_exists(
+    spec_id: SpecId,
+    account: &Account,
+    received: U256,
+    was_touched: bool,
+) -> bool {
+    if SpecId::enabled(spec_id, SpecId::SPURIOUS_DRAGON) {
+        let code_empty = account.info.is_empty_code_hash() || account.info.code_hash == B256::ZERO;
+        !code_empty || account.info.balance != received || account.info.nonce != 0
+    } else {
+        !account.is_loaded_as_not_existing() || was_touched
+    }
+}
+
+/// Returns gas that the opcode took from the frame and forwarded to the sub call or create
+/// it started, without the call stipend.
+fn forwarded_gas(env: &Env, action: &InterpreterAction) -> u64 {
+    match action {
+        InterpreterAction::Call { inputs } => {
+            // stipend is added only to calls that transfer value.
+            let stipend = if inputs.transfer.value == U256::ZERO {
+                0
+            } else {
+                env.cfg.call_stipend.unwrap_or(gas::CALL_STIPEND)
+            };
+            inputs.gas_limit - stipend
+        }
+        InterpreterAction::Create { inputs } => inputs.gas_limit,
+        _ => 0,
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::test_utils::{contract_info, db_with_contract, evm_calling};
     use crate::{
         db::EmptyDB,
         inspectors::NoOpInspector,
         interpreter::{opcode::*, CallInputs, CreateInputs, Interpreter},
-        primitives::BerlinSpec,
+        primitives::{Address, BerlinSpec, Log, B256, U256},
         Database, Evm, EvmContext, Inspector,
     };
 
@@ -294,8 +1068,9 @@ mod tests {
             self.initialize_interp_called = true;
         }
 
-        fn step(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        fn step(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<DB>) -> StepAction {
             self.step += 1;
+            StepAction::Continue
         }
 
         fn step_end(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
@@ -408,4 +1183,2113 @@ mod tests {
             .append_handler_register(inspector_handle_register)
             .build();
     }
+
+    #[derive(Default, Debug)]
+    struct ValidationInspector {
+        nonces: Vec<(u64, u64)>,
+        balances: Vec<(U256, U256)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for ValidationInspector {
+        fn nonce_check(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            tx_nonce: u64,
+            account_nonce: u64,
+        ) {
+            self.nonces.push((tx_nonce, account_nonce));
+        }
+
+        fn balance_check(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            required: U256,
+            available: U256,
+        ) {
+            self.balances.push((required, available));
+        }
+    }
+
+    #[test]
+    fn test_inspector_nonce_and_balance_check() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{address, AccountInfo, EVMError, InvalidTransaction, TransactTo},
+        };
+
+        let caller = address!("1000000000000000000000000000000000000000");
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            caller,
+            AccountInfo {
+                balance: U256::from(1_000_000),
+                nonce: 5,
+                ..Default::default()
+            },
+        );
+
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .with_external_context(ValidationInspector::default())
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = caller;
+                tx.transact_to = TransactTo::Call(Address::ZERO);
+                tx.gas_limit = 21_000;
+                tx.nonce = Some(3);
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert_eq!(
+            evm.transact().unwrap_err(),
+            EVMError::Transaction(InvalidTransaction::NonceTooLow { tx: 3, state: 5 })
+        );
+        assert_eq!(evm.context.external.nonces, vec![(3, 5)]);
+        assert!(evm.context.external.balances.is_empty());
+
+        // valid nonce reports the balance check.
+        evm.tx_mut().nonce = Some(5);
+        evm.tx_mut().gas_price = U256::from(2);
+        assert!(evm.transact().is_ok());
+        assert_eq!(evm.context.external.nonces, vec![(3, 5), (5, 5)]);
+        assert_eq!(
+            evm.context.external.balances,
+            vec![(U256::from(42_000), U256::from(1_000_000))]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct DelegateStorageInspector {
+        writes: Vec<(Address, Address, U256)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for DelegateStorageInspector {
+        fn delegate_storage_write(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            storage_address: Address,
+            code_address: Address,
+            slot: U256,
+        ) {
+            self.writes.push((storage_address, code_address, slot));
+        }
+    }
+
+    #[test]
+    fn test_inspector_delegate_storage_write() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{address, Bytecode},
+        };
+
+        let proxy = address!("2000000000000000000000000000000000000000");
+        let implementation = address!("00000000000000000000000000000000000000a1");
+
+        // stores to slot 7 and delegatecalls implementation.
+        let proxy_code = vec![
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x07,
+            opcode::SSTORE,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0xa1,
+            opcode::GAS,
+            opcode::DELEGATECALL,
+            opcode::STOP,
+        ];
+        // stores to slot 5.
+        let implementation_code = vec![
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x05,
+            opcode::SSTORE,
+            opcode::STOP,
+        ];
+
+        let mut db = InMemoryDB::default();
+        for (address, code) in [(proxy, proxy_code), (implementation, implementation_code)] {
+            let code = Bytecode::new_raw(code.into());
+            db.insert_account_info(address, contract_info(code));
+        }
+
+        let mut evm = evm_calling(db, proxy)
+            .with_external_context(DelegateStorageInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(
+            evm.context.external.writes,
+            vec![(proxy, implementation, U256::from(5))]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct LogGasInspector {
+        logs: Vec<(usize, usize, u64)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for LogGasInspector {
+        fn log_gas(
+            &mut self,
+            _interp: &mut Interpreter,
+            _context: &mut EvmContext<DB>,
+            topics: usize,
+            data_len: usize,
+            charged: u64,
+        ) {
+            self.logs.push((topics, data_len, charged));
+        }
+    }
+
+    #[test]
+    fn test_inspector_log_gas() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::gas,
+            primitives::{Bytecode, Bytes},
+        };
+
+        // LOG2 with 10 bytes of data.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::PUSH1,
+            0x02,
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x0a,
+            opcode::PUSH1,
+            0x00,
+            opcode::LOG2,
+            opcode::STOP,
+        ]));
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(LogGasInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+
+        let charged = gas::LOG + 2 * gas::LOGTOPIC + 10 * gas::LOGDATA;
+        assert_eq!(evm.context.external.logs, vec![(2, 10, charged)]);
+    }
+
+    #[derive(Default, Debug)]
+    struct CreateGasInspector {
+        gas_before_create: u64,
+        forwarded: Vec<u64>,
+    }
+
+    impl<DB: Database> Inspector<DB> for CreateGasInspector {
+        fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) -> StepAction {
+            if interp.current_opcode() == opcode::CREATE {
+                self.gas_before_create = interp.gas.remaining();
+            }
+            StepAction::Continue
+        }
+
+        fn create_gas(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            _inputs: &CreateInputs,
+            forwarded: u64,
+        ) {
+            self.forwarded.push(forwarded);
+        }
+    }
+
+    #[test]
+    fn test_inspector_create_gas() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::gas,
+            primitives::{Bytecode, Bytes},
+        };
+
+        // CREATE with empty init code.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::CREATE,
+            opcode::STOP,
+        ]));
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(CreateGasInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+
+        let inspector = &evm.context.external;
+        let available = inspector.gas_before_create - gas::CREATE;
+        assert_eq!(inspector.forwarded, vec![available - available / 64]);
+    }
+
+    #[derive(Default, Debug)]
+    struct EnvOverrideInspector {
+        timestamp: Option<U256>,
+        reads: Vec<(u8, U256)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for EnvOverrideInspector {
+        fn env_read(
+            &mut self,
+            _interp: &mut Interpreter,
+            _context: &mut EvmContext<DB>,
+            opcode: u8,
+            default_value: U256,
+        ) -> Option<U256> {
+            self.reads.push((opcode, default_value));
+            if opcode == opcode::TIMESTAMP {
+                self.timestamp
+            } else {
+                None
+            }
+        }
+    }
+
+    #[test]
+    fn test_inspector_env_read() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes},
+        };
+
+        // stores TIMESTAMP to slot 0 and NUMBER to slot 1.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::TIMESTAMP,
+            opcode::PUSH1,
+            0x00,
+            opcode::SSTORE,
+            opcode::NUMBER,
+            opcode::PUSH1,
+            0x01,
+            opcode::SSTORE,
+            opcode::STOP,
+        ]));
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(EnvOverrideInspector {
+                timestamp: Some(U256::from(1234)),
+                ..Default::default()
+            })
+            .modify_block_env(|block| {
+                block.timestamp = U256::from(10);
+                block.number = U256::from(20);
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let state = evm.transact().unwrap().state;
+        let storage = &state[&Address::ZERO].storage;
+        assert_eq!(storage[&U256::ZERO].present_value, U256::from(1234));
+        assert_eq!(storage[&U256::from(1)].present_value, U256::from(20));
+        assert_eq!(
+            evm.context.external.reads,
+            vec![
+                (opcode::TIMESTAMP, U256::from(10)),
+                (opcode::NUMBER, U256::from(20))
+            ]
+        );
+        assert_eq!(evm.block().timestamp, U256::from(10));
+
+        // block timestamp is read without the override.
+        evm.context.external.timestamp = None;
+        let state = evm.transact().unwrap().state;
+        assert_eq!(
+            state[&Address::ZERO].storage[&U256::ZERO].present_value,
+            U256::from(10)
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct GasPriceOverrideInspector;
+
+    impl<DB: Database> Inspector<DB> for GasPriceOverrideInspector {
+        fn env_read(
+            &mut self,
+            _interp: &mut Interpreter,
+            _context: &mut EvmContext<DB>,
+            opcode: u8,
+            _default_value: U256,
+        ) -> Option<U256> {
+            (opcode == opcode::GASPRICE).then(|| U256::from(1234))
+        }
+    }
+
+    #[test]
+    fn test_inspector_gas_price_override() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes, ResultAndState, TransactTo},
+        };
+
+        // stores GASPRICE to slot 0.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::GASPRICE,
+            opcode::PUSH1,
+            0x00,
+            opcode::SSTORE,
+            opcode::STOP,
+        ]));
+
+        // account with balance in benchmark db.
+        let caller = Address::with_last_byte(1);
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .with_external_context(GasPriceOverrideInspector)
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = caller;
+                tx.transact_to = TransactTo::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+                tx.gas_price = U256::from(10);
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let ResultAndState { result, state, .. } = evm.transact().unwrap();
+        assert!(result.is_success());
+        assert_eq!(
+            state[&Address::ZERO].storage[&U256::ZERO].present_value,
+            U256::from(1234)
+        );
+        // caller is billed with the real gas price.
+        assert_eq!(
+            state[&caller].info.balance,
+            U256::from(10_000_000 - result.gas_used() * 10)
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct GasReimbursedInspector {
+        reimbursed: Vec<(Address, U256)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for GasReimbursedInspector {
+        fn gas_reimbursed(&mut self, _context: &mut EvmContext<DB>, sender: Address, amount: U256) {
+            self.reimbursed.push((sender, amount));
+        }
+    }
+
+    #[test]
+    fn test_inspector_gas_reimbursed() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes, ExecutionResult, TransactTo},
+        };
+
+        // sets and clears slot 0 to get a refund.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x00,
+            opcode::SSTORE,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::SSTORE,
+            opcode::STOP,
+        ]));
+
+        // account with balance in benchmark db.
+        let caller = Address::with_last_byte(1);
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .with_external_context(GasReimbursedInspector::default())
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = caller;
+                tx.transact_to = TransactTo::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+                tx.gas_price = U256::from(10);
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        let ExecutionResult::Success {
+            gas_used,
+            gas_refunded,
+            ..
+        } = result
+        else {
+            panic!("transaction failed: {result:?}");
+        };
+        assert!(gas_refunded > 0);
+
+        // remaining gas and refund are reimbursed.
+        let amount = U256::from((100_000 - gas_used) * 10);
+        assert_eq!(evm.context.external.reimbursed, vec![(caller, amount)]);
+    }
+
+    #[derive(Default, Debug)]
+    struct MemoryChangeInspector {
+        changes: Vec<(usize, usize)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for MemoryChangeInspector {
+        fn memory_change(&mut self, _interp: &Interpreter, offset: usize, len: usize) {
+            self.changes.push((offset, len));
+        }
+    }
+
+    #[test]
+    fn test_inspector_memory_change() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes},
+        };
+
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            // writes word at 0x00.
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x00,
+            opcode::MSTORE,
+            // expands memory to 0x60 without writing.
+            opcode::PUSH1,
+            0x40,
+            opcode::MLOAD,
+            opcode::POP,
+            // writes byte at 0x00.
+            opcode::PUSH1,
+            0x02,
+            opcode::PUSH1,
+            0x00,
+            opcode::MSTORE8,
+            // does not change memory.
+            opcode::PUSH1,
+            0x00,
+            opcode::MLOAD,
+            opcode::POP,
+            opcode::STOP,
+        ]));
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(MemoryChangeInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(
+            evm.context.external.changes,
+            vec![(0x00, 0x20), (0x20, 0x40), (0x00, 0x01)]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct CallGasCappedInspector {
+        capped: Vec<(u64, u64, u64)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for CallGasCappedInspector {
+        fn call_gas_capped(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            inputs: &CallInputs,
+            requested: u64,
+            granted: u64,
+        ) {
+            self.capped.push((requested, granted, inputs.gas_limit));
+        }
+    }
+
+    #[test]
+    fn test_inspector_call_gas_capped() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes},
+        };
+
+        let call = |gas: &[u8]| {
+            let mut code = vec![
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0xff,
+                opcode::PUSH1 + gas.len() as u8 - 1,
+            ];
+            code.extend_from_slice(gas);
+            code.extend_from_slice(&[opcode::CALL, opcode::POP]);
+            code
+        };
+        // first call requests 100 gas, second one requests more than available.
+        let mut code = call(&[100]);
+        code.extend(call(&[0xff, 0xff, 0xff, 0xff]));
+        code.push(opcode::STOP);
+
+        let mut evm = evm_calling(
+            BenchmarkDB::new_bytecode(Bytecode::new_raw(Bytes::from(code))),
+            Address::ZERO,
+        )
+        .with_external_context(CallGasCappedInspector::default())
+        .append_handler_register(inspector_handle_register)
+        .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+
+        let capped = &evm.context.external.capped;
+        assert_eq!(capped.len(), 1);
+        let (requested, granted, gas_limit) = capped[0];
+        assert_eq!(requested, 0xffff_ffff);
+        assert!(granted < 100_000);
+        assert_eq!(granted, gas_limit);
+    }
+
+    #[derive(Default, Debug)]
+    struct CallCostInspector {
+        costs: Vec<(u64, u64, u64, u64)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for CallCostInspector {
+        fn call_cost(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            inputs: &CallInputs,
+            charged: u64,
+            forwarded: u64,
+            stipend: u64,
+        ) {
+            self.costs
+                .push((charged, forwarded, stipend, inputs.gas_limit));
+        }
+    }
+
+    #[test]
+    fn test_inspector_call_cost() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{address, AccountInfo, Bytecode},
+        };
+
+        let parent = address!("2000000000000000000000000000000000000000");
+        // calls existing cold account 0xa1 with value 1 and 1000 gas.
+        let parent_code = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0xa1,
+                opcode::PUSH2,
+                0x03,
+                0xe8,
+                opcode::CALL,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+        let child_code = Bytecode::new_raw(vec![opcode::STOP].into());
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            parent,
+            AccountInfo {
+                balance: U256::from(1),
+                ..contract_info(parent_code)
+            },
+        );
+        db.insert_account_info(
+            address!("00000000000000000000000000000000000000a1"),
+            contract_info(child_code),
+        );
+
+        let mut evm = evm_calling(db, parent)
+            .with_external_context(CallCostInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+
+        // cold account access 2600 and value transfer 9000.
+        assert_eq!(
+            evm.context.external.costs,
+            vec![(
+                2600 + 9000,
+                1000,
+                gas::CALL_STIPEND,
+                1000 + gas::CALL_STIPEND
+            )]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct FrameGasInspector {
+        /// Depth, spent, forwarded and returned gas of finished call frames.
+        frames: Vec<(u64, u64, u64, u64)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for FrameGasInspector {
+        fn call_end(
+            &mut self,
+            context: &mut EvmContext<DB>,
+            _inputs: &CallInputs,
+            outcome: CallOutcome,
+        ) -> CallOutcome {
+            self.frames.push((
+                context.journaled_state.depth(),
+                outcome.result.gas.spend(),
+                outcome.gas_forwarded,
+                outcome.gas_returned,
+            ));
+            outcome
+        }
+    }
+
+    #[test]
+    fn test_inspector_call_frame_gas() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{address, AccountInfo, Bytecode},
+        };
+
+        let parent = address!("2000000000000000000000000000000000000000");
+        let call = |target: u8, value: u8, gas: &[u8]| {
+            let mut code = vec![
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                value,
+                opcode::PUSH1,
+                target,
+            ];
+            code.extend_from_slice(gas);
+            code.extend_from_slice(&[opcode::CALL, opcode::POP]);
+            code
+        };
+        // calls 0xa1 with value and zero gas, so it runs on the stipend only,
+        // then calls 0xa2 with all but one 64th of the gas.
+        let mut parent_code = call(0xa1, 0x01, &[opcode::PUSH1, 0x00]);
+        parent_code.extend(call(0xa2, 0x00, &[opcode::GAS]));
+        parent_code.push(opcode::STOP);
+        // uses 12 gas.
+        let spender_code = vec![
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x00,
+            opcode::MSTORE,
+            opcode::STOP,
+        ];
+
+        let mut db = InMemoryDB::default();
+        for (address, code, balance) in [
+            (parent, parent_code, U256::from(1)),
+            (
+                address!("00000000000000000000000000000000000000a1"),
+                spender_code.clone(),
+                U256::ZERO,
+            ),
+            (
+                address!("00000000000000000000000000000000000000a2"),
+                spender_code,
+                U256::ZERO,
+            ),
+        ] {
+            let code = Bytecode::new_raw(code.into());
+            db.insert_account_info(
+                address,
+                AccountInfo {
+                    balance,
+                    ..contract_info(code)
+                },
+            );
+        }
+
+        let mut evm = evm_calling(db, parent)
+            .with_external_context(FrameGasInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+
+        let frames = &evm.context.external.frames;
+        assert_eq!(frames.len(), 3);
+        // sub-calls do not forward gas, first one spends 12 gas of its stipend.
+        assert_eq!(frames[0], (1, 12, 0, 0));
+        assert_eq!(frames[1], (1, 12, 0, 0));
+
+        let (depth, spent, forwarded, returned) = frames[2];
+        assert_eq!(depth, 0);
+        assert_eq!(spent + 21_000, result.gas_used());
+        // stipend is not forwarded but its unused part is returned.
+        assert_eq!(returned - forwarded, gas::CALL_STIPEND - 12 - 12);
+        // pushes, pops and GAS 45, cold accesses 2 * 2600 and value transfer 9000.
+        assert_eq!(spent + returned - forwarded, 45 + 2 * 2600 + 9000);
+
+        // gas spent by frames themselves adds up to the transaction gas plus the stipend.
+        let self_gas = frames
+            .iter()
+            .map(|(_, spent, forwarded, returned)| spent + returned - forwarded)
+            .sum::<u64>();
+        assert_eq!(self_gas, spent + gas::CALL_STIPEND);
+    }
+
+    #[derive(Default, Debug)]
+    struct NonceInspector {
+        changes: Vec<(Address, u64, u64)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for NonceInspector {
+        fn nonce_changed(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            address: Address,
+            old: u64,
+            new: u64,
+        ) {
+            self.changes.push((address, old, new));
+        }
+    }
+
+    #[test]
+    fn test_inspector_nonce_changed() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{address, AccountInfo, Bytecode, TransactTo},
+        };
+
+        let caller = address!("1000000000000000000000000000000000000000");
+        let creator = address!("2000000000000000000000000000000000000000");
+        // creates two empty contracts.
+        let code = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::CREATE,
+                opcode::POP,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::CREATE,
+                opcode::POP,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            creator,
+            AccountInfo {
+                nonce: 1,
+                ..contract_info(code)
+            },
+        );
+
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .with_external_context(NonceInspector::default())
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = caller;
+                tx.transact_to = TransactTo::Call(creator);
+                tx.gas_limit = 200_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(
+            evm.context.external.changes,
+            vec![
+                (caller, 0, 1),
+                (creator, 1, 2),
+                (creator.create(1), 0, 1),
+                (creator, 2, 3),
+                (creator.create(2), 0, 1),
+            ]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct TransientStorageInspector {
+        accesses: Vec<(&'static str, U256, U256)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for TransientStorageInspector {
+        fn tstore(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            _address: Address,
+            key: U256,
+            value: U256,
+        ) {
+            self.accesses.push(("tstore", key, value));
+        }
+
+        fn tload(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            _address: Address,
+            key: U256,
+            value: U256,
+        ) {
+            self.accesses.push(("tload", key, value));
+        }
+    }
+
+    #[test]
+    fn test_inspector_transient_storage() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes},
+        };
+
+        // writes 7 to transient slot 1 and reads it back.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::PUSH1,
+            0x07,
+            opcode::PUSH1,
+            0x01,
+            opcode::TSTORE,
+            opcode::PUSH1,
+            0x01,
+            opcode::TLOAD,
+            opcode::POP,
+            opcode::STOP,
+        ]));
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(TransientStorageInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(
+            evm.context.external.accesses,
+            vec![
+                ("tstore", U256::from(1), U256::from(7)),
+                ("tload", U256::from(1), U256::from(7)),
+            ]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct DeployedCodeInspector {
+        deployed: Vec<(InstructionResult, Option<crate::primitives::Bytes>)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for DeployedCodeInspector {
+        fn create_end(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            _inputs: &CreateInputs,
+            outcome: CreateOutcome,
+        ) -> CreateOutcome {
+            self.deployed.push((
+                *outcome.instruction_result(),
+                outcome.deployed_code().cloned(),
+            ));
+            outcome
+        }
+    }
+
+    #[test]
+    fn test_inspector_create_deployed_code() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{address, Bytes, TransactTo},
+        };
+
+        // returns one byte of code.
+        let init_code = |code: u8| {
+            Bytes::from(vec![
+                opcode::PUSH1,
+                code,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE8,
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ])
+        };
+
+        let mut evm = Evm::builder()
+            .with_db(InMemoryDB::default())
+            .with_external_context(DeployedCodeInspector::default())
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TransactTo::create();
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        evm.tx_mut().data = init_code(opcode::STOP);
+        assert!(evm.transact().unwrap().result.is_success());
+        // EIP-3541 rejects code starting with 0xEF.
+        evm.tx_mut().data = init_code(0xEF);
+        assert!(!evm.transact().unwrap().result.is_success());
+        // reverted create does not deploy the returned data.
+        evm.tx_mut().data = Bytes::from(vec![
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x00,
+            opcode::REVERT,
+        ]);
+        assert!(!evm.transact().unwrap().result.is_success());
+
+        assert_eq!(
+            evm.context.external.deployed,
+            vec![
+                (
+                    InstructionResult::Return,
+                    Some(Bytes::from(vec![opcode::STOP]))
+                ),
+                (InstructionResult::CreateContractStartingWithEF, None),
+                (InstructionResult::Revert, None),
+            ]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct CalldataReadInspector {
+        offsets: Vec<usize>,
+    }
+
+    impl<DB: Database> Inspector<DB> for CalldataReadInspector {
+        fn calldata_oob_read(
+            &mut self,
+            _interp: &mut Interpreter,
+            _context: &mut EvmContext<DB>,
+            offset: usize,
+        ) {
+            self.offsets.push(offset);
+        }
+    }
+
+    #[test]
+    fn test_inspector_calldata_oob_read() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes},
+        };
+
+        // loads word at 0, then returns word at 20 that is past the end of calldata.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::CALLDATALOAD,
+            opcode::POP,
+            opcode::PUSH1,
+            0x14,
+            opcode::CALLDATALOAD,
+            opcode::PUSH1,
+            0x00,
+            opcode::MSTORE,
+            opcode::PUSH1,
+            0x20,
+            opcode::PUSH1,
+            0x00,
+            opcode::RETURN,
+        ]));
+        let calldata = (1..=36).collect::<Vec<u8>>();
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(CalldataReadInspector::default())
+            .modify_tx_env(|tx| tx.data = calldata.clone().into())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        // bytes past the end are zero.
+        let mut expected = calldata[20..].to_vec();
+        expected.resize(32, 0);
+        assert_eq!(result.output().unwrap().as_ref(), expected.as_slice());
+        assert_eq!(evm.context.external.offsets, vec![20]);
+    }
+
+    #[derive(Default, Debug)]
+    struct ImmediateInspector {
+        steps: Vec<(u8, Vec<u8>)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for ImmediateInspector {
+        fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) -> StepAction {
+            self.steps
+                .push((interp.current_opcode(), interp.current_immediate().to_vec()));
+            StepAction::Continue
+        }
+    }
+
+    #[test]
+    fn test_inspector_current_immediate() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes},
+        };
+
+        // PUSH4 at the end of the code has only two bytes of immediate.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::PUSH2,
+            0x12,
+            0x34,
+            opcode::POP,
+            opcode::PUSH0,
+            opcode::POP,
+            opcode::PUSH4,
+            0xab,
+            0xcd,
+        ]));
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(ImmediateInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(
+            evm.context.external.steps,
+            vec![
+                (opcode::PUSH2, vec![0x12, 0x34]),
+                (opcode::POP, vec![]),
+                (opcode::PUSH0, vec![]),
+                (opcode::POP, vec![]),
+                (opcode::PUSH4, vec![0xab, 0xcd]),
+                // padding after the end of the code.
+                (opcode::STOP, vec![]),
+            ]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct PreRevertLogInspector {
+        logs: Vec<(Address, u64)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for PreRevertLogInspector {
+        fn log_pre_revert(&mut self, _context: &mut EvmContext<DB>, log: &Log, depth: u64) {
+            self.logs.push((log.address, depth));
+        }
+    }
+
+    #[test]
+    fn test_inspector_log_pre_revert() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{address, Bytecode},
+        };
+
+        let contract = address!("2000000000000000000000000000000000000000");
+        let reverting = address!("00000000000000000000000000000000000000a1");
+
+        // emits a log, calls the reverting contract and emits another log.
+        let contract_code = vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::LOG0,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0xa1,
+            opcode::GAS,
+            opcode::CALL,
+            opcode::POP,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::LOG0,
+            opcode::STOP,
+        ];
+        // emits a log and reverts.
+        let reverting_code = vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::LOG0,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::REVERT,
+        ];
+
+        let mut db = InMemoryDB::default();
+        for (address, code) in [(contract, contract_code), (reverting, reverting_code)] {
+            let code = Bytecode::new_raw(code.into());
+            db.insert_account_info(address, contract_info(code));
+        }
+
+        let mut evm = evm_calling(db, contract)
+            .with_external_context(PreRevertLogInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+        // log of the reverted call is reported but not included in the result.
+        assert_eq!(result.logs().len(), 2);
+        assert_eq!(
+            evm.context.external.logs,
+            vec![(contract, 0), (reverting, 1), (contract, 0)]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct ExpGasInspector {
+        exps: Vec<(usize, u64)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for ExpGasInspector {
+        fn exp_gas(
+            &mut self,
+            _interp: &mut Interpreter,
+            _context: &mut EvmContext<DB>,
+            exponent_byte_len: usize,
+            dynamic_cost: u64,
+        ) {
+            self.exps.push((exponent_byte_len, dynamic_cost));
+        }
+    }
+
+    #[test]
+    fn test_inspector_exp_gas() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes},
+        };
+
+        // 2 ** (1 << 255), then 2 ** 0x0100 and 2 ** 0.
+        let mut code = vec![opcode::PUSH32, 0x80];
+        code.extend([0; 31]);
+        code.extend([opcode::PUSH1, 0x02, opcode::EXP, opcode::POP]);
+        code.extend([opcode::PUSH2, 0x01, 0x00, opcode::PUSH1, 0x02, opcode::EXP]);
+        code.extend([opcode::POP, opcode::PUSH1, 0x00, opcode::PUSH1, 0x02]);
+        code.extend([opcode::EXP, opcode::STOP]);
+
+        let mut evm = evm_calling(
+            BenchmarkDB::new_bytecode(Bytecode::new_raw(Bytes::from(code))),
+            Address::ZERO,
+        )
+        .with_external_context(ExpGasInspector::default())
+        .append_handler_register(inspector_handle_register)
+        .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(
+            evm.context.external.exps,
+            vec![(32, 32 * 50), (2, 2 * 50), (0, 0)]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct GasBombInspector {
+        halt: bool,
+        bombs: Vec<(u8, u64)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for GasBombInspector {
+        fn max_single_opcode_gas(&self) -> Option<u64> {
+            Some(10_000)
+        }
+
+        fn gas_bomb(
+            &mut self,
+            _interp: &mut Interpreter,
+            _context: &mut EvmContext<DB>,
+            opcode: u8,
+            cost: u64,
+        ) -> bool {
+            self.bombs.push((opcode, cost));
+            self.halt
+        }
+    }
+
+    #[test]
+    fn test_inspector_gas_bomb() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::gas,
+            primitives::{Bytecode, Bytes, ExecutionResult, HaltReason},
+        };
+
+        // expands memory to 64KiB with a single MSTORE.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH3,
+            0x00,
+            0xff,
+            0xe0,
+            opcode::MSTORE,
+            opcode::STOP,
+        ]));
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(GasBombInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        // 2048 words of memory.
+        let memory_cost = gas::memory_gas(2048);
+        assert_eq!(
+            evm.context.external.bombs,
+            vec![(opcode::MSTORE, gas::VERYLOW + memory_cost)]
+        );
+
+        evm.context.external.halt = true;
+        let result = evm.transact().unwrap().result;
+        assert!(matches!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::GasBomb,
+                ..
+            }
+        ));
+        assert_eq!(evm.context.external.bombs.len(), 2);
+    }
+
+    #[derive(Default, Debug)]
+    struct SelfCallInspector {
+        self_calls: Vec<(Address, u64)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for SelfCallInspector {
+        fn self_call(&mut self, _context: &mut EvmContext<DB>, address: Address, depth: u64) {
+            self.self_calls.push((address, depth));
+        }
+    }
+
+    #[test]
+    fn test_inspector_self_call() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes},
+        };
+
+        // calls itself if calldata is empty, the nested call passes one byte of calldata.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::CALLDATASIZE,
+            opcode::PUSH1,
+            0x11,
+            opcode::JUMPI,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x00,
+            opcode::ADDRESS,
+            opcode::GAS,
+            opcode::STATICCALL,
+            opcode::POP,
+            opcode::STOP,
+            opcode::JUMPDEST,
+            opcode::STOP,
+        ]));
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(SelfCallInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(evm.context.external.self_calls, vec![(Address::ZERO, 1)]);
+    }
+
+    #[derive(Default, Debug)]
+    struct CreateMemoryCostInspector {
+        costs: Vec<u64>,
+    }
+
+    impl<DB: Database> Inspector<DB> for CreateMemoryCostInspector {
+        fn create_memory_cost(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            _inputs: &CreateInputs,
+            mem_cost: u64,
+        ) {
+            self.costs.push(mem_cost);
+        }
+    }
+
+    #[test]
+    fn test_inspector_create_memory_cost() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::gas,
+            primitives::{Bytecode, Bytes},
+        };
+
+        // CREATE with one byte of init code at offset 0x400.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH2,
+            0x04,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::CREATE,
+            opcode::STOP,
+        ]));
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(CreateMemoryCostInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+
+        // memory is expanded from zero to 33 words.
+        let expected = gas::memory_gas(33);
+        assert!(expected > 0);
+        assert_eq!(evm.context.external.costs, vec![expected]);
+    }
+
+    #[derive(Default, Debug)]
+    struct SelfdestructResultInspector {
+        results: Vec<(Address, Address, U256, bool)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for SelfdestructResultInspector {
+        fn selfdestruct_result(
+            &mut self,
+            contract: Address,
+            target: Address,
+            transferred: U256,
+            refund_granted: bool,
+        ) {
+            self.results
+                .push((contract, target, transferred, refund_granted));
+        }
+    }
+
+    #[test]
+    fn test_inspector_selfdestruct_result() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{address, AccountInfo, Bytecode, Bytes, SpecId},
+        };
+
+        let contract = address!("2000000000000000000000000000000000000000");
+        let target = address!("00000000000000000000000000000000000000aa");
+        let code = Bytecode::new_raw(Bytes::from(vec![opcode::PUSH1, 0xaa, opcode::SELFDESTRUCT]));
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            contract,
+            AccountInfo {
+                balance: U256::from(1000),
+                ..contract_info(code)
+            },
+        );
+
+        for (spec_id, refund_granted) in [(SpecId::BERLIN, true), (SpecId::LONDON, false)] {
+            let mut evm = evm_calling(db.clone(), contract)
+                .with_external_context(SelfdestructResultInspector::default())
+                .append_handler_register(inspector_handle_register)
+                .with_spec_id(spec_id)
+                .build();
+
+            assert!(evm.transact().unwrap().result.is_success());
+            assert_eq!(
+                evm.context.external.results,
+                vec![(contract, target, U256::from(1000), refund_granted)]
+            );
+        }
+    }
+
+    #[derive(Default, Debug)]
+    struct SelfdestructDetailInspector {
+        details: Vec<(U256, bool, bool)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for SelfdestructDetailInspector {
+        fn selfdestruct_detail(
+            &mut self,
+            _contract: Address,
+            _target: Address,
+            value: U256,
+            was_destroyed: bool,
+            target_existed: bool,
+        ) {
+            self.details.push((value, was_destroyed, target_existed));
+        }
+    }
+
+    #[derive(Default, Debug)]
+    struct LegacySelfdestructInspector {
+        destroyed: Vec<(Address, Address, U256)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for LegacySelfdestructInspector {
+        fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+            self.destroyed.push((contract, target, value));
+        }
+    }
+
+    #[test]
+    fn test_inspector_selfdestruct_detail() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{address, AccountInfo, Bytecode, Bytes, SpecId},
+        };
+
+        let contract = address!("2000000000000000000000000000000000000000");
+        let target = address!("00000000000000000000000000000000000000aa");
+        let code = Bytecode::new_raw(Bytes::from(vec![opcode::PUSH1, 0xaa, opcode::SELFDESTRUCT]));
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            contract,
+            AccountInfo {
+                balance: U256::from(1000),
+                ..contract_info(code)
+            },
+        );
+        let mut funded_target_db = db.clone();
+        funded_target_db.insert_account_info(target, AccountInfo::from_balance(U256::from(1)));
+
+        let build =
+            |db: InMemoryDB, spec_id: SpecId| evm_calling(db, contract).with_spec_id(spec_id);
+
+        // contract that is not created in the transaction is destroyed only before Cancun.
+        for (db, spec_id, detail) in [
+            (
+                db.clone(),
+                SpecId::SHANGHAI,
+                (U256::from(1000), true, false),
+            ),
+            (db.clone(), SpecId::CANCUN, (U256::from(1000), false, false)),
+            (
+                funded_target_db,
+                SpecId::CANCUN,
+                (U256::from(1000), false, true),
+            ),
+        ] {
+            let mut evm = build(db, spec_id)
+                .with_external_context(SelfdestructDetailInspector::default())
+                .append_handler_register(inspector_handle_register)
+                .build();
+            assert!(evm.transact().unwrap().result.is_success());
+            assert_eq!(evm.context.external.details, vec![detail]);
+        }
+
+        // `selfdestruct` is called only for destroyed contracts.
+        for (spec_id, destroyed) in [
+            (SpecId::SHANGHAI, vec![(contract, target, U256::from(1000))]),
+            (SpecId::CANCUN, vec![]),
+        ] {
+            let mut evm = build(db.clone(), spec_id)
+                .with_external_context(LegacySelfdestructInspector::default())
+                .append_handler_register(inspector_handle_register)
+                .build();
+            assert!(evm.transact().unwrap().result.is_success());
+            assert_eq!(evm.context.external.destroyed, destroyed);
+        }
+    }
+
+    #[derive(Default, Debug)]
+    struct SelfdestructGasInspector {
+        costs: Vec<(Address, u64, bool)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for SelfdestructGasInspector {
+        fn selfdestruct_gas(
+            &mut self,
+            _interp: &mut Interpreter,
+            _context: &mut EvmContext<DB>,
+            target: Address,
+            cost: u64,
+            is_cold: bool,
+        ) {
+            self.costs.push((target, cost, is_cold));
+        }
+    }
+
+    #[test]
+    fn test_inspector_selfdestruct_gas() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{address, AccountInfo, Bytecode, Bytes, SpecId},
+        };
+
+        let contract = address!("2000000000000000000000000000000000000000");
+        let target = address!("00000000000000000000000000000000000000aa");
+        let code = Bytecode::new_raw(Bytes::from(vec![opcode::PUSH1, 0xaa, opcode::SELFDESTRUCT]));
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            contract,
+            AccountInfo {
+                balance: U256::from(1000),
+                ..contract_info(code)
+            },
+        );
+
+        // target does not exist and receives balance, it is warm only if in the access list.
+        for (access_list, cost, is_cold) in [
+            (vec![], 5000 + 25000 + gas::COLD_ACCOUNT_ACCESS_COST, true),
+            (vec![(target, vec![])], 5000 + 25000, false),
+        ] {
+            let mut evm = evm_calling(db.clone(), contract)
+                .with_external_context(SelfdestructGasInspector::default())
+                .modify_tx_env(|tx| tx.access_list = access_list)
+                .append_handler_register(inspector_handle_register)
+                .with_spec_id(SpecId::BERLIN)
+                .build();
+
+            assert!(evm.transact().unwrap().result.is_success());
+            assert_eq!(evm.context.external.costs, vec![(target, cost, is_cold)]);
+        }
+    }
+
+    #[derive(Default, Debug)]
+    struct RevertSiteInspector {
+        sites: Vec<(Address, usize)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for RevertSiteInspector {
+        fn revert_site(
+            &mut self,
+            interp: &mut Interpreter,
+            _context: &mut EvmContext<DB>,
+            pc: usize,
+        ) {
+            self.sites.push((interp.contract.address, pc));
+        }
+    }
+
+    #[test]
+    fn test_inspector_revert_site() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes},
+        };
+
+        // reverts if calldata is not zero.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::CALLDATALOAD,
+            opcode::PUSH1,
+            0x07,
+            opcode::JUMPI,
+            opcode::STOP,
+            opcode::JUMPDEST,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::REVERT,
+        ]));
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(RevertSiteInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert!(evm.context.external.sites.is_empty());
+
+        evm.tx_mut().data = Bytes::from(vec![0x01; 32]);
+        assert!(!evm.transact().unwrap().result.is_success());
+        assert_eq!(evm.context.external.sites, vec![(Address::ZERO, 12)]);
+    }
+
+    #[derive(Default, Debug)]
+    struct McopyInspector {
+        copies: Vec<(U256, U256, U256)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for McopyInspector {
+        fn mcopy(
+            &mut self,
+            _interp: &mut Interpreter,
+            _context: &mut EvmContext<DB>,
+            dst: U256,
+            src: U256,
+            len: U256,
+        ) {
+            self.copies.push((dst, src, len));
+        }
+    }
+
+    #[test]
+    fn test_inspector_mcopy() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes},
+        };
+
+        // copies 64 bytes from offset 0x20 to offset 0x80.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::PUSH1,
+            0x40,
+            opcode::PUSH1,
+            0x20,
+            opcode::PUSH1,
+            0x80,
+            opcode::MCOPY,
+            opcode::STOP,
+        ]));
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(McopyInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(
+            evm.context.external.copies,
+            vec![(U256::from(0x80), U256::from(0x20), U256::from(0x40))]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct PatchInspector {
+        executed: Vec<u8>,
+        tstores: Vec<U256>,
+    }
+
+    impl<DB: Database> Inspector<DB> for PatchInspector {
+        fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) -> StepAction {
+            match interp.current_opcode() {
+                opcode::PUSH2 | opcode::INVALID | opcode::TSTORE => StepAction::Skip,
+                opcode::REVERT => StepAction::Halt(InstructionResult::Stop),
+                opcode => {
+                    self.executed.push(opcode);
+                    StepAction::Continue
+                }
+            }
+        }
+
+        fn tstore(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            _address: Address,
+            key: U256,
+            _value: U256,
+        ) {
+            self.tstores.push(key);
+        }
+    }
+
+    #[test]
+    fn test_inspector_step_action() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes},
+        };
+
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            // push data is skipped together with the opcode.
+            opcode::PUSH2,
+            opcode::PUSH0,
+            opcode::PUSH0,
+            opcode::INVALID,
+            // opcode specific calls are not made for skipped instructions.
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::TSTORE,
+            opcode::REVERT,
+        ]));
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(PatchInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(
+            evm.context.external.executed,
+            vec![opcode::PUSH1, opcode::PUSH1]
+        );
+        assert!(evm.context.external.tstores.is_empty());
+    }
+
+    #[derive(Default, Debug)]
+    struct CallGasInspector {
+        gas_before_call: u64,
+        call_gas_limits: Vec<u64>,
+    }
+
+    impl<DB: Database> Inspector<DB> for CallGasInspector {
+        fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) -> StepAction {
+            if interp.current_opcode() == opcode::CALL {
+                self.gas_before_call = interp.gas.remaining();
+            }
+            StepAction::Continue
+        }
+
+        fn call(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            inputs: &mut CallInputs,
+        ) -> Option<CallOutcome> {
+            self.call_gas_limits.push(inputs.gas_limit);
+            None
+        }
+    }
+
+    #[test]
+    fn test_inspector_call_gas_limit() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{address, Bytecode},
+        };
+
+        let parent = address!("2000000000000000000000000000000000000000");
+        let child = address!("00000000000000000000000000000000000000a1");
+        // calls child with all gas.
+        let parent_code = vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0xa1,
+            opcode::GAS,
+            opcode::CALL,
+            opcode::STOP,
+        ];
+        let mut db = InMemoryDB::default();
+        for (address, code) in [(parent, parent_code), (child, vec![opcode::STOP])] {
+            let code = Bytecode::new_raw(code.into());
+            db.insert_account_info(address, contract_info(code));
+        }
+
+        let mut evm = evm_calling(db, parent)
+            .with_external_context(CallGasInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+
+        let inspector = &evm.context.external;
+        // child is cold, so CALL costs 2600 before the gas is forwarded.
+        let left = inspector.gas_before_call - 2600;
+        assert_eq!(
+            inspector.call_gas_limits,
+            vec![100_000 - 21_000, left - left / 64]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct GasChangeInspector {
+        opcode: u8,
+        changes: Vec<(u8, u64, i64)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for GasChangeInspector {
+        fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) -> StepAction {
+            self.opcode = interp.current_opcode();
+            StepAction::Continue
+        }
+
+        fn gas_change(&mut self, _interp: &Interpreter, spent: u64, refunded_delta: i64) {
+            self.changes.push((self.opcode, spent, refunded_delta));
+        }
+    }
+
+    #[test]
+    fn test_inspector_gas_change() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{Bytecode, Bytes},
+        };
+
+        // sets slot 0 to 1 and resets it back to 0.
+        let bytecode = Bytecode::new_raw(Bytes::from(vec![
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x00,
+            opcode::SSTORE,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::SSTORE,
+            opcode::STOP,
+        ]));
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(GasChangeInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(
+            evm.context.external.changes,
+            vec![
+                (opcode::PUSH1, 3, 0),
+                (opcode::PUSH1, 3, 0),
+                // cold slot set from zero.
+                (opcode::SSTORE, 22_100, 0),
+                (opcode::PUSH1, 3, 0),
+                (opcode::PUSH1, 3, 0),
+                // slot restored to its original value.
+                (opcode::SSTORE, 100, 19_900),
+                (opcode::STOP, 0, 0),
+            ]
+        );
+
+        // halt consumes all remaining gas.
+        evm.context.external.changes.clear();
+        evm.context.evm.db = BenchmarkDB::new_bytecode(Bytecode::new_raw(Bytes::from(vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::INVALID,
+        ])));
+        assert!(evm.transact().unwrap().result.is_halt());
+        assert_eq!(
+            evm.context.external.changes,
+            vec![
+                (opcode::PUSH1, 3, 0),
+                (opcode::INVALID, 100_000 - 21_000 - 3, 0),
+            ]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct OriginalSlotInspector {
+        loaded: Vec<(Address, U256, U256)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for OriginalSlotInspector {
+        fn original_slot_loaded(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            address: Address,
+            slot: U256,
+            original: U256,
+        ) {
+            self.loaded.push((address, slot, original));
+        }
+    }
+
+    #[test]
+    fn test_inspector_original_slot_loaded() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{address, Bytecode},
+        };
+
+        let contract = address!("2000000000000000000000000000000000000000");
+        // writes slot 0 twice.
+        let code = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x07,
+                opcode::PUSH1,
+                0x00,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x08,
+                opcode::PUSH1,
+                0x00,
+                opcode::SSTORE,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(contract, contract_info(code));
+        db.insert_account_storage(contract, U256::ZERO, U256::from(5))
+            .unwrap();
+
+        let mut evm = evm_calling(db, contract)
+            .with_external_context(OriginalSlotInspector::default())
+            .modify_tx_env(|tx| {
+                tx.access_list = vec![(contract, vec![U256::from(1), U256::from(1)])]
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(
+            evm.context.external.loaded,
+            vec![
+                // access list slot is loaded before the execution.
+                (contract, U256::from(1), U256::ZERO),
+                (contract, U256::ZERO, U256::from(5)),
+            ]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct BalanceInspector {
+        insufficient: Vec<(Address, U256, U256)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for BalanceInspector {
+        fn insufficient_balance(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            from: Address,
+            needed: U256,
+            available: U256,
+        ) {
+            self.insufficient.push((from, needed, available));
+        }
+    }
+
+    #[test]
+    fn test_inspector_insufficient_balance() {
+        use crate::primitives::{address, Bytecode};
+
+        let contract = address!("2000000000000000000000000000000000000000");
+        // call 0xff with value 1 and return the call result.
+        let code = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0xff,
+                opcode::GAS,
+                opcode::CALL,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::RETURN,
+            ]
+            .into(),
+        );
+        let db = db_with_contract(contract, code);
+
+        let mut evm = evm_calling(db, contract)
+            .with_external_context(BalanceInspector::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        // transaction succeeds and call pushed 0.
+        assert!(result.is_success());
+        assert_eq!(result.output().unwrap().as_ref(), &[0u8; 32]);
+        assert_eq!(
+            evm.context.external.insufficient,
+            vec![(contract, U256::from(1), U256::ZERO)]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct BlobHashInspector {
+        checked: Vec<(B256, bool)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for BlobHashInspector {
+        fn blob_hash_checked(&mut self, _context: &mut EvmContext<DB>, hash: B256, valid: bool) {
+            self.checked.push((hash, valid));
+        }
+    }
+
+    #[test]
+    fn test_inspector_blob_hash_checked() {
+        use crate::primitives::{address, b256, EVMError, InvalidTransaction, TransactTo};
+
+        let invalid_hash =
+            b256!("0200000000000000000000000000000000000000000000000000000000000000");
+        let mut evm = Evm::builder()
+            .with_db(EmptyDB::default())
+            .with_external_context(BlobHashInspector::default())
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to =
+                    TransactTo::Call(address!("0000000000000000000000000000000000000000"));
+                tx.gas_limit = 21100;
+                tx.max_fee_per_blob_gas = Some(U256::from(1));
+                tx.blob_hashes = vec![invalid_hash];
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert_eq!(
+            evm.transact(),
+            Err(EVMError::Transaction(
+                InvalidTransaction::BlobVersionNotSupported
+            ))
+        );
+        assert_eq!(evm.context.external.checked, vec![(invalid_hash, false)]);
+    }
 }
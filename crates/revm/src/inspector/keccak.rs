@@ -0,0 +1,215 @@
+//! KeccakProfileInspector. Counts KECCAK256 invocations and hashed bytes.
+
+use revm_interpreter::CreateOutcome;
+
+use crate::{
+    interpreter::{opcode, CallInputs, CallOutcome, CreateInputs, InstructionResult, Interpreter},
+    primitives::{db::Database, CreateScheme},
+    EvmContext, Inspector, StepAction,
+};
+
+/// Totals of keccak256 hashing done in a transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct KeccakStats {
+    /// Number of keccak256 invocations.
+    pub count: u64,
+    /// Total number of bytes hashed.
+    pub bytes: u64,
+}
+
+impl KeccakStats {
+    /// Records one keccak256 invocation over `len` bytes.
+    #[inline]
+    pub fn record(&mut self, len: u64) {
+        self.count += 1;
+        self.bytes = self.bytes.saturating_add(len);
+    }
+}
+
+/// [Inspector] that counts KECCAK256 opcode invocations and bytes hashed.
+///
+/// Stats are reset at the start of every transaction.
+///
+/// By default only the KECCAK256 opcode is counted. If `include_internal` is set,
+/// hashing done by EVM itself is counted too: CREATE and CREATE2 address derivation,
+/// CREATE2 init code hashing and hashing of deployed bytecode.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KeccakProfileInspector {
+    /// Count keccak256 invocations done internally by EVM.
+    include_internal: bool,
+    /// Length of the hashed input of the currently executing KECCAK256 opcode.
+    pending_len: Option<u64>,
+    /// Accumulated stats.
+    stats: KeccakStats,
+}
+
+impl KeccakProfileInspector {
+    /// Creates new inspector that counts only KECCAK256 opcode.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates new inspector that also counts internal keccak256 invocations.
+    pub fn with_internal() -> Self {
+        Self {
+            include_internal: true,
+            ..Default::default()
+        }
+    }
+
+    /// Returns accumulated keccak256 stats.
+    pub fn keccak_stats(&self) -> KeccakStats {
+        self.stats
+    }
+}
+
+/// Returns length of RLP encoded `[address, nonce]` list used in CREATE address derivation.
+fn create_rlp_len(nonce: u64) -> u64 {
+    let nonce_len = if nonce < 0x80 {
+        1
+    } else {
+        1 + (8 - nonce.leading_zeros() as u64 / 8)
+    };
+    // list header + address header + address + nonce
+    1 + 1 + 20 + nonce_len
+}
+
+impl<DB: Database> Inspector<DB> for KeccakProfileInspector {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) -> StepAction {
+        self.pending_len = None;
+        if interp.current_opcode() == opcode::KECCAK256 {
+            if let Ok(len) = interp.stack.peek(1) {
+                self.pending_len = Some(len.saturating_to());
+            }
+        }
+        StepAction::Continue
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        // count only if opcode was executed successfully.
+        if let Some(len) = self.pending_len.take() {
+            if interp.instruction_result == InstructionResult::Continue {
+                self.stats.record(len);
+            }
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        if context.journaled_state.depth() == 0 {
+            self.stats = KeccakStats::default();
+        }
+        None
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        if context.journaled_state.depth() == 0 {
+            self.stats = KeccakStats::default();
+        }
+        if self.include_internal {
+            match inputs.scheme {
+                CreateScheme::Create => {
+                    let nonce = context
+                        .journaled_state
+                        .state
+                        .get(&inputs.caller)
+                        .map(|acc| acc.info.nonce)
+                        .unwrap_or_default();
+                    self.stats.record(create_rlp_len(nonce));
+                }
+                CreateScheme::Create2 { .. } => {
+                    // init code hash and `0xff ++ address ++ salt ++ init_code_hash`.
+                    self.stats.record(inputs.init_code.len() as u64);
+                    self.stats.record(1 + 20 + 32 + 32);
+                }
+            }
+        }
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        // deployed bytecode is hashed when it is set to the account.
+        if self.include_internal && outcome.result.is_ok() {
+            self.stats.record(outcome.result.output.len() as u64);
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        inspector::inspector_handle_register,
+        primitives::{address, Bytecode, TransactTo},
+        Evm,
+    };
+
+    #[test]
+    fn test_create_rlp_len() {
+        assert_eq!(create_rlp_len(0), 23);
+        assert_eq!(create_rlp_len(0x7f), 23);
+        assert_eq!(create_rlp_len(0x80), 24);
+        assert_eq!(create_rlp_len(0x1_0000), 26);
+    }
+
+    #[test]
+    fn test_keccak_profile_inspector() {
+        let bytecode = Bytecode::new_raw(
+            vec![
+                // keccak256 of 32 bytes
+                opcode::PUSH1,
+                0x20,
+                opcode::PUSH1,
+                0x00,
+                opcode::KECCAK256,
+                opcode::POP,
+                // keccak256 of 100 bytes
+                opcode::PUSH1,
+                0x64,
+                opcode::PUSH1,
+                0x00,
+                opcode::KECCAK256,
+                opcode::POP,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .with_external_context(KeccakProfileInspector::new())
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to =
+                    TransactTo::Call(address!("0000000000000000000000000000000000000000"));
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        evm.transact().unwrap();
+
+        assert_eq!(
+            evm.context.external.keccak_stats(),
+            KeccakStats {
+                count: 2,
+                bytes: 132
+            }
+        );
+    }
+}
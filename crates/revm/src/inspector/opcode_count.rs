@@ -0,0 +1,166 @@
+//! OpcodeCountInspector. Counts executed opcodes of a transaction.
+
+use crate::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    primitives::db::Database,
+    EvmContext, Inspector, StepAction,
+};
+
+/// [Inspector] that counts how many times every opcode was executed.
+///
+/// Only opcodes that were executed without an error are counted. Counts are kept in
+/// fixed size arrays, nothing is allocated during execution.
+///
+/// With the `opcode_gas` feature gas spent by every opcode is tracked too. Gas of calls
+/// and creates includes the gas forwarded to the new frame.
+///
+/// Counts are reset at the start of every transaction.
+#[derive(Clone, Debug)]
+pub struct OpcodeCountInspector {
+    /// Number of executions by opcode.
+    counts: [u64; 256],
+    /// Spent gas by opcode.
+    #[cfg(feature = "opcode_gas")]
+    gas: [u64; 256],
+    /// Opcode of the currently executing instruction.
+    pending: Option<u8>,
+    /// Gas spent by the currently executing instruction.
+    #[cfg(feature = "opcode_gas")]
+    pending_gas: u64,
+}
+
+impl Default for OpcodeCountInspector {
+    fn default() -> Self {
+        Self {
+            counts: [0; 256],
+            #[cfg(feature = "opcode_gas")]
+            gas: [0; 256],
+            pending: None,
+            #[cfg(feature = "opcode_gas")]
+            pending_gas: 0,
+        }
+    }
+}
+
+impl OpcodeCountInspector {
+    /// Creates new opcode count inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns number of executions of every opcode, indexed by opcode.
+    pub fn counts(&self) -> &[u64; 256] {
+        &self.counts
+    }
+
+    /// Returns total number of executed opcodes.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Returns gas spent by every opcode, indexed by opcode.
+    #[cfg(feature = "opcode_gas")]
+    pub fn gas(&self) -> &[u64; 256] {
+        &self.gas
+    }
+
+    /// Resets the counts at the start of the transaction.
+    fn reset_on_first_frame<DB: Database>(&mut self, context: &EvmContext<DB>) {
+        if context.journaled_state.depth() == 0 {
+            *self = Self::default();
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for OpcodeCountInspector {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) -> StepAction {
+        self.pending = Some(interp.current_opcode());
+        StepAction::Continue
+    }
+
+    #[cfg(feature = "opcode_gas")]
+    fn gas_change(&mut self, _interp: &Interpreter, spent: u64, _refunded_delta: i64) {
+        self.pending_gas = spent;
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        let Some(opcode) = self.pending.take() else {
+            return;
+        };
+        if interp.instruction_result.is_error() {
+            return;
+        }
+        self.counts[opcode as usize] += 1;
+        #[cfg(feature = "opcode_gas")]
+        {
+            self.gas[opcode as usize] += self.pending_gas;
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.reset_on_first_frame(context);
+        None
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.reset_on_first_frame(context);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::evm_calling;
+    use crate::{
+        db::BenchmarkDB,
+        inspector::inspector_handle_register,
+        interpreter::opcode,
+        primitives::{Address, Bytecode},
+    };
+
+    #[test]
+    fn test_opcode_count() {
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x02,
+                opcode::ADD,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(OpcodeCountInspector::new())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+
+        let inspector = &evm.context.external;
+        let counts = inspector.counts();
+        assert_eq!(counts[opcode::PUSH1 as usize], 2);
+        assert_eq!(counts[opcode::ADD as usize], 1);
+        assert_eq!(counts[opcode::STOP as usize], 1);
+        assert_eq!(inspector.total(), 4);
+
+        #[cfg(feature = "opcode_gas")]
+        {
+            let gas = inspector.gas();
+            assert_eq!(gas[opcode::PUSH1 as usize], 6);
+            assert_eq!(gas[opcode::ADD as usize], 3);
+            assert_eq!(gas[opcode::STOP as usize], 0);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! RefundTimelineInspector. Records every change of the gas refund counter in order.
+
+use crate::{
+    interpreter::{opcode, CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    primitives::{db::Database, Address, U256},
+    EvmContext, Inspector, StepAction,
+};
+use alloc::vec::Vec;
+
+/// Single change of the gas refund counter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RefundEvent {
+    /// Change of the refund counter, negative when the refund is reduced.
+    pub delta: i64,
+    /// Opcode that changed the refund counter.
+    pub opcode: u8,
+    /// Contract whose storage slot was written, or the self-destructed contract.
+    pub address: Address,
+    /// Written storage slot, `None` for SELFDESTRUCT.
+    pub slot: Option<U256>,
+}
+
+/// [Inspector] that records the timeline of gas refund changes in execution order.
+///
+/// Changes are recorded in the frame that executes the opcode. Refunds of frames that
+/// revert or halt are recorded too, even though they are not applied to the transaction.
+///
+/// Timeline is reset at the start of every transaction.
+#[derive(Clone, Debug, Default)]
+pub struct RefundTimelineInspector {
+    /// Opcode, address and slot of the currently executing instruction.
+    pending: Option<(u8, Address, Option<U256>)>,
+    /// Recorded refund changes.
+    timeline: Vec<RefundEvent>,
+}
+
+impl RefundTimelineInspector {
+    /// Creates new refund timeline inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns refund changes of the last transaction in execution order.
+    pub fn timeline(&self) -> &[RefundEvent] {
+        &self.timeline
+    }
+
+    /// Resets the timeline at the start of the transaction.
+    fn reset_on_first_frame<DB: Database>(&mut self, context: &EvmContext<DB>) {
+        if context.journaled_state.depth() == 0 {
+            self.timeline.clear();
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for RefundTimelineInspector {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) -> StepAction {
+        let opcode = interp.current_opcode();
+        let slot = if opcode == opcode::SSTORE {
+            interp.stack.peek(0).ok()
+        } else {
+            None
+        };
+        self.pending = Some((opcode, interp.contract.address, slot));
+        StepAction::Continue
+    }
+
+    fn gas_change(&mut self, _interp: &Interpreter, _spent: u64, refunded_delta: i64) {
+        let Some((opcode, address, slot)) = self.pending.take() else {
+            return;
+        };
+        if refunded_delta != 0 {
+            self.timeline.push(RefundEvent {
+                delta: refunded_delta,
+                opcode,
+                address,
+                slot,
+            });
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.reset_on_first_frame(context);
+        None
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.reset_on_first_frame(context);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{contract_info, evm_calling};
+    use crate::{
+        db::InMemoryDB,
+        inspector::inspector_handle_register,
+        primitives::{address, Bytecode},
+    };
+
+    #[test]
+    fn test_refund_timeline() {
+        let contract = address!("2000000000000000000000000000000000000000");
+        // clears slot 0 and sets it back to its original value.
+        let code = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x00,
+                opcode::SSTORE,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(contract, contract_info(code));
+        db.insert_account_storage(contract, U256::ZERO, U256::from(1))
+            .unwrap();
+
+        let mut evm = evm_calling(db, contract)
+            .with_external_context(RefundTimelineInspector::new())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+
+        // clearing refund is 4800, restoring the original value removes it and refunds
+        // 2800 of the reset cost.
+        let event = |delta| RefundEvent {
+            delta,
+            opcode: opcode::SSTORE,
+            address: contract,
+            slot: Some(U256::ZERO),
+        };
+        assert_eq!(
+            evm.context.external.timeline(),
+            &[event(4800), event(-4800 + 2800)]
+        );
+    }
+}
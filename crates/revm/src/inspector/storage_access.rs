@@ -0,0 +1,285 @@
+//! StorageAccessInspector. Groups storage reads and writes by account.
+
+use crate::{
+    interpreter::{
+        opcode, CallInputs, CallOutcome, CreateInputs, CreateOutcome, InstructionResult,
+        Interpreter, InterpreterResult,
+    },
+    primitives::{db::Database, Address, HashMap, HashSet, U256},
+    EvmContext, Inspector, StepAction,
+};
+use alloc::vec::Vec;
+
+/// Storage accesses of a single account.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountStorageAccess {
+    /// Slots read by SLOAD.
+    pub reads: HashSet<U256>,
+    /// Slots written by SSTORE with the value before the first write and the value
+    /// after the last write.
+    pub writes: HashMap<U256, (U256, U256)>,
+    /// Written slots whose last write was reverted.
+    pub reverted: HashSet<U256>,
+}
+
+/// Storage opcode that is currently executing.
+#[derive(Clone, Copy, Debug)]
+enum PendingAccess {
+    /// SLOAD of the slot.
+    Read { address: Address, key: U256 },
+    /// SSTORE of the slot. `before` is `None` if the slot was not loaded yet.
+    Write {
+        address: Address,
+        key: U256,
+        before: Option<U256>,
+    },
+}
+
+/// [Inspector] that records storage slots read and written by every account.
+///
+/// Accesses are reset at the start of every transaction.
+#[derive(Clone, Debug, Default)]
+pub struct StorageAccessInspector {
+    /// Access of the currently executing opcode, recorded when it succeeds.
+    pending: Option<PendingAccess>,
+    /// Slots written by every frame that is currently executing.
+    frame_writes: Vec<Vec<(Address, U256)>>,
+    /// Accesses of the last transaction.
+    accesses: HashMap<Address, AccountStorageAccess>,
+}
+
+impl StorageAccessInspector {
+    /// Creates new storage access inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns storage accesses of the last transaction grouped by account.
+    pub fn storage_accesses(&self) -> &HashMap<Address, AccountStorageAccess> {
+        &self.accesses
+    }
+
+    /// Starts a new frame.
+    fn start<DB: Database>(&mut self, context: &EvmContext<DB>) {
+        if context.journaled_state.depth() == 0 {
+            self.pending = None;
+            self.frame_writes.clear();
+            self.accesses.clear();
+        }
+        self.frame_writes.push(Vec::new());
+    }
+
+    /// Finishes the current frame, marking its writes as reverted if it failed.
+    fn end(&mut self, result: &InterpreterResult) {
+        let Some(writes) = self.frame_writes.pop() else {
+            return;
+        };
+        if !result.is_ok() {
+            for (address, key) in &writes {
+                if let Some(access) = self.accesses.get_mut(address) {
+                    access.reverted.insert(*key);
+                }
+            }
+        }
+        // writes are reverted again if the parent frame fails.
+        if let Some(parent) = self.frame_writes.last_mut() {
+            parent.extend(writes);
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for StorageAccessInspector {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) -> StepAction {
+        let address = interp.contract.address;
+        self.pending = match interp.current_opcode() {
+            opcode::SLOAD => interp
+                .stack
+                .peek(0)
+                .ok()
+                .map(|key| PendingAccess::Read { address, key }),
+            opcode::SSTORE => interp.stack.peek(0).ok().map(|key| {
+                let before = context
+                    .journaled_state
+                    .state
+                    .get(&address)
+                    .and_then(|account| account.storage.get(&key))
+                    .map(|slot| slot.present_value());
+                PendingAccess::Write {
+                    address,
+                    key,
+                    before,
+                }
+            }),
+            _ => None,
+        };
+        StepAction::Continue
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        // record only if opcode was executed successfully.
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+        if interp.instruction_result != InstructionResult::Continue {
+            return;
+        }
+        match pending {
+            PendingAccess::Read { address, key } => {
+                self.accesses.entry(address).or_default().reads.insert(key);
+            }
+            PendingAccess::Write {
+                address,
+                key,
+                before,
+            } => {
+                let Some(slot) = context
+                    .journaled_state
+                    .state
+                    .get(&address)
+                    .and_then(|account| account.storage.get(&key))
+                else {
+                    return;
+                };
+                // slot that was not loaded before SSTORE is loaded with its original value.
+                let before = before.unwrap_or(slot.original_value());
+                let after = slot.present_value();
+
+                let access = self.accesses.entry(address).or_default();
+                access
+                    .writes
+                    .entry(key)
+                    .and_modify(|(_, last)| *last = after)
+                    .or_insert((before, after));
+                access.reverted.remove(&key);
+                if let Some(frame) = self.frame_writes.last_mut() {
+                    frame.push((address, key));
+                }
+            }
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.start(context);
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.end(&outcome.result);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.start(context);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.end(&outcome.result);
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{contract_info, evm_calling};
+    use crate::{
+        db::InMemoryDB,
+        inspector::inspector_handle_register,
+        primitives::{address, Bytecode},
+    };
+
+    fn sstore(key: u8, value: u8) -> [u8; 5] {
+        [opcode::PUSH1, value, opcode::PUSH1, key, opcode::SSTORE]
+    }
+
+    #[test]
+    fn test_storage_access_inspector() {
+        let parent = address!("2000000000000000000000000000000000000000");
+        let reverter = address!("00000000000000000000000000000000000000a1");
+
+        // reads slots 1 and 2, writes 7 to slot 3 and calls the reverter.
+        let mut parent_code = vec![
+            opcode::PUSH1,
+            0x01,
+            opcode::SLOAD,
+            opcode::PUSH1,
+            0x02,
+            opcode::SLOAD,
+        ];
+        parent_code.extend(sstore(0x03, 0x07));
+        parent_code.extend([
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0xa1,
+            opcode::GAS,
+            opcode::CALL,
+            opcode::STOP,
+        ]);
+        // writes 9 to slot 1 and reverts.
+        let mut reverter_code = sstore(0x01, 0x09).to_vec();
+        reverter_code.extend([opcode::PUSH1, 0x00, opcode::PUSH1, 0x00, opcode::REVERT]);
+
+        let mut db = InMemoryDB::default();
+        for (address, code) in [(parent, parent_code), (reverter, reverter_code)] {
+            let code = Bytecode::new_raw(code.into());
+            db.insert_account_info(address, contract_info(code));
+        }
+        db.insert_account_storage(parent, U256::from(3), U256::from(5))
+            .unwrap();
+
+        let mut evm = evm_calling(db, parent)
+            .with_external_context(StorageAccessInspector::new())
+            .modify_tx_env(|tx| tx.gas_limit = 200_000)
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+
+        let accesses = evm.context.external.storage_accesses();
+        assert_eq!(accesses.len(), 2);
+        assert_eq!(
+            accesses[&parent],
+            AccountStorageAccess {
+                reads: HashSet::from([U256::from(1), U256::from(2)]),
+                writes: HashMap::from([(U256::from(3), (U256::from(5), U256::from(7)))]),
+                reverted: HashSet::default(),
+            }
+        );
+        assert_eq!(
+            accesses[&reverter],
+            AccountStorageAccess {
+                reads: HashSet::default(),
+                writes: HashMap::from([(U256::from(1), (U256::ZERO, U256::from(9)))]),
+                reverted: HashSet::from([U256::from(1)]),
+            }
+        );
+    }
+}
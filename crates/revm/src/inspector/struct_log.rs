@@ -0,0 +1,194 @@
+//! StructLogInspector. Records geth style `structLog` entries for every executed opcode.
+
+use crate::{
+    interpreter::{
+        opcode::OpCode, CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter,
+    },
+    primitives::{db::Database, Bytes, U256},
+    EvmContext, Inspector, StepAction,
+};
+use alloc::vec::Vec;
+
+/// Single step of the execution, as reported by geth `debug_traceTransaction`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StructLog {
+    /// Program counter of the opcode.
+    pub pc: u64,
+    /// Executed opcode.
+    pub op: u8,
+    /// Gas remaining before the opcode is executed.
+    pub gas: u64,
+    /// Gas charged by the opcode.
+    ///
+    /// It includes the memory expansion cost and, for calls and creates,
+    /// the gas forwarded to the new frame.
+    pub gas_cost: u64,
+    /// Depth of the frame. Transaction frame has depth 1.
+    pub depth: u64,
+    /// Stack before the opcode is executed, top of the stack is the last element.
+    pub stack: Vec<U256>,
+    /// Memory of the frame before the opcode is executed.
+    pub memory: Bytes,
+}
+
+impl StructLog {
+    /// Returns name of the opcode.
+    pub fn op_name(&self) -> &'static str {
+        OpCode::new(self.op).map_or("INVALID", OpCode::as_str)
+    }
+}
+
+/// [Inspector] that records a [StructLog] for every executed opcode.
+///
+/// Logs are reset at the start of every transaction.
+#[derive(Clone, Debug, Default)]
+pub struct StructLogInspector {
+    /// Log of the currently executing opcode, gas cost is set when it finishes.
+    pending: Option<StructLog>,
+    /// Logs of the last transaction.
+    logs: Vec<StructLog>,
+}
+
+impl StructLogInspector {
+    /// Creates new struct log inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns logs of the last transaction.
+    pub fn logs(&self) -> &[StructLog] {
+        &self.logs
+    }
+
+    /// Resets logs at the start of the transaction.
+    fn start<DB: Database>(&mut self, context: &EvmContext<DB>) {
+        if context.journaled_state.depth() == 0 {
+            self.pending = None;
+            self.logs.clear();
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for StructLogInspector {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) -> StepAction {
+        self.pending = Some(StructLog {
+            pc: interp.program_counter() as u64,
+            op: interp.current_opcode(),
+            gas: interp.gas.remaining(),
+            gas_cost: 0,
+            depth: context.journaled_state.depth(),
+            stack: interp.stack.data().clone(),
+            memory: Bytes::copy_from_slice(interp.shared_memory.context_memory()),
+        });
+        StepAction::Continue
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if let Some(mut log) = self.pending.take() {
+            log.gas_cost = log.gas.saturating_sub(interp.gas.remaining());
+            self.logs.push(log);
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.start(context);
+        None
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.start(context);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::evm_calling;
+    use crate::{
+        db::BenchmarkDB,
+        inspector::inspector_handle_register,
+        interpreter::opcode,
+        primitives::{Address, Bytecode},
+    };
+
+    #[test]
+    fn test_struct_logs() {
+        let bytecode = Bytecode::new_raw(
+            vec![
+                opcode::PUSH1,
+                0x2a,
+                opcode::PUSH1,
+                0x00,
+                opcode::MSTORE,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+
+        let mut evm = evm_calling(BenchmarkDB::new_bytecode(bytecode), Address::ZERO)
+            .with_external_context(StructLogInspector::new())
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+
+        let mut memory = [0u8; 32];
+        memory[31] = 0x2a;
+        let gas = 100_000 - 21_000;
+        let expected = [
+            (0, opcode::PUSH1, gas, 3, vec![], Bytes::new()),
+            (
+                2,
+                opcode::PUSH1,
+                gas - 3,
+                3,
+                vec![U256::from(0x2a)],
+                Bytes::new(),
+            ),
+            (
+                4,
+                opcode::MSTORE,
+                gas - 6,
+                // static cost and expansion of memory to one word.
+                6,
+                vec![U256::from(0x2a), U256::ZERO],
+                Bytes::new(),
+            ),
+            (
+                5,
+                opcode::STOP,
+                gas - 12,
+                0,
+                vec![],
+                Bytes::copy_from_slice(&memory),
+            ),
+        ];
+
+        let logs = evm.context.external.logs();
+        assert_eq!(logs.len(), expected.len());
+        for (log, (pc, op, gas, gas_cost, stack, memory)) in logs.iter().zip(expected) {
+            assert_eq!(
+                log,
+                &StructLog {
+                    pc,
+                    op,
+                    gas,
+                    gas_cost,
+                    depth: 1,
+                    stack,
+                    memory,
+                }
+            );
+        }
+        assert_eq!(logs[2].op_name(), "MSTORE");
+    }
+}
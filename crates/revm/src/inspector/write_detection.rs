@@ -0,0 +1,169 @@
+//! WriteDetectionInspector. Detects whether a transaction changes the state.
+
+use crate::{
+    interpreter::{opcode, CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    primitives::{db::Database, Address, U256},
+    EvmContext, Inspector, StepAction,
+};
+
+/// [Inspector] that detects the first state-mutating operation of a transaction.
+///
+/// Operations that write state are SSTORE, LOG0-LOG4, CREATE, CREATE2, SELFDESTRUCT and
+/// CALL or CALLCODE that transfer value. Transaction that creates a contract or transfers
+/// value is state-changing too and is reported as CREATE or CALL of the caller.
+///
+/// Execution is not aborted. Writes are detected even if they are reverted later, so the
+/// transaction is read-only only if it never tried to change the state.
+///
+/// Detection is reset at the start of every transaction.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WriteDetectionInspector {
+    /// Write of the currently executing opcode, recorded when it succeeds.
+    pending: Option<(u8, Address)>,
+    /// First write of the last transaction.
+    first_write: Option<(u8, Address)>,
+}
+
+impl WriteDetectionInspector {
+    /// Creates new write detection inspector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if the last transaction did not write to the state.
+    pub fn is_read_only(&self) -> bool {
+        self.first_write.is_none()
+    }
+
+    /// Returns the opcode and the address of the account that made the first write.
+    pub fn first_write(&self) -> Option<(u8, Address)> {
+        self.first_write
+    }
+
+    /// Resets the detection at the start of the transaction.
+    fn start<DB: Database>(&mut self, context: &EvmContext<DB>) {
+        if context.journaled_state.depth() == 0 {
+            *self = Self::default();
+        }
+    }
+
+    /// Records the write if it is the first one.
+    fn record(&mut self, opcode: u8, address: Address) {
+        self.first_write.get_or_insert((opcode, address));
+    }
+}
+
+impl<DB: Database> Inspector<DB> for WriteDetectionInspector {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) -> StepAction {
+        let op = interp.current_opcode();
+        let is_write = match op {
+            opcode::SSTORE
+            | opcode::LOG0..=opcode::LOG4
+            | opcode::CREATE
+            | opcode::CREATE2
+            | opcode::SELFDESTRUCT => true,
+            opcode::CALL | opcode::CALLCODE => {
+                matches!(interp.stack.peek(2), Ok(value) if value != U256::ZERO)
+            }
+            _ => false,
+        };
+        self.pending = is_write.then_some((op, interp.contract.address));
+        StepAction::Continue
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        // record only if opcode did not halt, e.g. on write in static call.
+        if let Some((op, address)) = self.pending.take() {
+            if !interp.instruction_result.is_error() {
+                self.record(op, address);
+            }
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.start(context);
+        if context.journaled_state.depth() == 0 && inputs.transfer.value != U256::ZERO {
+            self.record(opcode::CALL, inputs.transfer.source);
+        }
+        None
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.start(context);
+        if context.journaled_state.depth() == 0 {
+            self.record(opcode::CREATE, inputs.caller);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::evm_calling;
+    use crate::{db::BenchmarkDB, inspector::inspector_handle_register, primitives::Bytecode};
+
+    fn run(code: Vec<u8>) -> WriteDetectionInspector {
+        let mut evm = evm_calling(
+            BenchmarkDB::new_bytecode(Bytecode::new_raw(code.into())),
+            Address::ZERO,
+        )
+        .with_external_context(WriteDetectionInspector::new())
+        .append_handler_register(inspector_handle_register)
+        .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        evm.context.external
+    }
+
+    #[test]
+    fn test_write_detection_getter() {
+        // returns slot 0.
+        let inspector = run(vec![
+            opcode::PUSH1,
+            0x00,
+            opcode::SLOAD,
+            opcode::PUSH1,
+            0x00,
+            opcode::MSTORE,
+            opcode::PUSH1,
+            0x20,
+            opcode::PUSH1,
+            0x00,
+            opcode::RETURN,
+        ]);
+        assert!(inspector.is_read_only());
+        assert_eq!(inspector.first_write(), None);
+    }
+
+    #[test]
+    fn test_write_detection_setter() {
+        // stores 1 to slot 0 and logs.
+        let inspector = run(vec![
+            opcode::PUSH1,
+            0x01,
+            opcode::PUSH1,
+            0x00,
+            opcode::SSTORE,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::LOG0,
+            opcode::STOP,
+        ]);
+        assert!(!inspector.is_read_only());
+        assert_eq!(
+            inspector.first_write(),
+            Some((opcode::SSTORE, Address::ZERO))
+        );
+    }
+}
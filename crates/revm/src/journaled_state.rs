@@ -1,9 +1,10 @@
 use crate::interpreter::{InstructionResult, SelfDestructResult};
 use crate::primitives::{
-    db::Database, hash_map::Entry, Account, Address, Bytecode, HashMap, HashSet, Log, SpecId::*,
-    State, StorageSlot, TransientStorage, KECCAK_EMPTY, PRECOMPILE3, U256,
+    db::Database, hash_map::Entry, Account, AccountInfo, AccountOverride, AccountStatus, Address,
+    Bytecode, HashMap, HashSet, Log, SpecId::*, State, StorageSlot, TransientStorage, KECCAK_EMPTY,
+    PRECOMPILE3, U256,
 };
-use alloc::vec::Vec;
+use alloc::{boxed::Box, vec::Vec};
 use core::mem;
 use revm_interpreter::primitives::SpecId;
 
@@ -18,8 +19,16 @@ pub struct JournaledState {
     pub transient_storage: TransientStorage,
     /// logs
     pub logs: Vec<Log>,
+    /// Number of logs emitted in the current transaction.
+    ///
+    /// Unlike `logs` this counter is not decreased when logs are reverted.
+    pub log_count: usize,
     /// how deep are we in call stack.
     pub depth: usize,
+    /// Number of create frames that are currently executing.
+    pub create_depth: usize,
+    /// Number of bytes returned by RETURN and REVERT in the current transaction.
+    pub return_data_size: usize,
     /// journal with changes that happened between calls.
     pub journal: Vec<Vec<JournalEntry>>,
     /// Ethereum before EIP-161 differently defined empty and not-existing account
@@ -33,6 +42,23 @@ pub struct JournaledState {
     /// Note that this not include newly loaded accounts, account and storage
     /// is considered warm if it is found in the `State`.
     pub warm_preloaded_addresses: HashSet<Address>,
+    /// Loaded accounts that are considered cold again after
+    /// [JournaledState::restore_access_list]. Account is warmed on the next access.
+    pub cold_accounts: HashSet<Address>,
+    /// Loaded storage slots that are considered cold again after
+    /// [JournaledState::restore_access_list]. Slot is warmed on the next access.
+    pub cold_storage: HashSet<(Address, U256)>,
+}
+
+/// Snapshot of warm accounts and storage slots.
+///
+/// Created by [JournaledState::access_list_snapshot].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessListSnapshot {
+    /// Warm accounts.
+    pub accounts: HashSet<Address>,
+    /// Warm storage slots.
+    pub storage: HashSet<(Address, U256)>,
 }
 
 impl JournaledState {
@@ -52,10 +78,15 @@ impl JournaledState {
             state: HashMap::new(),
             transient_storage: TransientStorage::default(),
             logs: Vec::new(),
+            log_count: 0,
             journal: vec![vec![]],
             depth: 0,
+            create_depth: 0,
+            return_data_size: 0,
             spec,
             warm_preloaded_addresses,
+            cold_accounts: HashSet::new(),
+            cold_storage: HashSet::new(),
         }
     }
 
@@ -96,11 +127,115 @@ impl JournaledState {
         let state = mem::take(&mut self.state);
 
         let logs = mem::take(&mut self.logs);
+        self.log_count = 0;
         self.journal = vec![vec![]];
         self.depth = 0;
+        self.create_depth = 0;
+        self.return_data_size = 0;
+        self.cold_accounts.clear();
+        self.cold_storage.clear();
         (state, logs)
     }
 
+    /// Applies `account_override` to the loaded account at `address` and marks it touched.
+    ///
+    /// Overridden storage slots are warm. If the whole storage is replaced, the account
+    /// storage is marked as cleared and missing slots are zero. Previous account is journaled
+    /// and restored on revert.
+    pub fn override_account(&mut self, address: Address, account_override: &AccountOverride) {
+        let account = self.state.get_mut(&address).unwrap(); // assume acc is loaded
+        self.journal
+            .last_mut()
+            .unwrap()
+            .push(JournalEntry::AccountOverridden {
+                address,
+                info: Box::new(account.info.clone()),
+                status: account.status,
+                storage: account
+                    .storage
+                    .iter()
+                    .map(|(k, v)| (*k, v.clone()))
+                    .collect(),
+            });
+
+        if let Some(balance) = account_override.balance {
+            account.info.balance = balance;
+        }
+        if let Some(nonce) = account_override.nonce {
+            account.info.nonce = nonce;
+        }
+        if let Some(code) = &account_override.code {
+            account.info.code_hash = code.hash_slow();
+            account.info.code = Some(code.clone());
+        }
+        if let Some(state) = &account_override.state {
+            account.mark_storage_cleared();
+            for slot in account.storage.values_mut() {
+                *slot = StorageSlot::new(U256::ZERO);
+            }
+            for (key, value) in state {
+                account.storage.insert(*key, StorageSlot::new(*value));
+            }
+        }
+        if let Some(state_diff) = &account_override.state_diff {
+            for (key, value) in state_diff {
+                account.storage.insert(*key, StorageSlot::new(*value));
+            }
+        }
+        account.mark_touch();
+    }
+
+    /// Returns addresses of accounts created in the transaction that were not reverted,
+    /// in creation order.
+    pub fn created_accounts(&self) -> impl Iterator<Item = Address> + '_ {
+        self.journal
+            .iter()
+            .flatten()
+            .filter_map(|entry| match entry {
+                JournalEntry::AccountCreated { address } => Some(*address),
+                _ => None,
+            })
+    }
+
+    /// Returns accounts and storage slots that are currently warm.
+    ///
+    /// Warm preloaded addresses are not included as they are always warm.
+    pub fn access_list_snapshot(&self) -> AccessListSnapshot {
+        let mut snapshot = AccessListSnapshot::default();
+        for (address, account) in self.state.iter() {
+            if !self.cold_accounts.contains(address) {
+                snapshot.accounts.insert(*address);
+            }
+            for key in account.storage.keys() {
+                if !self.cold_storage.contains(&(*address, *key)) {
+                    snapshot.storage.insert((*address, *key));
+                }
+            }
+        }
+        snapshot
+    }
+
+    /// Restores warm accounts and storage slots to the given snapshot.
+    ///
+    /// Accounts and slots warmed after the snapshot are cold on their next access.
+    /// Loaded state, storage values and balances are not changed.
+    pub fn restore_access_list(&mut self, snapshot: AccessListSnapshot) {
+        self.cold_accounts.clear();
+        self.cold_storage.clear();
+        for (address, account) in self.state.iter() {
+            if !snapshot.accounts.contains(address)
+                && !self.warm_preloaded_addresses.contains(address)
+            {
+                self.cold_accounts.insert(*address);
+            }
+            for key in account.storage.keys() {
+                if !snapshot.storage.contains(&(*address, *key)) {
+                    self.cold_storage.insert((*address, *key));
+                }
+            }
+        }
+    }
+
     /// Returns the _loaded_ [Account] for the given address.
     ///
     /// This assumes that the account has already been loaded.
@@ -294,6 +429,8 @@ impl JournaledState {
     fn journal_revert(
         state: &mut State,
         transient_storage: &mut TransientStorage,
+        cold_accounts: &mut HashSet<Address>,
+        cold_storage: &mut HashSet<(Address, U256)>,
         journal_entries: Vec<JournalEntry>,
         is_spurious_dragon_enabled: bool,
     ) {
@@ -378,6 +515,23 @@ impl JournaledState {
                     acc.info.code_hash = KECCAK_EMPTY;
                     acc.info.code = None;
                 }
+                JournalEntry::AccountWarmed { address } => {
+                    cold_accounts.insert(address);
+                }
+                JournalEntry::AccountOverridden {
+                    address,
+                    info,
+                    status,
+                    storage,
+                } => {
+                    let account = state.get_mut(&address).unwrap();
+                    account.info = *info;
+                    account.status = status;
+                    account.storage = storage.into_iter().collect();
+                }
+                JournalEntry::StorageWarmed { address, key } => {
+                    cold_storage.insert((address, key));
+                }
             }
         }
     }
@@ -406,6 +560,8 @@ impl JournaledState {
         let is_spurious_dragon_enabled = SpecId::enabled(self.spec, SPURIOUS_DRAGON);
         let state = &mut self.state;
         let transient_storage = &mut self.transient_storage;
+        let cold_accounts = &mut self.cold_accounts;
+        let cold_storage = &mut self.cold_storage;
         self.depth -= 1;
         // iterate over last N journals sets and revert our global state
         let leng = self.journal.len();
@@ -417,6 +573,8 @@ impl JournaledState {
                 Self::journal_revert(
                     state,
                     transient_storage,
+                    cold_accounts,
+                    cold_storage,
                     mem::take(cs),
                     is_spurious_dragon_enabled,
                 )
@@ -533,8 +691,21 @@ impl JournaledState {
         db: &mut DB,
     ) -> Result<(&mut Account, bool), DB::Error> {
         Ok(match self.state.entry(address) {
-            Entry::Occupied(entry) => (entry.into_mut(), false),
+            Entry::Occupied(entry) => {
+                // account can be made cold again by restoring the access list.
+                let is_cold = self.cold_accounts.remove(&address);
+                if is_cold {
+                    self.journal
+                        .last_mut()
+                        .unwrap()
+                        .push(JournalEntry::AccountWarmed { address });
+                }
+                (entry.into_mut(), is_cold)
+            }
             Entry::Vacant(vac) => {
+                // account could be marked cold before its load was reverted.
+                self.cold_accounts.remove(&address);
+
                 let account = if let Some(account) = db.basic(address)? {
                     account.into()
                 } else {
@@ -610,11 +781,23 @@ impl JournaledState {
         db: &mut DB,
     ) -> Result<(U256, bool), DB::Error> {
         let account = self.state.get_mut(&address).unwrap(); // assume acc is warm
-                                                             // only if account is created in this tx we can assume that storage is empty.
-        let is_newly_created = account.is_created();
+                                                             // only if account is created or its storage cleared in this tx we can assume that storage is empty.
+        let is_newly_created = account.is_created() || account.is_storage_cleared();
         let load = match account.storage.entry(key) {
-            Entry::Occupied(occ) => (occ.get().present_value, false),
+            Entry::Occupied(occ) => {
+                // slot can be made cold again by restoring the access list.
+                let is_cold = self.cold_storage.remove(&(address, key));
+                if is_cold {
+                    self.journal
+                        .last_mut()
+                        .unwrap()
+                        .push(JournalEntry::StorageWarmed { address, key });
+                }
+                (occ.get().present_value, is_cold)
+            }
             Entry::Vacant(vac) => {
+                // slot could be marked cold before its load was reverted.
+                self.cold_storage.remove(&(address, key));
                 // if storage was cleared, we don't need to ping db.
                 let value = if is_newly_created {
                     U256::ZERO
@@ -734,6 +917,7 @@ impl JournaledState {
     /// push log into subroutine
     #[inline]
     pub fn log(&mut self, log: Log) {
+        self.log_count += 1;
         self.logs.push(log);
     }
 }
@@ -799,6 +983,23 @@ pub enum JournalEntry {
     /// Action: Account code changed
     /// Revert: Revert to previous bytecode.
     CodeChange { address: Address },
+    /// Loaded account that was cold after access list restore is accessed.
+    /// Action: Mark account warm
+    /// Revert: Mark account cold
+    AccountWarmed { address: Address },
+    /// Account state is overridden, see [AccountOverride].
+    /// Action: Override account info and storage
+    /// Revert: Restore the previous account.
+    AccountOverridden {
+        address: Address,
+        info: Box<AccountInfo>,
+        status: AccountStatus,
+        storage: Vec<(U256, StorageSlot)>,
+    },
+    /// Loaded storage slot that was cold after access list restore is accessed.
+    /// Action: Mark storage slot warm
+    /// Revert: Mark storage slot cold
+    StorageWarmed { address: Address, key: U256 },
 }
 
 /// SubRoutine checkpoint that will help us to go back from this
@@ -2,8 +2,9 @@
 
 use crate::{
     handler::{
-        mainnet::{self, deduct_caller_inner},
+        mainnet::{self, deduct_caller_with_fees},
         register::EvmHandler,
+        CallerCheckHandle,
     },
     interpreter::{return_ok, return_revert, Gas, InstructionResult},
     optimism,
@@ -13,7 +14,7 @@ use crate::{
     },
     Context, FrameResult,
 };
-use alloc::sync::Arc;
+use alloc::{sync::Arc, vec::Vec};
 use core::ops::Mul;
 
 pub fn optimism_handle_register<DB: Database, EXT>(handler: &mut EvmHandler<'_, EXT, DB>) {
@@ -50,18 +51,19 @@ pub fn validate_env<SPEC: Spec, DB: Database>(env: &Env) -> Result<(), EVMError<
         return Err(InvalidTransaction::DepositSystemTxPostRegolith.into());
     }
 
-    env.validate_tx::<SPEC>()?;
+    env.validate_tx_with_blob_hash_check::<SPEC>(|_| true)?;
     Ok(())
 }
 
 /// Don not perform any extra validation for deposit transactions, they are pre-verified on L1.
 pub fn validate_tx_against_state<SPEC: Spec, EXT, DB: Database>(
     context: &mut Context<EXT, DB>,
+    caller_check: &CallerCheckHandle<'_, EXT, DB>,
 ) -> Result<(), EVMError<DB::Error>> {
     if context.evm.env.tx.optimism.source_hash.is_some() {
         return Ok(());
     }
-    mainnet::validate_tx_against_state::<SPEC, EXT, DB>(context)
+    mainnet::validate_tx_against_state::<SPEC, EXT, DB>(context, caller_check)
 }
 
 /// Handle output of the transaction
@@ -176,7 +178,7 @@ pub fn deduct_caller<SPEC: Spec, EXT, DB: Database>(
 
     // We deduct caller max balance after minting and before deducing the
     // l1 cost, max values is already checked in pre_validate but l1 cost wasn't.
-    deduct_caller_inner::<SPEC>(caller_account, &context.evm.env);
+    deduct_caller_with_fees(caller_account, &context.evm.env, &context.evm.tx_fees);
 
     // If the transaction is not a deposit transaction, subtract the L1 data fee from the
     // caller's balance directly after minting the requested amount of ETH.
@@ -355,6 +357,7 @@ pub fn end<SPEC: Spec, EXT, DB: Database>(
                     gas_used,
                 },
                 state,
+                created_contracts: Vec::new(),
             })
         } else {
             Err(err)
@@ -0,0 +1,243 @@
+//! Typed diff of the state changed by a transaction.
+
+use crate::{
+    db::DatabaseRef,
+    primitives::{AccountInfo, Address, Bytes, State, B256, KECCAK_EMPTY, U256},
+};
+use alloc::collections::BTreeMap;
+
+/// Changes of a single account, values are pairs of the value before and after the
+/// transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountDiff {
+    /// Balance change, `None` if unchanged.
+    pub balance: Option<(U256, U256)>,
+    /// Nonce change, `None` if unchanged.
+    pub nonce: Option<(u64, u64)>,
+    /// Code change, `None` if unchanged.
+    pub code: Option<(Bytes, Bytes)>,
+    /// Changed storage slots.
+    pub storage: BTreeMap<U256, (U256, U256)>,
+    /// Whether the account was self-destructed.
+    ///
+    /// Values after the transaction are values of a non-existing account. Only storage slots
+    /// that were loaded by the transaction are reported as cleared.
+    pub selfdestructed: bool,
+}
+
+impl AccountDiff {
+    /// Returns `true` if the account was not changed.
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_none()
+            && self.nonce.is_none()
+            && self.code.is_none()
+            && self.storage.is_empty()
+            && !self.selfdestructed
+    }
+}
+
+/// Diff of the state changed by a transaction, ordered by account address.
+pub type StateDiff = BTreeMap<Address, AccountDiff>;
+
+/// Computes the diff of the `state` returned by a transaction against the database
+/// that the transaction was executed on.
+///
+/// Must be called before the state is committed to the database. Accounts that were only
+/// loaded or were touched without any change are not included.
+pub fn state_diff<DB: DatabaseRef>(db: &DB, state: &State) -> Result<StateDiff, DB::Error> {
+    let mut diff = StateDiff::new();
+    for (address, account) in state {
+        if !account.is_touched() {
+            continue;
+        }
+        let selfdestructed = account.is_selfdestructed();
+        let before = db.basic_ref(*address)?.unwrap_or_default();
+        let after = if selfdestructed {
+            AccountInfo::default()
+        } else {
+            account.info.clone()
+        };
+
+        let mut account_diff = AccountDiff {
+            selfdestructed,
+            ..Default::default()
+        };
+        if before.balance != after.balance {
+            account_diff.balance = Some((before.balance, after.balance));
+        }
+        if before.nonce != after.nonce {
+            account_diff.nonce = Some((before.nonce, after.nonce));
+        }
+        if code_hash(&before) != code_hash(&after) {
+            account_diff.code = Some((code(db, &before)?, code(db, &after)?));
+        }
+        for (index, slot) in &account.storage {
+            let value = if selfdestructed {
+                U256::ZERO
+            } else {
+                slot.present_value()
+            };
+            if slot.original_value() != value {
+                account_diff
+                    .storage
+                    .insert(*index, (slot.original_value(), value));
+            }
+        }
+
+        if !account_diff.is_empty() {
+            diff.insert(*address, account_diff);
+        }
+    }
+    Ok(diff)
+}
+
+/// Returns code hash of the account, accounts without code have [KECCAK_EMPTY] hash.
+fn code_hash(info: &AccountInfo) -> B256 {
+    if info.code_hash == B256::ZERO {
+        KECCAK_EMPTY
+    } else {
+        info.code_hash
+    }
+}
+
+/// Returns code of the account, loading it from the database if it is not set.
+fn code<DB: DatabaseRef>(db: &DB, info: &AccountInfo) -> Result<Bytes, DB::Error> {
+    if code_hash(info) == KECCAK_EMPTY {
+        return Ok(Bytes::new());
+    }
+    Ok(match &info.code {
+        Some(code) => code.original_bytes(),
+        None => db.code_by_hash_ref(info.code_hash)?.original_bytes(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::contract_info;
+    use crate::{
+        db::InMemoryDB,
+        interpreter::opcode,
+        primitives::{address, Bytecode, SpecId, TransactTo},
+        Evm,
+    };
+
+    #[test]
+    fn test_state_diff() {
+        let caller = address!("1000000000000000000000000000000000000000");
+        let contract = address!("2000000000000000000000000000000000000000");
+        let destructed = address!("00000000000000000000000000000000000000b0");
+        let beneficiary = address!("00000000000000000000000000000000000000be");
+        let read_only = address!("00000000000000000000000000000000000000a1");
+
+        // writes 7 to slot 1, reads slot 2 and balance of `read_only`,
+        // then calls `destructed`.
+        let contract_code = vec![
+            opcode::PUSH1,
+            0x07,
+            opcode::PUSH1,
+            0x01,
+            opcode::SSTORE,
+            opcode::PUSH1,
+            0x02,
+            opcode::SLOAD,
+            opcode::POP,
+            opcode::PUSH1,
+            0xa1,
+            opcode::BALANCE,
+            opcode::POP,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0x00,
+            opcode::PUSH1,
+            0xb0,
+            opcode::GAS,
+            opcode::CALL,
+            opcode::STOP,
+        ];
+        // sends its balance to `beneficiary`.
+        let destructed_code = vec![opcode::PUSH1, 0xbe, opcode::SELFDESTRUCT];
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(caller, AccountInfo::from_balance(U256::from(100)));
+        db.insert_account_info(read_only, AccountInfo::from_balance(U256::from(1)));
+        for (address, code, balance) in [
+            (contract, contract_code, U256::ZERO),
+            (destructed, destructed_code.clone(), U256::from(3)),
+        ] {
+            let code = Bytecode::new_raw(code.into());
+            db.insert_account_info(
+                address,
+                AccountInfo {
+                    balance,
+                    ..contract_info(code)
+                },
+            );
+        }
+        db.insert_account_storage(contract, U256::from(1), U256::from(5))
+            .unwrap();
+        db.insert_account_storage(contract, U256::from(2), U256::from(6))
+            .unwrap();
+
+        // selfdestruct removes existing accounts before Cancun.
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .with_spec_id(SpecId::SHANGHAI)
+            .modify_tx_env(|tx| {
+                tx.clear();
+                tx.caller = caller;
+                tx.transact_to = TransactTo::Call(contract);
+                tx.value = U256::from(10);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+
+        let result = evm.transact().unwrap();
+        assert!(result.result.is_success());
+        let diff = state_diff(&evm.context.evm.db, &result.state).unwrap();
+
+        assert_eq!(
+            diff.keys().copied().collect::<Vec<_>>(),
+            vec![destructed, beneficiary, caller, contract]
+        );
+        assert_eq!(
+            diff[&caller],
+            AccountDiff {
+                balance: Some((U256::from(100), U256::from(90))),
+                nonce: Some((0, 1)),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff[&contract],
+            AccountDiff {
+                balance: Some((U256::ZERO, U256::from(10))),
+                storage: BTreeMap::from([(U256::from(1), (U256::from(5), U256::from(7)))]),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff[&destructed],
+            AccountDiff {
+                balance: Some((U256::from(3), U256::ZERO)),
+                code: Some((destructed_code.into(), Bytes::new())),
+                selfdestructed: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff[&beneficiary],
+            AccountDiff {
+                balance: Some((U256::ZERO, U256::from(3))),
+                ..Default::default()
+            }
+        );
+    }
+}
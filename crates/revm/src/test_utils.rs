@@ -1,2 +1,42 @@
 #[doc(hidden)]
 pub use crate::context::test_utils::*;
+
+use crate::{
+    builder::SetGenericStage,
+    db::InMemoryDB,
+    primitives::{address, AccountInfo, Address, Bytecode, TransactTo},
+    Database, Evm, EvmBuilder,
+};
+
+/// Caller of the transaction set by [`evm_calling`].
+pub const TEST_CALLER: Address = address!("1000000000000000000000000000000000000000");
+
+/// Returns an [`Evm`] builder with the `db` and a transaction from [`TEST_CALLER`] calling `to`
+/// with a gas limit of 100_000.
+pub fn evm_calling<'a, DB: Database>(
+    db: DB,
+    to: Address,
+) -> EvmBuilder<'a, SetGenericStage, (), DB> {
+    Evm::builder().with_db(db).modify_tx_env(|tx| {
+        tx.clear();
+        tx.caller = TEST_CALLER;
+        tx.transact_to = TransactTo::Call(to);
+        tx.gas_limit = 100_000;
+    })
+}
+
+/// Returns account info of a contract with the given `code`.
+pub fn contract_info(code: Bytecode) -> AccountInfo {
+    AccountInfo {
+        code_hash: code.hash_slow(),
+        code: Some(code),
+        ..Default::default()
+    }
+}
+
+/// Returns a database with a contract with the given `code` at `address`.
+pub fn db_with_contract(address: Address, code: Bytecode) -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(address, contract_info(code));
+    db
+}